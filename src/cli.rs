@@ -1,4 +1,27 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+/// Output format for the rendered statusline
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// ANSI-colored statusline
+    #[default]
+    Text,
+    /// Collected segment data as a JSON array
+    Json,
+}
+
+/// How --print and --dump-effective-config show the configuration
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrintFormat {
+    /// Sample statusline rendered with the configuration
+    Rendered,
+    /// Configuration as TOML
+    #[default]
+    Toml,
+    /// Configuration as JSON
+    Json,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "ccline")]
@@ -8,14 +31,62 @@ pub struct Cli {
     #[arg(short = 'c', long = "config")]
     pub config: bool,
 
+    /// Use this config file instead of ~/.claude/ccline/config.toml
+    #[arg(long = "config-path", value_name = "PATH")]
+    pub config_path: Option<PathBuf>,
+
     /// Set theme
     #[arg(short = 't', long = "theme")]
     pub theme: Option<String>,
 
+    /// List built-in and custom themes, marking the active one
+    #[arg(long = "list-themes")]
+    pub list_themes: bool,
+
+    /// Save the current configuration as a theme in ~/.claude/ccline/themes
+    #[arg(long = "save-theme", value_name = "NAME")]
+    pub save_theme: Option<String>,
+
+    /// Allow --save-theme to overwrite a built-in theme of the same name
+    #[arg(long = "force", requires = "save_theme")]
+    pub force: bool,
+
+    /// Output format for the statusline
+    #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Disable ANSI colors (also enabled by a non-empty NO_COLOR env var)
+    #[arg(long = "no-color")]
+    pub no_color: bool,
+
+    /// Read Claude Code input JSON from a file instead of stdin
+    #[arg(long = "input", value_name = "PATH")]
+    pub input: Option<String>,
+
+    /// Render only the given segment (e.g. `git`)
+    #[arg(long = "segment")]
+    pub segment: Option<String>,
+
+    /// Log debug details (cache hits, network and parse failures) to stderr; RUST_LOG filters further
+    #[arg(short = 'v', long = "verbose")]
+    pub verbose: bool,
+
+    /// Print how long each segment took to collect to stderr
+    #[arg(long = "benchmark")]
+    pub benchmark: bool,
+
+    /// Print why each configured segment rendered or not to stderr instead of the statusline
+    #[arg(long = "explain")]
+    pub explain: bool,
+
     /// Print current configuration
     #[arg(long = "print")]
     pub print: bool,
 
+    /// Format used by --print and --dump-effective-config
+    #[arg(long = "print-format", value_enum, default_value_t = PrintFormat::Toml)]
+    pub print_format: PrintFormat,
+
     /// Initialize config file
     #[arg(long = "init")]
     pub init: bool,
@@ -24,17 +95,54 @@ pub struct Cli {
     #[arg(long = "check")]
     pub check: bool,
 
+    /// Validate a theme TOML file without installing it
+    #[arg(long = "check-theme", value_name = "PATH")]
+    pub check_theme: Option<String>,
+
+    /// Print the fully resolved configuration used for rendering
+    #[arg(long = "dump-effective-config")]
+    pub dump_effective_config: bool,
+
+    /// Mask secret option values (keys, tokens) in the dumped configuration
+    #[arg(long = "redact", requires = "dump_effective_config")]
+    pub redact: bool,
+
+    /// Print the quota API's auth files and parsed quotas as JSON, bypassing the cache
+    #[arg(long = "dump-quota", hide = true)]
+    pub dump_quota: bool,
+
     /// Check for updates
     #[arg(short = 'u', long = "update")]
     pub update: bool,
 
+    /// Only report what --update would change
+    #[arg(long = "dry-run", requires = "update")]
+    pub dry_run: bool,
+
     /// Patch Claude Code cli.js to disable context warnings
     #[arg(long = "patch")]
     pub patch: Option<String>,
+
+    /// Show what --patch would change in cli.js without writing anything
+    #[arg(long = "patch-dry-run", value_name = "PATH", conflicts_with = "patch")]
+    pub patch_dry_run: Option<String>,
+
+    /// Restore cli.js from its most recent patcher backup
+    #[arg(long = "restore", value_name = "PATH")]
+    pub restore: Option<String>,
+
+    /// Backup to restore instead of the most recent (file path or timestamp)
+    #[arg(long = "backup", requires = "restore")]
+    pub backup: Option<String>,
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Whether colors are disabled by flag or by the NO_COLOR convention
+    pub fn colors_disabled(&self) -> bool {
+        self.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+    }
 }
@@ -0,0 +1,255 @@
+//! Environment variable overrides applied on top of the loaded configuration
+//!
+//! Precedence is env > config file > built-in defaults. Names are upper-cased
+//! and prefixed with `CCLINE_`:
+//!
+//! - `CCLINE_STYLE_MODE` (`plain`, `nerd_font`, `powerline`, `auto`)
+//! - `CCLINE_STYLE_SEPARATOR`
+//! - `CCLINE_STYLE_POWERLINE` (`true`/`false`)
+//! - `CCLINE_STYLE_MAX_WIDTH` (columns, `0` removes the limit)
+//! - `CCLINE_STYLE_COLOR_DEPTH` (`auto`, `truecolor`, `256`, `16`)
+//! - `CCLINE_SEGMENT_<ID>_ENABLED`, e.g. `CCLINE_SEGMENT_GIT_ENABLED=false`
+//! - `CCLINE_SEGMENT_<ID>_OPTION_<KEY>`, e.g. `CCLINE_SEGMENT_DIRECTORY_OPTION_MAX_COMPONENTS=3`
+//!
+//! `<ID>` is the segment id as written in config.toml (`context_window` becomes
+//! `CONTEXT_WINDOW`). Option values are parsed as JSON when possible (numbers,
+//! booleans, arrays) and used as plain strings otherwise.
+
+use super::{ColorDepth, Config, SegmentId, StyleMode};
+
+const PREFIX: &str = "CCLINE_";
+
+impl Config {
+    /// Apply `CCLINE_*` overrides from the process environment
+    pub fn apply_env_overrides(&mut self) {
+        self.apply_overrides(std::env::vars());
+    }
+
+    /// Apply overrides from arbitrary `(name, value)` pairs, ignoring unknown names
+    pub fn apply_overrides<I>(&mut self, vars: I)
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, value) in vars {
+            if let Some(key) = name.strip_prefix(PREFIX) {
+                self.apply_override(key, &value);
+            }
+        }
+    }
+
+    fn apply_override(&mut self, key: &str, value: &str) {
+        match key {
+            "STYLE_MODE" => {
+                if let Ok(mode) =
+                    serde_json::from_value::<StyleMode>(serde_json::Value::String(value.into()))
+                {
+                    self.style.mode = mode;
+                }
+            }
+            "STYLE_SEPARATOR" => self.style.separator = value.into(),
+            "STYLE_POWERLINE" => {
+                if let Some(powerline) = parse_bool(value) {
+                    self.style.powerline = powerline;
+                }
+            }
+            "STYLE_MAX_WIDTH" => {
+                if let Ok(max_width) = value.trim().parse::<usize>() {
+                    self.style.max_width = (max_width > 0).then_some(max_width);
+                }
+            }
+            "STYLE_COLOR_DEPTH" => {
+                if let Ok(depth) = serde_json::from_value::<ColorDepth>(serde_json::Value::String(
+                    value.trim().to_lowercase(),
+                )) {
+                    self.style.color_depth = depth;
+                }
+            }
+            _ => {
+                if let Some(rest) = key.strip_prefix("SEGMENT_") {
+                    self.apply_segment_override(rest, value);
+                }
+            }
+        }
+    }
+
+    fn apply_segment_override(&mut self, key: &str, value: &str) {
+        // Prefer the longest id so MODEL_CONTEXT_* isn't taken for MODEL + CONTEXT_*
+        let Some((id, field)) = SegmentId::all()
+            .iter()
+            .filter_map(|id| {
+                let field = key
+                    .strip_prefix(&id.name().to_uppercase())?
+                    .strip_prefix('_')?;
+                Some((*id, field))
+            })
+            .max_by_key(|(id, _)| id.name().len())
+        else {
+            return;
+        };
+
+        let Some(segment) = self.segments.iter_mut().find(|s| s.id == id) else {
+            return;
+        };
+
+        if field == "ENABLED" {
+            if let Some(enabled) = parse_bool(value) {
+                segment.enabled = enabled;
+            }
+        } else if let Some(option) = field.strip_prefix("OPTION_") {
+            // Reuse the existing key's spelling, new keys default to snake_case
+            let option_key = segment
+                .options
+                .keys()
+                .find(|k| k.eq_ignore_ascii_case(option))
+                .cloned()
+                .unwrap_or_else(|| option.to_lowercase());
+            let parsed = serde_json::from_str(value)
+                .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+            segment.options.insert(option_key, parsed);
+        }
+    }
+}
+
+pub(super) fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overridden(vars: &[(&str, &str)]) -> Config {
+        let mut config = Config::default();
+        config.apply_overrides(
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        );
+        config
+    }
+
+    fn segment(config: &Config, id: SegmentId) -> &crate::config::SegmentConfig {
+        config.segments.iter().find(|s| s.id == id).unwrap()
+    }
+
+    #[test]
+    fn style_fields_are_overridden() {
+        let config = overridden(&[
+            ("CCLINE_STYLE_MODE", "plain"),
+            ("CCLINE_STYLE_SEPARATOR", " / "),
+            ("CCLINE_STYLE_POWERLINE", "yes"),
+            ("CCLINE_STYLE_MAX_WIDTH", "100"),
+            ("CCLINE_STYLE_COLOR_DEPTH", "TrueColor"),
+        ]);
+
+        assert_eq!(config.style.mode, StyleMode::Plain);
+        assert_eq!(config.style.separator(), " / ");
+        assert!(config.style.powerline);
+        assert_eq!(config.style.max_width, Some(100));
+        assert_eq!(config.style.color_depth, ColorDepth::Truecolor);
+
+        let config = overridden(&[
+            ("CCLINE_STYLE_MAX_WIDTH", "100"),
+            ("CCLINE_STYLE_MAX_WIDTH", "0"),
+            ("CCLINE_STYLE_COLOR_DEPTH", "16"),
+        ]);
+        assert_eq!(config.style.max_width, None);
+        assert_eq!(config.style.color_depth, ColorDepth::Color16);
+    }
+
+    #[test]
+    fn invalid_values_keep_the_configured_ones() {
+        let defaults = Config::default();
+        let config = overridden(&[
+            ("CCLINE_STYLE_MODE", "fancy"),
+            ("CCLINE_STYLE_POWERLINE", "maybe"),
+            ("CCLINE_STYLE_MAX_WIDTH", "wide"),
+            ("CCLINE_STYLE_COLOR_DEPTH", "8"),
+            ("CCLINE_SEGMENT_GIT_ENABLED", "sometimes"),
+        ]);
+
+        assert_eq!(config.style.mode, defaults.style.mode);
+        assert_eq!(config.style.powerline, defaults.style.powerline);
+        assert_eq!(config.style.max_width, defaults.style.max_width);
+        assert_eq!(config.style.color_depth, defaults.style.color_depth);
+        assert_eq!(
+            segment(&config, SegmentId::Git).enabled,
+            segment(&defaults, SegmentId::Git).enabled
+        );
+    }
+
+    #[test]
+    fn segment_enabled_and_options_are_overridden() {
+        let config = overridden(&[
+            ("CCLINE_SEGMENT_GIT_ENABLED", "false"),
+            ("CCLINE_SEGMENT_COMMAND_ENABLED", "1"),
+            ("CCLINE_SEGMENT_COMMAND_OPTION_COMMAND", "echo hi"),
+            ("CCLINE_SEGMENT_COMMAND_OPTION_CACHE_DURATION", "30"),
+            ("CCLINE_SEGMENT_CONTEXT_WINDOW_OPTION_SHOW_TOKENS", "false"),
+            (
+                "CCLINE_SEGMENT_CONTEXT_WINDOW_OPTION_THRESHOLDS",
+                "[50, 80]",
+            ),
+        ]);
+
+        assert!(!segment(&config, SegmentId::Git).enabled);
+
+        let command = segment(&config, SegmentId::Command);
+        assert!(command.enabled);
+        assert_eq!(command.options["command"], "echo hi");
+        assert_eq!(command.options["cache_duration"], 30);
+
+        // The multi-word id isn't mistaken for a shorter one
+        let context = segment(&config, SegmentId::ContextWindow);
+        assert_eq!(context.options["show_tokens"], false);
+        assert_eq!(context.options["thresholds"], serde_json::json!([50, 80]));
+    }
+
+    #[test]
+    fn option_keys_keep_their_existing_spelling() {
+        let mut config = Config::default();
+        let git = config
+            .segments
+            .iter_mut()
+            .find(|s| s.id == SegmentId::Git)
+            .unwrap();
+        git.options
+            .insert("showSha".to_string(), serde_json::Value::Bool(false));
+
+        config.apply_overrides([(
+            "CCLINE_SEGMENT_GIT_OPTION_SHOWSHA".to_string(),
+            "true".to_string(),
+        )]);
+        let git = segment(&config, SegmentId::Git);
+        assert_eq!(git.options["showSha"], true);
+        assert!(!git.options.contains_key("showsha"));
+    }
+
+    #[test]
+    fn unrelated_names_are_ignored() {
+        let defaults = serde_json::to_value(Config::default()).unwrap();
+        let config = overridden(&[
+            ("STYLE_SEPARATOR", "x"),
+            ("CCLINE_STYLE_UNKNOWN", "x"),
+            ("CCLINE_SEGMENT_NOPE_ENABLED", "false"),
+            ("CCLINE_SEGMENT_GIT_COLOR", "red"),
+        ]);
+        assert_eq!(serde_json::to_value(config).unwrap(), defaults);
+    }
+
+    #[test]
+    fn process_environment_is_applied() {
+        // A name no other test reads, so setting it can't race with them
+        let name = "CCLINE_SEGMENT_DIRECTORY_OPTION_ENV_OVERRIDE_TEST";
+        std::env::set_var(name, "42");
+        let mut config = Config::default();
+        config.apply_env_overrides();
+        std::env::remove_var(name);
+
+        let directory = segment(&config, SegmentId::Directory);
+        assert_eq!(directory.options["env_override_test"], 42);
+    }
+}
@@ -1,6 +1,12 @@
-use super::types::Config;
+use super::paths::{config_dir, NoConfigDir};
+use super::types::{Config, SegmentId, StyleMode};
+use crate::core::segments::ClockSegment;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Config file chosen with `--config-path`, replacing the ~/.claude/ccline lookup
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
 /// Result of config initialization
 #[derive(Debug)]
@@ -11,6 +17,61 @@ pub enum InitResult {
     AlreadyExists(PathBuf),
 }
 
+/// Serialization format of a config file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// File names looked up in the config directory, in order of preference
+    const CANDIDATES: [&'static str; 4] =
+        ["config.toml", "config.json", "config.yaml", "config.yml"];
+
+    /// Format for a path's extension; unknown extensions are read as TOML
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path
+            .as_ref()
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("json") => Self::Json,
+            Some("yaml") | Some("yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    /// Reported for YAML files when the `yaml` feature is disabled
+    #[cfg(not(feature = "yaml"))]
+    const NO_YAML: &'static str =
+        "YAML configs need ccline built with the `yaml` feature, use config.toml or config.json";
+
+    pub fn parse(&self, content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        Ok(match self {
+            Self::Toml => toml::from_str(content)?,
+            Self::Json => serde_json::from_str(content)?,
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::from_str(content)?,
+            #[cfg(not(feature = "yaml"))]
+            Self::Yaml => return Err(Self::NO_YAML.into()),
+        })
+    }
+
+    pub fn serialize(&self, config: &Config) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(match self {
+            Self::Toml => toml::to_string_pretty(config)?,
+            Self::Json => serde_json::to_string_pretty(config)? + "\n",
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::to_string(config)?,
+            #[cfg(not(feature = "yaml"))]
+            Self::Yaml => return Err(Self::NO_YAML.into()),
+        })
+    }
+}
+
 pub struct ConfigLoader;
 
 impl ConfigLoader {
@@ -19,14 +80,13 @@ impl ConfigLoader {
     }
 
     pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        let content = fs::read_to_string(&path)?;
+        ConfigFormat::from_path(&path).parse(&content)
     }
 
     /// Initialize themes directory and create built-in theme files
     pub fn init_themes() -> Result<(), Box<dyn std::error::Error>> {
-        let themes_dir = Self::get_themes_path();
+        let themes_dir = Self::get_themes_path()?;
 
         // Create themes directory
         fs::create_dir_all(&themes_dir)?;
@@ -50,7 +110,7 @@ impl ConfigLoader {
             if !theme_path.exists() {
                 let theme_config = crate::ui::themes::ThemePresets::get_theme(theme_name);
                 let content = toml::to_string_pretty(&theme_config)?;
-                fs::write(&theme_path, content)?;
+                crate::utils::atomic_write(&theme_path, content)?;
                 println!("Created theme file: {}", theme_path.display());
                 created_any = true;
             }
@@ -64,12 +124,8 @@ impl ConfigLoader {
     }
 
     /// Get the themes directory path (~/.claude/ccline/themes/)
-    pub fn get_themes_path() -> PathBuf {
-        if let Some(home) = dirs::home_dir() {
-            home.join(".claude").join("ccline").join("themes")
-        } else {
-            PathBuf::from(".claude/ccline/themes")
-        }
+    pub fn get_themes_path() -> Result<PathBuf, NoConfigDir> {
+        Ok(config_dir()?.join("themes"))
     }
 
     /// Ensure themes directory exists and has built-in themes (silent mode)
@@ -80,7 +136,7 @@ impl ConfigLoader {
 
     /// Initialize themes directory and create built-in theme files (silent mode)
     fn init_themes_silent() -> Result<(), Box<dyn std::error::Error>> {
-        let themes_dir = Self::get_themes_path();
+        let themes_dir = Self::get_themes_path()?;
 
         // Create themes directory
         fs::create_dir_all(&themes_dir)?;
@@ -103,7 +159,7 @@ impl ConfigLoader {
             if !theme_path.exists() {
                 let theme_config = crate::ui::themes::ThemePresets::get_theme(theme_name);
                 let content = toml::to_string_pretty(&theme_config)?;
-                fs::write(&theme_path, content)?;
+                crate::utils::atomic_write(&theme_path, content)?;
             }
         }
 
@@ -117,43 +173,63 @@ impl Config {
         // Ensure themes directory exists and has built-in themes
         ConfigLoader::ensure_themes_exist();
 
-        let config_path = Self::get_config_path();
+        let config_path = Self::get_config_path()?;
 
-        if !config_path.exists() {
-            return Ok(Config::default());
+        // An explicitly chosen file must exist, defaults would hide a typo
+        if CONFIG_PATH_OVERRIDE.get().is_some() && !config_path.exists() {
+            return Err(format!("Config file not found: {}", config_path.display()).into());
         }
 
-        let content = fs::read_to_string(config_path)?;
-        let config: Config = toml::from_str(&content)?;
+        let mut config = if config_path.exists() {
+            let content = fs::read_to_string(&config_path)?;
+            ConfigFormat::from_path(&config_path).parse(&content)?
+        } else {
+            Config::default()
+        };
+
+        // CCLINE_* environment variables take precedence over the file
+        config.apply_env_overrides();
         Ok(config)
     }
 
     /// Save configuration to default location
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let config_path = Self::get_config_path();
+        let config_path = Self::get_config_path()?;
 
         // Ensure config directory exists
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
-        fs::write(config_path, content)?;
+        // Keep whichever format the existing file uses
+        let content = ConfigFormat::from_path(&config_path).serialize(self)?;
+        crate::utils::atomic_write(config_path, content)?;
         Ok(())
     }
 
-    /// Get the default config file path (~/.claude/ccline/config.toml)
-    fn get_config_path() -> PathBuf {
-        if let Some(home) = dirs::home_dir() {
-            home.join(".claude").join("ccline").join("config.toml")
-        } else {
-            PathBuf::from(".claude/ccline/config.toml")
+    /// Get the config file path: the first existing `config.{toml,json,yaml,yml}`
+    /// in the config directory, or `config.toml` when none exists yet
+    pub fn get_config_path() -> Result<PathBuf, NoConfigDir> {
+        if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+            return Ok(path.clone());
         }
+
+        let dir = config_dir()?;
+        Ok(ConfigFormat::CANDIDATES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| dir.join("config.toml")))
+    }
+
+    /// Use `path` instead of the default config location for the rest of the process
+    pub fn set_config_path(path: PathBuf) {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
     }
 
     /// Initialize config directory and create default config
     pub fn init() -> Result<InitResult, Box<dyn std::error::Error>> {
-        let config_path = Self::get_config_path();
+        let config_path = Self::get_config_path()?;
 
         // Create directory
         if let Some(parent) = config_path.parent() {
@@ -191,10 +267,170 @@ impl Config {
         Ok(())
     }
 
+    /// Advisory problems that don't stop the statusline from rendering
+    ///
+    /// Covers nerd-font mode in a terminal unlikely to have the glyphs and
+    /// `nerd_font` icons that aren't a single private-use codepoint, which
+    /// typically render as tofu boxes, and clock timezones it can't show.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        // Auto already falls back to plain icons on its own
+        if matches!(self.style.mode, StyleMode::NerdFont | StyleMode::Powerline)
+            && !StyleMode::terminal_supports_nerd_font()
+        {
+            warnings.push(format!(
+                "style.mode is {:?} but this terminal (TERM={}) likely has no nerd font; icons may render as boxes, consider mode = \"plain\"",
+                self.style.mode,
+                std::env::var("TERM").unwrap_or_default()
+            ));
+        }
+
+        for segment in &self.segments {
+            if segment.id == SegmentId::Clock {
+                let timezone = segment.options.get("timezone").and_then(|v| v.as_str());
+                if let Some(tz) = timezone.filter(|tz| !ClockSegment::supports_timezone(tz)) {
+                    warnings.push(format!(
+                        "segment clock: timezone {:?} is not supported (IANA names aren't), use local, utc or a fixed offset like +09:00; the segment is hidden",
+                        tz
+                    ));
+                }
+            }
+
+            let icon = &segment.icon.nerd_font;
+            // An empty icon is a deliberate "no icon"
+            if icon.is_empty() {
+                continue;
+            }
+
+            let mut chars = icon.chars();
+            match (chars.next(), chars.next()) {
+                (Some(glyph), None) if !Self::is_private_use(glyph) => warnings.push(format!(
+                    "segment {}: nerd_font icon U+{:04X} is outside the private use area, so it is not a nerd-font glyph",
+                    segment.id.name(),
+                    glyph as u32
+                )),
+                (Some(_), None) => {}
+                _ => warnings.push(format!(
+                    "segment {}: nerd_font icon {:?} should be a single glyph, found {} codepoints",
+                    segment.id.name(),
+                    icon,
+                    icon.chars().count()
+                )),
+            }
+        }
+
+        warnings
+    }
+
+    /// Nerd fonts put their glyphs in the BMP and supplementary private use areas
+    fn is_private_use(glyph: char) -> bool {
+        matches!(glyph as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+    }
+
     /// Print configuration as TOML
     pub fn print(&self) -> Result<(), Box<dyn std::error::Error>> {
         let content = toml::to_string_pretty(self)?;
         println!("{}", content);
         Ok(())
     }
+
+    /// Return a copy with secret segment options (keys, tokens, passwords) masked
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        for segment in &mut config.segments {
+            for (name, value) in segment.options.iter_mut() {
+                if Self::is_secret_option(name) && !value.is_null() {
+                    *value = serde_json::Value::String("<redacted>".to_string());
+                }
+            }
+        }
+        config
+    }
+
+    fn is_secret_option(name: &str) -> bool {
+        let name = name.to_lowercase();
+        name == "key"
+            || name.ends_with("_key")
+            || name.contains("token")
+            || name.contains("secret")
+            || name.contains("password")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_follow_the_extension() {
+        assert_eq!(ConfigFormat::from_path("a/config.json"), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path("config.YML"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path("config.yaml"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path("config.toml"), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path("config"), ConfigFormat::Toml);
+    }
+
+    #[test]
+    fn toml_and_json_round_trip() {
+        for format in [ConfigFormat::Toml, ConfigFormat::Json] {
+            let config = Config::default();
+            let parsed = format.parse(&format.serialize(&config).unwrap()).unwrap();
+            assert_eq!(
+                serde_json::to_value(&parsed).unwrap(),
+                serde_json::to_value(&config).unwrap(),
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_round_trips() {
+        let config = Config::default();
+        let yaml = ConfigFormat::Yaml.serialize(&config).unwrap();
+        let parsed = ConfigFormat::Yaml.parse(&yaml).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn yaml_without_the_feature_is_an_error() {
+        let error = ConfigFormat::Yaml.parse("theme: default\n").unwrap_err();
+        assert!(error.to_string().contains("`yaml` feature"), "{}", error);
+        assert!(ConfigFormat::Yaml.serialize(&Config::default()).is_err());
+    }
+
+    #[test]
+    fn iana_clock_timezone_is_warned_about() {
+        let mut config = Config::default();
+        let clock = config
+            .segments
+            .iter_mut()
+            .find(|segment| segment.id == SegmentId::Clock)
+            .unwrap();
+        clock
+            .options
+            .insert("timezone".to_string(), "+09:00".into());
+        assert!(!config.warnings().iter().any(|w| w.contains("timezone")));
+
+        let clock = config
+            .segments
+            .iter_mut()
+            .find(|segment| segment.id == SegmentId::Clock)
+            .unwrap();
+        clock
+            .options
+            .insert("timezone".to_string(), "Europe/Berlin".into());
+        let warnings = config.warnings();
+        assert!(
+            warnings.iter().any(|w| w.contains("\"Europe/Berlin\"")),
+            "{:?}",
+            warnings
+        );
+    }
 }
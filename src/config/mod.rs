@@ -1,8 +1,11 @@
 pub mod defaults;
+pub mod env;
 pub mod loader;
 pub mod models;
+pub mod paths;
 pub mod types;
 
-pub use loader::{ConfigLoader, InitResult};
+pub use loader::{ConfigFormat, ConfigLoader, InitResult};
 pub use models::*;
+pub use paths::{config_dir, NoConfigDir};
 pub use types::*;
@@ -1,6 +1,7 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Model alias entry for exact model ID matching
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +13,9 @@ pub struct ModelAlias {
     /// Optional context limit override
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context_limit: Option<u32>,
+    /// Ignore ASCII case when matching `id`, overriding the file-wide setting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_insensitive: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +26,115 @@ pub struct ModelConfig {
     /// Model patterns for fuzzy matching (fallback)
     #[serde(default, rename = "models")]
     pub model_entries: Vec<ModelEntry>,
+    /// Match alias ids ignoring ASCII case (patterns always ignore case)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive: bool,
+    /// Cleanup of the raw id when no alias or pattern matches
+    #[serde(default)]
+    pub fallback_transform: FallbackTransform,
+}
+
+/// Steps turning an unmatched id like `anthropic/claude-x-internal-20250101` into
+/// `Claude X Internal`; disabled by default so the model segment's own fallback applies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackTransform {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Drop routing prefixes up to the last `/`
+    #[serde(default = "default_true")]
+    pub strip_provider: bool,
+    /// Drop trailing `-YYYYMMDD` / `-YYYY-MM-DD` snapshot dates
+    #[serde(default = "default_true")]
+    pub drop_date: bool,
+    /// Split on `-`/`_` and capitalize words, joining version numbers as `3.5`
+    #[serde(default = "default_true")]
+    pub title_case: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for FallbackTransform {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strip_provider: true,
+            drop_date: true,
+            title_case: true,
+        }
+    }
+}
+
+impl FallbackTransform {
+    /// Apply the enabled steps, `None` when disabled or nothing readable is left
+    pub fn apply(&self, model_id: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut id = model_id.trim();
+        if self.strip_provider {
+            id = id.rsplit('/').next().unwrap_or(id);
+        }
+
+        let mut parts: Vec<&str> = id.split(['-', '_']).collect();
+        if self.drop_date {
+            Self::drop_date(&mut parts);
+        }
+
+        let name = if self.title_case {
+            Self::title_case(&parts)
+        } else {
+            // Keep the id's own separators, only the dropped suffix goes
+            let kept = parts.iter().map(|p| p.len()).sum::<usize>() + parts.len() - 1;
+            id[..kept].to_string()
+        };
+
+        let name = name.trim().to_string();
+        (!name.is_empty()).then_some(name)
+    }
+
+    fn drop_date(parts: &mut Vec<&str>) {
+        let is_digits =
+            |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+
+        if parts.len() > 1 && parts.last().is_some_and(|p| is_digits(p, 8)) {
+            parts.pop();
+        } else if parts.len() > 3 {
+            let tail = &parts[parts.len() - 3..];
+            if is_digits(tail[0], 4) && is_digits(tail[1], 2) && is_digits(tail[2], 2) {
+                parts.truncate(parts.len() - 3);
+            }
+        }
+    }
+
+    fn title_case(parts: &[&str]) -> String {
+        let mut words: Vec<String> = Vec::new();
+        let mut previous_numeric = false;
+
+        for part in parts.iter().filter(|p| !p.is_empty()) {
+            // Short numbers in a row are a version, `3-5` reads as `3.5`
+            let numeric = part.len() <= 2 && part.bytes().all(|b| b.is_ascii_digit());
+            match words.last_mut() {
+                Some(last) if numeric && previous_numeric => {
+                    last.push('.');
+                    last.push_str(part);
+                }
+                _ => {
+                    let mut chars = part.chars();
+                    let word = match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    };
+                    words.push(word);
+                }
+            }
+            previous_numeric = numeric;
+        }
+
+        words.join(" ")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +142,67 @@ pub struct ModelEntry {
     pub pattern: String,
     pub display_name: String,
     pub context_limit: u32,
+    /// Match the whole id against `pattern` as a glob (`*`, `?`, `[...]`) instead of a substring
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub glob: bool,
+}
+
+impl ModelEntry {
+    /// Case-insensitive match against an already lowercased model id
+    pub fn matches(&self, model_lower: &str) -> bool {
+        if !self.glob {
+            return model_lower.contains(&self.pattern.to_lowercase());
+        }
+
+        match glob_to_regex(&self.pattern.to_lowercase()) {
+            Some(regex) => regex.is_match(model_lower),
+            None => {
+                log::debug!("skipping invalid model glob '{}'", self.pattern);
+                false
+            }
+        }
+    }
+}
+
+/// Translate a glob into an anchored regex, `None` for an unclosed `[` class
+fn glob_to_regex(pattern: &str) -> Option<Regex> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                let mut class = String::new();
+                let mut closed = false;
+                for (i, c) in chars.by_ref().enumerate() {
+                    match c {
+                        ']' if i > 0 => {
+                            closed = true;
+                            break;
+                        }
+                        '!' if i == 0 => class.push('^'),
+                        '\\' | '[' | '&' | '~' => {
+                            class.push('\\');
+                            class.push(c);
+                        }
+                        _ => class.push(c),
+                    }
+                }
+                if !closed {
+                    return None;
+                }
+                regex.push('[');
+                regex.push_str(&class);
+                regex.push(']');
+            }
+            _ => regex.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+
+    regex.push('$');
+    Regex::new(&regex).ok()
 }
 
 impl ModelConfig {
@@ -41,49 +215,67 @@ impl ModelConfig {
 
     /// Load model configuration with fallback locations
     pub fn load() -> Self {
-        let mut model_config = Self::default();
+        let user_models_path = super::config_dir().ok().map(|dir| dir.join("models.toml"));
 
         // First, try to create default models.toml if it doesn't exist
-        if let Some(home_dir) = dirs::home_dir() {
-            let user_models_path = home_dir.join(".claude").join("ccline").join("models.toml");
+        if let Some(user_models_path) = &user_models_path {
             if !user_models_path.exists() {
-                let _ = Self::create_default_file(&user_models_path);
+                let _ = Self::create_default_file(user_models_path);
             }
         }
 
-        // Try loading from user config directory first, then local
-        let config_paths = [
-            dirs::home_dir().map(|d| d.join(".claude").join("ccline").join("models.toml")),
-            Some(Path::new("models.toml").to_path_buf()),
-        ];
-
-        for path in config_paths.iter().flatten() {
-            if path.exists() {
-                if let Ok(config) = Self::load_from_file(path) {
-                    // Merge aliases (user config takes priority)
-                    let mut merged_aliases = config.model_aliases;
-                    merged_aliases.extend(model_config.model_aliases);
-                    model_config.model_aliases = merged_aliases;
-
-                    // Prepend external models to built-in ones for priority
-                    let mut merged_entries = config.model_entries;
-                    merged_entries.extend(model_config.model_entries);
-                    model_config.model_entries = merged_entries;
-                    return model_config;
+        // User config directory first, then local
+        let config_paths: Vec<PathBuf> = [user_models_path, Some(PathBuf::from("models.toml"))]
+            .into_iter()
+            .flatten()
+            .collect();
+        Self::load_from_paths(&config_paths)
+    }
+
+    /// Layer the first of `paths` that loads on top of the built-in configuration
+    ///
+    /// Later paths are only fallbacks: since the user file is created on first run,
+    /// a `models.toml` in the working directory (a checked-out project) is never mixed in.
+    fn load_from_paths(paths: &[PathBuf]) -> Self {
+        let mut model_config = Self::default();
+
+        for path in paths.iter().filter(|path| path.exists()) {
+            let config = match Self::load_from_file(path) {
+                Ok(config) => config,
+                Err(e) => {
+                    log::debug!("ignoring unreadable {}: {}", path.display(), e);
+                    continue;
                 }
-            }
+            };
+
+            // There are no built-in aliases; the file's patterns go before the built-in ones
+            let mut entries = config.model_entries;
+            entries.append(&mut model_config.model_entries);
+            model_config.model_aliases = config.model_aliases;
+            model_config.model_entries = entries;
+            model_config.case_insensitive = config.case_insensitive;
+            model_config.fallback_transform = config.fallback_transform;
+            break;
         }
 
-        // Fallback to default configuration if no file found
         model_config
     }
 
+    /// Whether `alias` is an exact match for `model_id`, honoring `case_insensitive`
+    pub fn alias_matches(&self, alias: &ModelAlias, model_id: &str) -> bool {
+        if alias.case_insensitive.unwrap_or(self.case_insensitive) {
+            alias.id.eq_ignore_ascii_case(model_id)
+        } else {
+            alias.id == model_id
+        }
+    }
+
     /// Get context limit for a model based on ID matching
     /// Priority: exact alias match > pattern match > default
     pub fn get_context_limit(&self, model_id: &str) -> u32 {
         // First, check exact alias match
         for alias in &self.model_aliases {
-            if alias.id == model_id {
+            if self.alias_matches(alias, model_id) {
                 if let Some(limit) = alias.context_limit {
                     return limit;
                 }
@@ -94,7 +286,7 @@ impl ModelConfig {
 
         // Check model entries (pattern matching)
         for entry in &self.model_entries {
-            if model_lower.contains(&entry.pattern.to_lowercase()) {
+            if entry.matches(&model_lower) {
                 return entry.context_limit;
             }
         }
@@ -103,11 +295,11 @@ impl ModelConfig {
     }
 
     /// Get display name for a model based on ID matching
-    /// Priority: exact alias match > pattern match > None (use fallback)
+    /// Priority: exact alias match > pattern match > fallback transform > None (use fallback)
     pub fn get_display_name(&self, model_id: &str) -> Option<String> {
         // First, check exact alias match (highest priority)
         for alias in &self.model_aliases {
-            if alias.id == model_id {
+            if self.alias_matches(alias, model_id) {
                 return Some(alias.display_name.clone());
             }
         }
@@ -116,12 +308,12 @@ impl ModelConfig {
 
         // Check model entries (pattern matching)
         for entry in &self.model_entries {
-            if model_lower.contains(&entry.pattern.to_lowercase()) {
+            if entry.matches(&model_lower) {
                 return Some(entry.display_name.clone());
             }
         }
 
-        None
+        self.fallback_transform.apply(model_id)
     }
 
     /// Create default model configuration file with minimal template
@@ -142,6 +334,10 @@ impl ModelConfig {
 # Use aliases for exact model ID matching. This is useful when you want to
 # customize the display name for a specific model ID.
 #
+# Ids are compared case-sensitively. Set `case_insensitive = true` at the top
+# of this file (before any [[aliases]]) to ignore ASCII case for every alias,
+# or inside a single [[aliases]] entry for just that one.
+#
 # Example:
 # [[aliases]]
 # id = "gemini-claude-opus-4-5-thinking"      # Exact model ID to match
@@ -158,15 +354,30 @@ impl ModelConfig {
 # =============================================================================
 # Use patterns for fuzzy matching. The pattern is matched using "contains".
 # Order matters: first match wins, so put more specific patterns first.
+# Add `glob = true` to match the whole id with `*`, `?` and `[...]` wildcards,
+# e.g. pattern = "claude-3-*-sonnet*".
 #
 # Example:
 # [[models]]
 # pattern = "glm-4.5"
 # display_name = "GLM-4.5"
 # context_limit = 128000
+
+# =============================================================================
+# Fallback Transform (No Match)
+# =============================================================================
+# Clean up ids nothing above matched instead of showing them raw, e.g.
+# "anthropic/claude-x-internal-20250101" -> "Claude X Internal".
+# Each step defaults to true once enabled.
+#
+# [fallback_transform]
+# enabled = true
+# strip_provider = true    # drop everything up to the last "/"
+# drop_date = true         # drop a trailing -YYYYMMDD or -YYYY-MM-DD
+# title_case = true        # "claude-3-5-sonnet" -> "Claude 3.5 Sonnet"
 "#;
 
-        fs::write(path, template_content)?;
+        crate::utils::atomic_write(path, template_content)?;
         Ok(())
     }
 }
@@ -175,40 +386,208 @@ impl Default for ModelConfig {
     fn default() -> Self {
         Self {
             model_aliases: vec![],
+            case_insensitive: false,
+            fallback_transform: FallbackTransform::default(),
             model_entries: vec![
                 // 1M context models (put first for priority matching)
                 ModelEntry {
                     pattern: "[1m]".to_string(),
                     display_name: "Sonnet 4.5 1M".to_string(),
                     context_limit: 1_000_000,
+                    glob: false,
                 },
                 ModelEntry {
                     pattern: "claude-3-7-sonnet".to_string(),
                     display_name: "Sonnet 3.7".to_string(),
                     context_limit: 200_000,
+                    glob: false,
                 },
                 // Third-party models
                 ModelEntry {
                     pattern: "glm-4.5".to_string(),
                     display_name: "GLM-4.5".to_string(),
                     context_limit: 128_000,
+                    glob: false,
                 },
                 ModelEntry {
                     pattern: "kimi-k2-turbo".to_string(),
                     display_name: "Kimi K2 Turbo".to_string(),
                     context_limit: 128_000,
+                    glob: false,
                 },
                 ModelEntry {
                     pattern: "kimi-k2".to_string(),
                     display_name: "Kimi K2".to_string(),
                     context_limit: 128_000,
+                    glob: false,
                 },
                 ModelEntry {
                     pattern: "qwen3-coder".to_string(),
                     display_name: "Qwen Coder".to_string(),
                     context_limit: 256_000,
+                    glob: false,
                 },
             ],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(id: &str, display_name: &str, case_insensitive: Option<bool>) -> ModelAlias {
+        ModelAlias {
+            id: id.to_string(),
+            display_name: display_name.to_string(),
+            context_limit: Some(123_000),
+            case_insensitive,
+        }
+    }
+
+    fn config(case_insensitive: bool, aliases: Vec<ModelAlias>) -> ModelConfig {
+        ModelConfig {
+            model_aliases: aliases,
+            case_insensitive,
+            ..ModelConfig::default()
+        }
+    }
+
+    #[test]
+    fn alias_ids_match_exactly_by_default() {
+        let config = config(false, vec![alias("claude-opus-4", "Opus", None)]);
+
+        assert_eq!(
+            config.get_display_name("claude-opus-4").as_deref(),
+            Some("Opus")
+        );
+        assert_ne!(
+            config.get_display_name("Claude-Opus-4").as_deref(),
+            Some("Opus")
+        );
+        assert_eq!(config.get_context_limit("CLAUDE-OPUS-4"), 200_000);
+    }
+
+    #[test]
+    fn case_insensitive_matches_mixed_case_ids() {
+        let config = config(true, vec![alias("Claude-Opus-4", "Opus", None)]);
+
+        for id in [
+            "claude-opus-4",
+            "CLAUDE-OPUS-4",
+            "cLaUdE-oPuS-4",
+            "Claude-Opus-4",
+        ] {
+            assert_eq!(
+                config.get_display_name(id).as_deref(),
+                Some("Opus"),
+                "{}",
+                id
+            );
+            assert_eq!(config.get_context_limit(id), 123_000, "{}", id);
+        }
+        assert_ne!(
+            config.get_display_name("claude-opus-4-1").as_deref(),
+            Some("Opus")
+        );
+    }
+
+    #[test]
+    fn per_alias_case_insensitive_overrides_the_file() {
+        let config = config(
+            true,
+            vec![
+                alias("Strict-Id", "Strict", Some(false)),
+                alias("Loose-Id", "Loose", Some(true)),
+            ],
+        );
+        assert_eq!(
+            config.get_display_name("Strict-Id").as_deref(),
+            Some("Strict")
+        );
+        assert_eq!(config.get_display_name("strict-id"), None);
+        assert_eq!(
+            config.get_display_name("LOOSE-ID").as_deref(),
+            Some("Loose")
+        );
+
+        let config = ModelConfig {
+            case_insensitive: false,
+            ..config
+        };
+        assert_eq!(
+            config.get_display_name("loose-id").as_deref(),
+            Some("Loose")
+        );
+    }
+
+    #[test]
+    fn patterns_ignore_case_regardless_of_the_setting() {
+        let config = config(false, vec![]);
+        assert_eq!(
+            config.get_display_name("KIMI-K2-Turbo").as_deref(),
+            Some("Kimi K2 Turbo")
+        );
+        assert_eq!(config.get_context_limit("Qwen3-Coder-Plus"), 256_000);
+    }
+
+    #[test]
+    fn first_loadable_file_wins() {
+        let dir = crate::test_support::temp_dir("models-first-file");
+        let user = dir.join("user.toml");
+        let local = dir.join("local.toml");
+        std::fs::write(
+            &user,
+            "case_insensitive = true\n\n[[aliases]]\nid = \"shared\"\ndisplay_name = \"From user\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &local,
+            "[fallback_transform]\nenabled = true\n\n\
+             [[aliases]]\nid = \"Local-Only\"\ndisplay_name = \"Local\"\n\n\
+             [[models]]\npattern = \"evil\"\ndisplay_name = \"Injected\"\ncontext_limit = 1\n",
+        )
+        .unwrap();
+
+        let config = ModelConfig::load_from_paths(&[user.clone(), local.clone()]);
+
+        assert!(config.case_insensitive);
+        assert_eq!(
+            config.get_display_name("SHARED").as_deref(),
+            Some("From user")
+        );
+        // Nothing from the project's models.toml leaks in
+        assert_eq!(config.get_display_name("local-only"), None);
+        assert_ne!(
+            config.get_display_name("evil-model").as_deref(),
+            Some("Injected")
+        );
+        assert!(!config.fallback_transform.enabled);
+        // Built-in patterns still come after the file's
+        assert_eq!(
+            config.get_display_name("kimi-k2").as_deref(),
+            Some("Kimi K2")
+        );
+
+        // An unreadable user file falls back to the next one
+        std::fs::write(&user, "case_insensitive = \"maybe\"\n").unwrap();
+        let config = ModelConfig::load_from_paths(&[user, local, dir.join("missing.toml")]);
+        assert_eq!(
+            config.get_display_name("Local-Only").as_deref(),
+            Some("Local")
+        );
+        assert!(config.fallback_transform.enabled);
+    }
+
+    #[test]
+    fn unset_settings_keep_their_defaults() {
+        let dir = crate::test_support::temp_dir("models-defaults");
+        let path = dir.join("models.toml");
+        std::fs::write(&path, "[[aliases]]\nid = \"a\"\ndisplay_name = \"A\"\n").unwrap();
+
+        let config = ModelConfig::load_from_paths(&[path]);
+        assert!(!config.case_insensitive);
+        assert!(!config.fallback_transform.enabled);
+        assert_eq!(config.model_aliases.len(), 1);
+    }
+}
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+/// Neither `CCLINE_HOME` nor a home directory is available
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct NoConfigDir;
+
+impl std::fmt::Display for NoConfigDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cannot locate the ccline config directory: set CCLINE_HOME, or HOME to use ~/.claude/ccline"
+        )
+    }
+}
+
+// `main` returning this error prints its Debug form, so keep it readable
+impl std::fmt::Debug for NoConfigDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for NoConfigDir {}
+
+/// Directory holding ccline's config, themes, models.toml and caches
+///
+/// `CCLINE_HOME` replaces the default `~/.claude/ccline`, for sandboxes and containers
+/// without a usable home directory. Callers that only cache skip caching on error;
+/// commands that need the directory report it.
+pub fn config_dir() -> Result<PathBuf, NoConfigDir> {
+    if let Some(dir) = std::env::var_os("CCLINE_HOME").filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+
+    dirs::home_dir()
+        .map(|home| home.join(".claude").join("ccline"))
+        .ok_or(NoConfigDir)
+}
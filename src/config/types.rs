@@ -14,7 +14,101 @@ pub struct Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StyleConfig {
     pub mode: StyleMode,
-    pub separator: String,
+    pub separator: SeparatorConfig,
+    /// Join segments with colored `\u{e0b0}` arrows instead of the separator (non-plain modes)
+    #[serde(default)]
+    pub powerline: bool,
+    /// Terminal columns the statusline may use; low-priority segments are dropped to fit
+    #[serde(default)]
+    pub max_width: Option<usize>,
+    /// Colors the terminal can show; RGB and 256 colors are mapped down to fit
+    #[serde(default)]
+    pub color_depth: ColorDepth,
+}
+
+impl StyleConfig {
+    /// Separator text for the active mode, resolving `Auto` against the terminal
+    pub fn separator(&self) -> &str {
+        self.separator.for_mode(self.mode.resolve())
+    }
+}
+
+/// Segment separator, either one string or a variant per style mode like `IconConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SeparatorConfig {
+    Uniform(String),
+    PerMode { plain: String, nerd_font: String },
+}
+
+impl SeparatorConfig {
+    /// `plain` in plain mode, `nerd_font` in nerd-font and powerline modes
+    pub fn for_mode(&self, mode: StyleMode) -> &str {
+        match self {
+            Self::Uniform(separator) => separator,
+            Self::PerMode { plain, nerd_font } => match mode {
+                StyleMode::Plain => plain,
+                _ => nerd_font,
+            },
+        }
+    }
+
+    /// Replace the separator used in `mode`, keeping the other variant of a per-mode pair
+    pub fn set_for_mode(&mut self, mode: StyleMode, separator: String) {
+        match self {
+            Self::Uniform(current) => *current = separator,
+            Self::PerMode { plain, nerd_font } => match mode {
+                StyleMode::Plain => *plain = separator,
+                _ => *nerd_font = separator,
+            },
+        }
+    }
+}
+
+impl From<&str> for SeparatorConfig {
+    fn from(separator: &str) -> Self {
+        Self::Uniform(separator.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorDepth {
+    /// Detect from `COLORTERM`/`TERM`
+    #[serde(rename = "auto")]
+    Auto,
+    #[default]
+    #[serde(rename = "truecolor")]
+    Truecolor,
+    #[serde(rename = "256")]
+    Color256,
+    #[serde(rename = "16")]
+    Color16,
+}
+
+impl ColorDepth {
+    /// Turn `Auto` into the depth supported by the current terminal
+    pub fn resolve(self) -> ColorDepth {
+        if self != ColorDepth::Auto {
+            return self;
+        }
+
+        let colorterm = std::env::var("COLORTERM")
+            .unwrap_or_default()
+            .to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::Truecolor;
+        }
+
+        let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+        if term.contains("256color") {
+            ColorDepth::Color256
+        } else if term.is_empty() {
+            // Nothing to go on, keep colors as configured
+            ColorDepth::Truecolor
+        } else {
+            ColorDepth::Color16
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -23,18 +117,77 @@ pub enum StyleMode {
     Plain,
     NerdFont,
     Powerline,
+    /// Nerd-font icons when the terminal likely has them, plain otherwise
+    Auto,
+}
+
+impl StyleMode {
+    /// Turn `Auto` into `NerdFont` or `Plain` for the current terminal
+    ///
+    /// `CCLINE_NERD_FONT=1`/`0` overrides the guess.
+    pub fn resolve(self) -> StyleMode {
+        if self != StyleMode::Auto {
+            return self;
+        }
+
+        let nerd_font = std::env::var("CCLINE_NERD_FONT")
+            .ok()
+            .and_then(|value| super::env::parse_bool(&value))
+            .unwrap_or_else(Self::terminal_supports_nerd_font);
+
+        if nerd_font {
+            StyleMode::NerdFont
+        } else {
+            StyleMode::Plain
+        }
+    }
+
+    /// Best-effort guess from the environment, since fonts can't be queried
+    ///
+    /// Only terminals that can't use a patched font are ruled out: the Linux
+    /// console, `dumb` and VT serial terminals, and the legacy Windows console.
+    pub fn terminal_supports_nerd_font() -> bool {
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term == "linux" || term == "dumb" || term.starts_with("vt") {
+            return false;
+        }
+
+        if cfg!(windows) {
+            // Windows Terminal, VS Code and most third-party terminals identify themselves
+            return std::env::var_os("WT_SESSION").is_some()
+                || std::env::var_os("TERM_PROGRAM").is_some()
+                || !term.is_empty();
+        }
+
+        true
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentConfig {
     pub id: SegmentId,
     pub enabled: bool,
+    /// End of the statusline the segment sits at when `style.max_width` is set
+    #[serde(default)]
+    pub align: SegmentAlign,
+    /// Statusline row the segment is rendered on, starting at 0
+    #[serde(default)]
+    pub line: usize,
     pub icon: IconConfig,
     pub colors: ColorConfig,
     pub styles: TextStyleConfig,
     pub options: HashMap<String, serde_json::Value>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentAlign {
+    #[default]
+    Left,
+    /// Pushed to the right edge; joined after the left segments when no width is known
+    Right,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IconConfig {
     pub plain: String,
@@ -43,17 +196,37 @@ pub struct IconConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorConfig {
+    #[serde(default, deserialize_with = "lenient_color")]
     pub icon: Option<AnsiColor>,
+    #[serde(default, deserialize_with = "lenient_color")]
     pub text: Option<AnsiColor>,
+    #[serde(default, deserialize_with = "lenient_color")]
     pub background: Option<AnsiColor>,
 }
 
+/// A color that doesn't parse falls back to the terminal default instead of
+/// failing the whole config, which would drop every other setting with it
+fn lenient_color<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<AnsiColor>, D::Error> {
+    let Some(value) = Option::<serde_json::Value>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    match AnsiColor::deserialize(&value) {
+        Ok(color) => Ok(Some(color)),
+        Err(e) => {
+            log::warn!("ignoring color {}: {}", value, e);
+            Ok(None)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TextStyleConfig {
     pub text_bold: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum AnsiColor {
     Color16 { c16: u8 },
@@ -61,6 +234,208 @@ pub enum AnsiColor {
     Rgb { r: u8, g: u8, b: u8 },
 }
 
+/// Accepted spellings of a color in config files and options
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnsiColorRepr {
+    Color16 {
+        c16: u8,
+    },
+    Color256 {
+        c256: u8,
+    },
+    Rgb {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
+    /// `#rrggbb`, `#rgb` or `hsl(h, s%, l%)`
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for AnsiColor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match AnsiColorRepr::deserialize(deserializer)? {
+            AnsiColorRepr::Color16 { c16 } => Ok(AnsiColor::Color16 { c16 }),
+            AnsiColorRepr::Color256 { c256 } => Ok(AnsiColor::Color256 { c256 }),
+            AnsiColorRepr::Rgb { r, g, b } => Ok(AnsiColor::Rgb { r, g, b }),
+            AnsiColorRepr::Text(text) => AnsiColor::parse(&text)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid color '{}'", text))),
+        }
+    }
+}
+
+impl AnsiColor {
+    /// Parse a textual color: hex (`#rrggbb`, `#rgb`) or `hsl(h, s%, l%)`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.starts_with('#') {
+            Self::from_hex(text)
+        } else {
+            Self::from_hsl(text)
+        }
+    }
+
+    /// Parse `#rrggbb` or `#rgb` (the leading `#` is optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        match hex.len() {
+            6 => Some(AnsiColor::Rgb {
+                r: channel(&hex[0..2])?,
+                g: channel(&hex[2..4])?,
+                b: channel(&hex[4..6])?,
+            }),
+            // Shorthand: each digit is doubled
+            3 => Some(AnsiColor::Rgb {
+                r: channel(&hex[0..1])? * 17,
+                g: channel(&hex[1..2])? * 17,
+                b: channel(&hex[2..3])? * 17,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Parse `hsl(h, s%, l%)` with hue in degrees
+    pub fn from_hsl(text: &str) -> Option<Self> {
+        let inner = text.trim().strip_prefix("hsl(")?.strip_suffix(')')?;
+        let parts: Vec<f64> = inner
+            .split(',')
+            .map(|p| p.trim().trim_end_matches('%').parse::<f64>().ok())
+            .collect::<Option<_>>()?;
+        let [h, s, l] = parts[..] else {
+            return None;
+        };
+        if !(0.0..=100.0).contains(&s) || !(0.0..=100.0).contains(&l) {
+            return None;
+        }
+
+        let (s, l) = (s / 100.0, l / 100.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u8 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+        let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Some(AnsiColor::Rgb {
+            r: to_u8(r),
+            g: to_u8(g),
+            b: to_u8(b),
+        })
+    }
+
+    /// Approximate RGB value, using the xterm palette for indexed colors
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            AnsiColor::Color16 { c16 } => XTERM_BASIC[(*c16 as usize).min(15)],
+            AnsiColor::Color256 { c256 } => match *c256 {
+                0..=15 => XTERM_BASIC[*c256 as usize],
+                16..=231 => {
+                    // 6x6x6 color cube
+                    let index = c256 - 16;
+                    let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                    (level(index / 36), level((index / 6) % 6), level(index % 6))
+                }
+                _ => {
+                    // 24-step grayscale ramp
+                    let gray = 8 + (c256 - 232) * 10;
+                    (gray, gray, gray)
+                }
+            },
+            AnsiColor::Rgb { r, g, b } => (*r, *g, *b),
+        }
+    }
+
+    /// `#rrggbb` form of the color
+    pub fn to_hex(&self) -> String {
+        let (r, g, b) = self.to_rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Nearest color the given depth can display
+    pub fn downgrade(&self, depth: ColorDepth) -> AnsiColor {
+        match (depth, self) {
+            (ColorDepth::Color256, AnsiColor::Rgb { r, g, b }) => AnsiColor::Color256 {
+                c256: rgb_to_256(*r, *g, *b),
+            },
+            (ColorDepth::Color16, AnsiColor::Color16 { .. }) => self.clone(),
+            (ColorDepth::Color16, AnsiColor::Color256 { c256 }) if *c256 < 16 => {
+                AnsiColor::Color16 { c16: *c256 }
+            }
+            (ColorDepth::Color16, _) => {
+                let (r, g, b) = self.to_rgb();
+                let c16 = (0..16u8)
+                    .min_by_key(|&i| color_distance((r, g, b), XTERM_BASIC[i as usize]))
+                    .unwrap_or(7);
+                AnsiColor::Color16 { c16 }
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+/// Standard xterm values of the 16 basic colors
+const XTERM_BASIC: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Closest xterm-256 index, choosing between the color cube and the grayscale ramp
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = ((r as u32 + g as u32 + b as u32) / 3) as u8;
+    let gray = if average > 238 {
+        255
+    } else {
+        232 + average.saturating_sub(3) / 10
+    };
+
+    let target = (r, g, b);
+    let cube_rgb = AnsiColor::Color256 { c256: cube }.to_rgb();
+    let gray_rgb = AnsiColor::Color256 { c256: gray }.to_rgb();
+    if color_distance(target, gray_rgb) < color_distance(target, cube_rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SegmentId {
@@ -74,6 +449,55 @@ pub enum SegmentId {
     OutputStyle,
     Update,
     CliProxyApiQuota,
+    RateLimit,
+    ModelContext,
+    Clock,
+    Battery,
+    Kube,
+    Venv,
+    Command,
+    CloudProfile,
+    Host,
+    ExitCode,
+}
+
+impl SegmentId {
+    pub fn all() -> &'static [SegmentId] {
+        &[
+            Self::Model,
+            Self::Directory,
+            Self::Git,
+            Self::ContextWindow,
+            Self::Usage,
+            Self::Cost,
+            Self::Session,
+            Self::OutputStyle,
+            Self::Update,
+            Self::CliProxyApiQuota,
+            Self::RateLimit,
+            Self::ModelContext,
+            Self::Clock,
+            Self::Battery,
+            Self::Kube,
+            Self::Venv,
+            Self::Command,
+            Self::CloudProfile,
+            Self::Host,
+            Self::ExitCode,
+        ]
+    }
+
+    /// Config file name of the segment, e.g. `context_window`
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
 }
 
 // Legacy compatibility structure
@@ -86,18 +510,25 @@ pub struct SegmentsConfig {
 }
 
 // Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+//
+// Everything defaults when missing and unknown fields are ignored, so a schema
+// change in Claude Code only hides the segments that need the affected field.
+#[derive(Clone, Deserialize, Default)]
 pub struct Model {
+    // Some proxy setups omit the id entirely; treat that the same as an empty id
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Default)]
 pub struct Workspace {
+    #[serde(default)]
     pub current_dir: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Default)]
 pub struct Cost {
     pub total_cost_usd: Option<f64>,
     pub total_duration_ms: Option<u64>,
@@ -106,18 +537,46 @@ pub struct Cost {
     pub total_lines_removed: Option<u32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct OutputStyle {
+    #[serde(default)]
     pub name: String,
 }
 
-#[derive(Deserialize)]
+/// Rate-limit / retry state reported by Claude Code (all fields optional)
+#[derive(Clone, Deserialize, Default)]
+pub struct RateLimit {
+    #[serde(default)]
+    pub limited: bool,
+    #[serde(default)]
+    pub retrying: bool,
+    pub retry_after_seconds: Option<u64>,
+    pub retry_attempt: Option<u32>,
+}
+
+#[derive(Clone, Deserialize, Default)]
 pub struct InputData {
+    #[serde(default)]
     pub model: Model,
+    #[serde(default)]
     pub workspace: Workspace,
+    #[serde(default)]
     pub transcript_path: String,
+    #[serde(default)]
     pub cost: Option<Cost>,
+    #[serde(default)]
     pub output_style: Option<OutputStyle>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// RFC3339 timestamp of when the Claude Code session started
+    #[serde(default)]
+    pub session_start: Option<String>,
+    /// Token usage of the latest turn, when the host reports it
+    #[serde(default)]
+    pub usage: Option<RawUsage>,
+    /// Exit status of the last command, when the host reports it
+    #[serde(default)]
+    pub exit_code: Option<i32>,
 }
 
 // OpenAI-style nested token details
@@ -248,6 +707,9 @@ impl Config {
         // Compare style config
         if self.style.mode != theme_preset.style.mode
             || self.style.separator != theme_preset.style.separator
+            || self.style.powerline != theme_preset.style.powerline
+            || self.style.max_width != theme_preset.style.max_width
+            || self.style.color_depth != theme_preset.style.color_depth
         {
             return false;
         }
@@ -276,6 +738,8 @@ impl Config {
     fn segment_matches(&self, current: &SegmentConfig, preset: &SegmentConfig) -> bool {
         current.id == preset.id
             && current.enabled == preset.enabled
+            && current.align == preset.align
+            && current.line == preset.line
             && current.icon.plain == preset.icon.plain
             && current.icon.nerd_font == preset.icon.nerd_font
             && self.color_matches(&current.colors.icon, &preset.colors.icon)
@@ -415,3 +879,149 @@ pub struct TranscriptEntry {
     pub parent_uuid: Option<String>,
     pub summary: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::{DirectorySegment, ModelSegment, Segment};
+
+    #[test]
+    fn hex_colors_round_trip() {
+        for hex in ["#000000", "#ffffff", "#1e90ff", "#c0ffee"] {
+            let color = AnsiColor::parse(hex).unwrap();
+            assert_eq!(color.to_hex(), hex);
+        }
+
+        assert_eq!(
+            AnsiColor::from_hex("1E90FF"),
+            Some(AnsiColor::Rgb {
+                r: 30,
+                g: 144,
+                b: 255
+            })
+        );
+        assert_eq!(AnsiColor::parse(" #f80 ").unwrap().to_hex(), "#ff8800");
+    }
+
+    #[test]
+    fn hsl_colors_convert_to_rgb() {
+        let rgb = |text: &str| AnsiColor::from_hsl(text).map(|color| color.to_rgb());
+        assert_eq!(rgb("hsl(0, 100%, 50%)"), Some((255, 0, 0)));
+        assert_eq!(rgb("hsl(120, 100%, 50%)"), Some((0, 255, 0)));
+        assert_eq!(rgb("hsl(240,100%,50%)"), Some((0, 0, 255)));
+        assert_eq!(rgb("hsl(360, 100%, 50%)"), Some((255, 0, 0)));
+        assert_eq!(rgb("hsl(0, 0%, 100%)"), Some((255, 255, 255)));
+        assert_eq!(
+            AnsiColor::parse("hsl(210, 50%, 40%)").unwrap().to_hex(),
+            "#336699"
+        );
+    }
+
+    #[test]
+    fn invalid_color_text_is_rejected() {
+        for text in [
+            "#zzzzzz",
+            "#12345",
+            "#1234567",
+            "#",
+            "",
+            "blue",
+            "hsl(0, 100%)",
+            "hsl(0, 101%, 50%)",
+            "hsl(0, 50%, -1%)",
+            "hsl(a, 50%, 50%)",
+            "rgb(1, 2, 3)",
+        ] {
+            assert_eq!(AnsiColor::parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn indexed_colors_report_their_palette_hex() {
+        assert_eq!(AnsiColor::Color16 { c16: 9 }.to_hex(), "#ff0000");
+        assert_eq!(AnsiColor::Color256 { c256: 196 }.to_hex(), "#ff0000");
+        assert_eq!(AnsiColor::Color256 { c256: 232 }.to_hex(), "#080808");
+    }
+
+    #[test]
+    fn colors_deserialize_from_tables_and_text() {
+        let color = |json: serde_json::Value| serde_json::from_value::<AnsiColor>(json).ok();
+        assert_eq!(
+            color(serde_json::json!({"c256": 33})),
+            Some(AnsiColor::Color256 { c256: 33 })
+        );
+        assert_eq!(
+            color(serde_json::json!("#1e90ff")),
+            AnsiColor::parse("#1e90ff")
+        );
+        assert_eq!(color(serde_json::json!("#zzzzzz")), None);
+    }
+
+    #[test]
+    fn bad_color_keeps_the_rest_of_the_config() {
+        let mut config = Config::default();
+        config.style.separator = " ~~ ".into();
+        let mut value = toml::Value::try_from(&config).unwrap();
+        let colors = &mut value["segments"][0]["colors"];
+        colors["icon"] = toml::Value::String("#zzzzzz".to_string());
+        colors["text"] = toml::Value::String("#1e90ff".to_string());
+
+        let parsed: Config = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+
+        assert_eq!(parsed.style.separator(), " ~~ ");
+        assert_eq!(parsed.segments.len(), config.segments.len());
+        assert_eq!(parsed.segments[0].colors.icon, None);
+        assert_eq!(parsed.segments[0].colors.text, AnsiColor::parse("#1e90ff"));
+        assert_eq!(
+            parsed.segments[0].colors.background,
+            config.segments[0].colors.background
+        );
+    }
+
+    #[test]
+    fn minimal_payload_still_renders_core_segments() {
+        let input: InputData = serde_json::from_str(
+            r#"{"model": {"display_name": "Sonnet 4"}, "workspace": {"current_dir": "/tmp/project"}}"#,
+        )
+        .unwrap();
+
+        assert!(input.model.id.is_empty());
+        assert!(input.transcript_path.is_empty());
+        assert!(input.cost.is_none() && input.usage.is_none() && input.exit_code.is_none());
+
+        let directory = DirectorySegment::new().collect(&input).unwrap();
+        assert_eq!(directory.primary, "project");
+        let model = ModelSegment::new().collect(&input).unwrap();
+        assert_eq!(model.primary, "unknown model");
+    }
+
+    #[test]
+    fn empty_payload_parses() {
+        let input: InputData = serde_json::from_str("{}").unwrap();
+
+        assert!(input.workspace.current_dir.is_empty());
+        assert!(DirectorySegment::new().collect(&input).is_none());
+        assert!(ModelSegment::new().collect(&input).is_some());
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        let input: InputData = serde_json::from_str(
+            r#"{
+                "model": {"id": "claude-sonnet-4", "display_name": "Sonnet 4", "tier": "pro"},
+                "workspace": {"current_dir": "/tmp/project", "project_dir": "/tmp"},
+                "transcript_path": "/tmp/transcript.jsonl",
+                "session_id": "abc",
+                "version": "9.9.9",
+                "cost": {"total_cost_usd": 0.5, "total_api_duration_ms": 10}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(input.model.id, "claude-sonnet-4");
+        assert_eq!(input.workspace.current_dir, "/tmp/project");
+        assert_eq!(input.cost.as_ref().unwrap().total_cost_usd, Some(0.5));
+        let model = ModelSegment::new().collect(&input).unwrap();
+        assert!(model.primary.contains("Sonnet"), "{:?}", model.primary);
+    }
+}
@@ -0,0 +1,135 @@
+/// Suffixes from the largest scale down
+const SUFFIXES: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")];
+
+/// Format a count with `k`/`M`/`B` suffixes, e.g. `950`, `12k`, `1.5M`
+///
+/// `precision` is the maximum number of decimals; trailing zeros are dropped so
+/// `3000` renders as `3k` rather than `3.0k`, and values of 100 or more at their
+/// scale never get decimals. A value that rounds up to the next scale moves to
+/// its suffix, so `999_960` is `1M` rather than `1000k`.
+pub fn humanize(value: u64, precision: usize) -> String {
+    let Some(index) = SUFFIXES.iter().position(|(scale, _)| value >= *scale) else {
+        return value.to_string();
+    };
+
+    let (scale, suffix) = SUFFIXES[index];
+    let scaled = value as f64 / scale as f64;
+    let precision = if scaled >= 100.0 { 0 } else { precision };
+
+    let factor = 10f64.powi(precision as i32);
+    let rounded = (scaled * factor).round() / factor;
+    if rounded >= 1000.0 && index > 0 {
+        return humanize(SUFFIXES[index - 1].0, precision);
+    }
+
+    let text = format!("{:.*}", precision, rounded);
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    format!("{}{}", text, suffix)
+}
+
+/// Format how long ago something happened, e.g. `just now`, `5m ago`, `3h ago`, `2w ago`
+///
+/// Negative ages come from clock skew and are treated as `just now`.
+pub fn relative_age(seconds: i64) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (365 * 86_400, "y"),
+        (30 * 86_400, "mo"),
+        (7 * 86_400, "w"),
+        (86_400, "d"),
+        (3_600, "h"),
+        (60, "m"),
+    ];
+
+    UNITS
+        .iter()
+        .find(|(unit, _)| seconds >= *unit)
+        .map(|(unit, suffix)| format!("{}{} ago", seconds / unit, suffix))
+        .unwrap_or_else(|| "just now".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_humanized(precision: usize, cases: &[(u64, &str)]) {
+        for (value, expected) in cases {
+            assert_eq!(
+                humanize(*value, precision),
+                *expected,
+                "humanize({})",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn suffix_starts_exactly_at_each_scale() {
+        assert_humanized(
+            1,
+            &[
+                (0, "0"),
+                (999, "999"),
+                (1_000, "1k"),
+                (1_001, "1k"),
+                (1_000_000, "1M"),
+                (1_000_001, "1M"),
+                (1_000_000_000, "1B"),
+                (1_000_000_001, "1B"),
+            ],
+        );
+    }
+
+    #[test]
+    fn rounding_up_to_the_next_scale_moves_to_its_suffix() {
+        assert_humanized(
+            1,
+            &[
+                (999_499, "999k"),
+                (999_500, "1M"),
+                (999_501, "1M"),
+                (999_999, "1M"),
+                (999_499_999, "999M"),
+                (999_500_000, "1B"),
+                (999_999_999, "1B"),
+            ],
+        );
+    }
+
+    #[test]
+    fn decimals_stop_at_one_hundred() {
+        assert_humanized(
+            1,
+            &[
+                (99_949, "99.9k"),
+                (99_950, "100k"),
+                (99_999, "100k"),
+                (100_000, "100k"),
+                (100_001, "100k"),
+            ],
+        );
+        assert_humanized(
+            2,
+            &[(99_949, "99.95k"), (100_000, "100k"), (100_499, "100k")],
+        );
+    }
+
+    #[test]
+    fn precision_rounds_half_up_and_drops_trailing_zeros() {
+        assert_humanized(
+            1,
+            &[
+                (1_049, "1k"),
+                (1_050, "1.1k"),
+                (1_449, "1.4k"),
+                (1_450, "1.5k"),
+                (2_000, "2k"),
+                (2_500_000, "2.5M"),
+            ],
+        );
+        assert_humanized(0, &[(1_499, "1k"), (1_500, "2k"), (9_500, "10k")]);
+    }
+}
@@ -1,4 +1,11 @@
+pub mod format;
+pub mod network_cache;
 pub mod segments;
 pub mod statusline;
 
-pub use statusline::{collect_all_segments, StatusLineGenerator};
+pub use format::{humanize, relative_age};
+pub use network_cache::{CacheEntry, Cached, KeyedCache, NetworkCache};
+pub use statusline::{
+    collect_all_segments, color_depth, color_enabled, set_benchmark, set_color_depth,
+    set_color_enabled, set_explain, StatusLineGenerator,
+};
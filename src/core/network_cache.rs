@@ -0,0 +1,589 @@
+use crate::config::config_dir;
+use crate::utils::{atomic_write, FileLock};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// A cached value as stored on disk, with the time it was fetched
+///
+/// `value` is flattened so a struct keeps its fields at the top level of the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry<T> {
+    #[serde(flatten)]
+    pub value: T,
+    pub cached_at: String,
+}
+
+/// Consecutive fetch failures, stored next to the cache as `<file>.failures`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FailureState {
+    failures: u32,
+    /// No fetch is attempted before this RFC3339 time
+    retry_after: Option<String>,
+}
+
+/// Value returned by [`NetworkCache::get_or_fetch`]
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub value: T,
+    /// The fetch failed and an expired cache entry was used instead
+    pub stale: bool,
+}
+
+/// JSON cache in `~/.claude/ccline/` for segments backed by a network request
+///
+/// Fresh entries are returned without fetching. Once an entry expires the fetch
+/// is retried, and when it fails the expired entry is still served as stale so a
+/// flaky endpoint doesn't blank the segment.
+///
+/// Every prompt can start a ccline, so refreshes and writes hold a lock on the
+/// cache file: one instance fetches while the others keep showing the previous
+/// value instead of fetching too.
+///
+/// After `failure_threshold` failed fetches in a row the circuit opens: for
+/// `cool_down` seconds nothing is fetched and the stale entry is served, so a
+/// dead endpoint doesn't slow down every prompt.
+pub struct NetworkCache<T> {
+    path: Option<PathBuf>,
+    cache_duration: u64,
+    failure_threshold: u32,
+    cool_down: u64,
+    _value: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> NetworkCache<T> {
+    /// Cache stored as `~/.claude/ccline/<file_name>`, valid for `cache_duration` seconds
+    pub fn new(file_name: &str, cache_duration: u64) -> Self {
+        let path = config_dir().ok().map(|dir| dir.join(file_name));
+        Self::with_path(path, cache_duration)
+    }
+
+    /// Cache stored at an explicit path, `None` disables persistence
+    pub fn with_path(path: Option<PathBuf>, cache_duration: u64) -> Self {
+        Self {
+            path,
+            cache_duration,
+            failure_threshold: 3,
+            cool_down: 60,
+            _value: PhantomData,
+        }
+    }
+
+    /// Pause fetching for `cool_down` seconds after `failure_threshold` failures, 0 never pauses
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cool_down: u64) -> Self {
+        self.failure_threshold = failure_threshold;
+        self.cool_down = cool_down;
+        self
+    }
+
+    /// Read the stored entry; no lock is needed as writes replace the file atomically
+    pub fn load(&self) -> Option<CacheEntry<T>> {
+        let path = self.path.as_ref()?;
+        if !path.exists() {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::debug!("ignoring unreadable cache {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, value: T) -> CacheEntry<T> {
+        let _lock = self.lock(true);
+        self.store(value)
+    }
+
+    /// Write the entry, the caller holds the lock
+    fn store(&self, value: T) -> CacheEntry<T> {
+        let entry = CacheEntry {
+            value,
+            cached_at: Utc::now().to_rfc3339(),
+        };
+
+        if let Some(path) = &self.path {
+            if let Ok(json) = serde_json::to_string_pretty(&entry) {
+                let _ = atomic_write(path, json);
+            }
+        }
+
+        entry
+    }
+
+    pub fn is_valid(&self, entry: &CacheEntry<T>) -> bool {
+        is_fresh(&entry.cached_at, self.cache_duration)
+    }
+
+    /// Return the cached value while fresh, otherwise fetch and store a new one
+    ///
+    /// `fetch` returns `None` on failure; the expired entry is then returned as
+    /// stale, or `None` when there is nothing cached at all.
+    pub fn get_or_fetch<F>(&self, fetch: F) -> Option<Cached<T>>
+    where
+        F: FnOnce() -> Option<T>,
+    {
+        let mut previous = match self.load() {
+            Some(entry) if self.is_valid(&entry) => {
+                log::debug!("cache hit: {}", self.describe());
+                return Some(Cached {
+                    value: entry.value,
+                    stale: false,
+                });
+            }
+            cached => cached,
+        };
+
+        let _lock = match self.lock(false) {
+            Locked::Busy => match previous.take() {
+                Some(entry) => {
+                    log::debug!(
+                        "cache being refreshed by another process, using previous value: {}",
+                        self.describe()
+                    );
+                    return Some(Cached {
+                        stale: !self.is_valid(&entry),
+                        value: entry.value,
+                    });
+                }
+                None => self.lock(true),
+            },
+            lock => lock,
+        };
+
+        // Whoever held the lock before may have just refreshed the entry
+        let cached = match self.load() {
+            Some(entry) if self.is_valid(&entry) => {
+                log::debug!("cache refreshed by another process: {}", self.describe());
+                return Some(Cached {
+                    value: entry.value,
+                    stale: false,
+                });
+            }
+            cached => cached.or(previous),
+        };
+
+        if let Some(failures) = self.circuit_open() {
+            log::debug!(
+                "{} failed fetches in a row, pausing fetches: {}",
+                failures,
+                self.describe()
+            );
+            return cached.map(|entry| Cached {
+                value: entry.value,
+                stale: true,
+            });
+        }
+
+        log::debug!(
+            "cache {}: {}, fetching",
+            if cached.is_some() {
+                "expired"
+            } else {
+                "missing"
+            },
+            self.describe()
+        );
+        match fetch() {
+            Some(value) => {
+                self.record_fetch(true);
+                Some(Cached {
+                    value: self.store(value).value,
+                    stale: false,
+                })
+            }
+            None => {
+                self.record_fetch(false);
+                log::debug!(
+                    "fetch failed, {}",
+                    if cached.is_some() {
+                        "using stale cache"
+                    } else {
+                        "no cache to fall back to"
+                    }
+                );
+                cached.map(|entry| Cached {
+                    value: entry.value,
+                    stale: true,
+                })
+            }
+        }
+    }
+
+    fn failures_path(&self) -> Option<PathBuf> {
+        let mut path = self.path.clone()?.into_os_string();
+        path.push(".failures");
+        Some(PathBuf::from(path))
+    }
+
+    /// Number of failures while the circuit is open, `None` when fetching is allowed
+    fn circuit_open(&self) -> Option<u32> {
+        if self.failure_threshold == 0 {
+            return None;
+        }
+        let content = std::fs::read_to_string(self.failures_path()?).ok()?;
+        let state: FailureState = serde_json::from_str(&content).ok()?;
+        let retry_after = DateTime::parse_from_rfc3339(state.retry_after.as_deref()?).ok()?;
+        (state.failures >= self.failure_threshold && Utc::now() < retry_after)
+            .then_some(state.failures)
+    }
+
+    /// Count a failed fetch, opening the circuit at the threshold; a success resets it
+    ///
+    /// Called with the cache lock held.
+    fn record_fetch(&self, succeeded: bool) {
+        let Some(path) = self.failures_path() else {
+            return;
+        };
+        if succeeded {
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+
+        let mut state: FailureState = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        state.failures = state.failures.saturating_add(1);
+        if self.failure_threshold > 0 && state.failures >= self.failure_threshold {
+            let retry_after = Utc::now() + chrono::Duration::seconds(self.cool_down as i64);
+            state.retry_after = Some(retry_after.to_rfc3339());
+        }
+        if let Ok(json) = serde_json::to_string(&state) {
+            let _ = atomic_write(path, json);
+        }
+    }
+
+    /// Lock the cache file, waiting for other processes when `wait` is set
+    ///
+    /// Failing to lock only costs the serialization, so it is logged and ignored.
+    fn lock(&self, wait: bool) -> Locked {
+        let Some(path) = &self.path else {
+            return Locked::Unlocked;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let result = if wait {
+            FileLock::exclusive(path).map(Some)
+        } else {
+            FileLock::try_exclusive(path)
+        };
+        match result {
+            Ok(Some(lock)) => Locked::Held { _lock: lock },
+            Ok(None) => Locked::Busy,
+            Err(e) => {
+                log::debug!("cannot lock cache {}: {}", path.display(), e);
+                Locked::Unlocked
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "<no cache file>".to_string())
+    }
+}
+
+/// Whether an RFC3339 `cached_at` is less than `cache_duration` seconds old
+fn is_fresh(cached_at: &str, cache_duration: u64) -> bool {
+    if let Ok(cached_at) = DateTime::parse_from_rfc3339(cached_at) {
+        let elapsed = Utc::now().signed_duration_since(cached_at.with_timezone(&Utc));
+        elapsed.num_seconds() < cache_duration as i64
+    } else {
+        false
+    }
+}
+
+/// Stored form of a [`KeyedCache`]
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyedEntries<T> {
+    entries: HashMap<String, CacheEntry<T>>,
+}
+
+impl<T> Default for KeyedEntries<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// Several cached values in one [`NetworkCache`] file, each with its own age
+///
+/// For results keyed by e.g. repository or working directory. Inserts re-read the
+/// file under its lock, so concurrent instances keep each other's entries, and
+/// drop entries that have expired.
+pub struct KeyedCache<T> {
+    file: NetworkCache<KeyedEntries<T>>,
+}
+
+impl<T: Serialize + DeserializeOwned> KeyedCache<T> {
+    /// Cache stored as `~/.claude/ccline/<file_name>`, entries valid for `cache_duration` seconds
+    pub fn new(file_name: &str, cache_duration: u64) -> Self {
+        Self {
+            file: NetworkCache::new(file_name, cache_duration),
+        }
+    }
+
+    /// Cache stored at an explicit path, `None` disables persistence
+    pub fn with_path(path: Option<PathBuf>, cache_duration: u64) -> Self {
+        Self {
+            file: NetworkCache::with_path(path, cache_duration),
+        }
+    }
+
+    /// Fresh value stored under `key`, if `is_current` still accepts it
+    pub fn get(&self, key: &str, is_current: impl FnOnce(&T) -> bool) -> Option<T> {
+        let mut entries = self.file.load()?.value.entries;
+        let entry = entries.remove(key)?;
+        if !is_fresh(&entry.cached_at, self.file.cache_duration) || !is_current(&entry.value) {
+            return None;
+        }
+        log::debug!("cache hit: {} [{}]", self.file.describe(), key);
+        Some(entry.value)
+    }
+
+    pub fn insert(&self, key: &str, value: T) {
+        let _lock = self.file.lock(true);
+        let mut entries = self
+            .file
+            .load()
+            .map(|entry| entry.value)
+            .unwrap_or_default();
+        entries
+            .entries
+            .retain(|_, entry| is_fresh(&entry.cached_at, self.file.cache_duration));
+        entries.entries.insert(
+            key.to_string(),
+            CacheEntry {
+                value,
+                cached_at: Utc::now().to_rfc3339(),
+            },
+        );
+        self.file.store(entries);
+    }
+
+    /// Return the value under `key`, computing and storing it when missing or outdated
+    pub fn get_or_insert_with(
+        &self,
+        key: &str,
+        is_current: impl FnOnce(&T) -> bool,
+        compute: impl FnOnce() -> T,
+    ) -> T
+    where
+        T: Clone,
+    {
+        if let Some(value) = self.get(key, is_current) {
+            return value;
+        }
+        let value = compute();
+        self.insert(key, value.clone());
+        value
+    }
+}
+
+/// Outcome of [`NetworkCache::lock`]
+enum Locked {
+    /// Kept alive to hold the lock
+    Held { _lock: FileLock },
+    /// Another process holds the lock
+    Busy,
+    /// No cache file, or locking is unsupported there
+    Unlocked,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Value {
+        n: u32,
+    }
+
+    fn cache(name: &str, cache_duration: u64) -> NetworkCache<Value> {
+        let dir = crate::test_support::temp_dir(name);
+        NetworkCache::with_path(Some(dir.join("cache.json")), cache_duration)
+    }
+
+    #[test]
+    fn missing_cache_fetches_and_stores() {
+        let cache = cache("network-missing", 60);
+        let cached = cache.get_or_fetch(|| Some(Value { n: 1 })).unwrap();
+        assert_eq!(cached.value, Value { n: 1 });
+        assert!(!cached.stale);
+        assert_eq!(cache.load().unwrap().value, Value { n: 1 });
+    }
+
+    #[test]
+    fn missing_cache_with_failed_fetch_is_none() {
+        let cache = cache("network-missing-failed", 60);
+        assert!(cache.get_or_fetch(|| None).is_none());
+    }
+
+    #[test]
+    fn valid_cache_skips_fetch() {
+        let cache = cache("network-valid", 60);
+        cache.save(Value { n: 1 });
+        let cached = cache
+            .get_or_fetch(|| panic!("fresh entry must not be fetched"))
+            .unwrap();
+        assert_eq!(cached.value, Value { n: 1 });
+        assert!(!cached.stale);
+    }
+
+    #[test]
+    fn expired_cache_is_refetched() {
+        let cache = cache("network-expired", 0);
+        cache.save(Value { n: 1 });
+        let cached = cache.get_or_fetch(|| Some(Value { n: 2 })).unwrap();
+        assert_eq!(cached.value, Value { n: 2 });
+        assert!(!cached.stale);
+    }
+
+    #[test]
+    fn expired_cache_is_served_stale_when_fetch_fails() {
+        let cache = cache("network-expired-failed", 0);
+        cache.save(Value { n: 1 });
+        let cached = cache.get_or_fetch(|| None).unwrap();
+        assert_eq!(cached.value, Value { n: 1 });
+        assert!(cached.stale);
+    }
+
+    #[test]
+    fn circuit_opens_after_repeated_failures() {
+        let cache = cache("network-circuit", 0).with_circuit_breaker(2, 60);
+        cache.save(Value { n: 1 });
+        let fetches = Cell::new(0);
+        let failing_fetch = || {
+            fetches.set(fetches.get() + 1);
+            None
+        };
+
+        for _ in 0..4 {
+            let cached = cache.get_or_fetch(failing_fetch).unwrap();
+            assert!(cached.stale);
+        }
+        assert_eq!(fetches.get(), 2);
+    }
+
+    #[test]
+    fn circuit_resets_after_success() {
+        let cache = cache("network-circuit-reset", 0).with_circuit_breaker(2, 0);
+        cache.get_or_fetch(|| None);
+        cache.get_or_fetch(|| Some(Value { n: 1 }));
+        cache.get_or_fetch(|| None);
+        // One failure since the success, so the circuit is still closed
+        assert_eq!(cache.circuit_open(), None);
+        assert!(cache.get_or_fetch(|| Some(Value { n: 2 })).is_some());
+    }
+
+    #[test]
+    fn concurrent_writers_never_leave_invalid_json() {
+        let dir = crate::test_support::temp_dir("network-stress");
+        let path = dir.join("cache.json");
+
+        let threads: Vec<_> = (0..16)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let cache = NetworkCache::<Value>::with_path(Some(path.clone()), 0);
+                    for n in 0..25 {
+                        cache.save(Value { n: i * 100 + n });
+                        if path.exists() {
+                            let content = std::fs::read_to_string(&path).unwrap();
+                            serde_json::from_str::<CacheEntry<Value>>(&content).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let cache = NetworkCache::<Value>::with_path(Some(path), 0);
+        assert!(cache.load().is_some());
+    }
+
+    fn keyed_cache(name: &str, cache_duration: u64) -> KeyedCache<Value> {
+        let dir = crate::test_support::temp_dir(name);
+        KeyedCache::with_path(Some(dir.join("cache.json")), cache_duration)
+    }
+
+    #[test]
+    fn keyed_entries_are_independent() {
+        let cache = keyed_cache("keyed-independent", 60);
+        cache.insert("a", Value { n: 1 });
+        cache.insert("b", Value { n: 2 });
+        assert_eq!(cache.get("a", |_| true), Some(Value { n: 1 }));
+        assert_eq!(cache.get("b", |_| true), Some(Value { n: 2 }));
+        assert_eq!(cache.get("c", |_| true), None);
+    }
+
+    #[test]
+    fn keyed_entry_rejected_by_is_current_is_recomputed() {
+        let cache = keyed_cache("keyed-current", 60);
+        cache.insert("repo", Value { n: 1 });
+        let value = cache.get_or_insert_with("repo", |v| v.n == 2, || Value { n: 2 });
+        assert_eq!(value, Value { n: 2 });
+        assert_eq!(cache.get("repo", |_| true), Some(Value { n: 2 }));
+    }
+
+    #[test]
+    fn expired_keyed_entry_is_recomputed() {
+        let cache = keyed_cache("keyed-expired", 0);
+        cache.insert("repo", Value { n: 1 });
+        assert_eq!(cache.get("repo", |_| true), None);
+        let value = cache.get_or_insert_with("repo", |_| true, || Value { n: 2 });
+        assert_eq!(value, Value { n: 2 });
+    }
+
+    #[test]
+    fn concurrent_keyed_inserts_keep_every_key() {
+        let dir = crate::test_support::temp_dir("keyed-stress");
+        let path = dir.join("cache.json");
+
+        let threads: Vec<_> = (0..16)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let cache = KeyedCache::with_path(Some(path), 60);
+                    cache.insert(&i.to_string(), Value { n: i });
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let cache = KeyedCache::<Value>::with_path(Some(path), 60);
+        for i in 0..16 {
+            assert_eq!(cache.get(&i.to_string(), |_| true), Some(Value { n: i }));
+        }
+    }
+
+    #[test]
+    fn refresh_in_progress_serves_previous_value_as_stale() {
+        let cache = cache("network-busy", 0);
+        cache.save(Value { n: 1 });
+        let _held = FileLock::exclusive(cache.path.as_ref().unwrap()).unwrap();
+
+        let cached = cache
+            .get_or_fetch(|| panic!("another process is already fetching"))
+            .unwrap();
+        assert_eq!(cached.value, Value { n: 1 });
+        assert!(cached.stale);
+    }
+}
@@ -0,0 +1,150 @@
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{AnsiColor, InputData, SegmentId};
+use std::collections::HashMap;
+
+/// Charge level and state of the first battery reported by the platform
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "battery"), allow(dead_code))]
+struct BatteryReading {
+    percentage: f64,
+    charging: bool,
+    state: &'static str,
+}
+
+/// Battery charge with low/critical colors, hidden on machines without a battery
+///
+/// Requires the `battery` cargo feature; without it the segment never renders.
+pub struct BatterySegment {
+    low_threshold: f64,
+    critical_threshold: f64,
+    low_color: AnsiColor,
+    critical_color: AnsiColor,
+    charging_icon: String,
+}
+
+impl Default for BatterySegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatterySegment {
+    pub fn new() -> Self {
+        Self {
+            low_threshold: 20.0,
+            critical_threshold: 10.0,
+            low_color: AnsiColor::Color16 { c16: 11 },
+            critical_color: AnsiColor::Color16 { c16: 9 },
+            charging_icon: "⚡".to_string(),
+        }
+    }
+
+    /// Percentages at or below which the charge turns the low/critical color
+    pub fn with_thresholds(mut self, low_threshold: f64, critical_threshold: f64) -> Self {
+        self.low_threshold = low_threshold;
+        self.critical_threshold = critical_threshold;
+        self
+    }
+
+    pub fn with_low_color(mut self, low_color: AnsiColor) -> Self {
+        self.low_color = low_color;
+        self
+    }
+
+    pub fn with_critical_color(mut self, critical_color: AnsiColor) -> Self {
+        self.critical_color = critical_color;
+        self
+    }
+
+    /// Marker shown after the percentage while charging, empty to hide it
+    pub fn with_charging_icon(mut self, charging_icon: &str) -> Self {
+        self.charging_icon = charging_icon.to_string();
+        self
+    }
+
+    fn severity(&self, reading: &BatteryReading) -> &'static str {
+        // A draining battery is the only case worth warning about
+        if reading.charging {
+            "normal"
+        } else if reading.percentage <= self.critical_threshold {
+            "critical"
+        } else if reading.percentage <= self.low_threshold {
+            "low"
+        } else {
+            "normal"
+        }
+    }
+
+    #[cfg(feature = "battery")]
+    fn read_battery() -> Option<BatteryReading> {
+        use battery::units::ratio::percent;
+        use battery::State;
+
+        let manager = battery::Manager::new().ok()?;
+        let battery = manager.batteries().ok()?.find_map(|b| b.ok())?;
+
+        let (charging, state) = match battery.state() {
+            State::Charging => (true, "charging"),
+            State::Discharging => (false, "discharging"),
+            State::Full => (true, "full"),
+            State::Empty => (false, "empty"),
+            _ => (false, "unknown"),
+        };
+
+        Some(BatteryReading {
+            percentage: f64::from(battery.state_of_charge().get::<percent>()).clamp(0.0, 100.0),
+            charging,
+            state,
+        })
+    }
+
+    #[cfg(not(feature = "battery"))]
+    fn read_battery() -> Option<BatteryReading> {
+        None
+    }
+}
+
+impl Segment for BatterySegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        // Desktops report no battery, so there is nothing to show
+        let Some(reading) = Self::read_battery() else {
+            super::skip_reason(if cfg!(feature = "battery") {
+                "no battery found"
+            } else {
+                "built without the battery feature"
+            });
+            return None;
+        };
+        let severity = self.severity(&reading);
+
+        let text = format!("{:.0}%", reading.percentage);
+        let primary = match severity {
+            "critical" => AnsiColorHelper::apply_foreground_color(&text, &self.critical_color),
+            "low" => AnsiColorHelper::apply_foreground_color(&text, &self.low_color),
+            _ => text,
+        };
+
+        let secondary = if reading.state == "charging" {
+            self.charging_icon.clone()
+        } else {
+            String::new()
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("percentage".to_string(), reading.percentage.to_string());
+        metadata.insert("state".to_string(), reading.state.to_string());
+        metadata.insert("charging".to_string(), reading.charging.to_string());
+        metadata.insert("severity".to_string(), severity.to_string());
+
+        Some(SegmentData {
+            primary,
+            secondary,
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Battery
+    }
+}
@@ -1,12 +1,20 @@
 use super::{Segment, SegmentData};
 use crate::config::{AnsiColor, InputData, SegmentId};
-use chrono::{DateTime, Utc};
+use crate::core::NetworkCache;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Bright black, used for the failure and stale-cache text
+const STALE_COLOR: AnsiColor = AnsiColor::Color16 { c16: 8 };
+
+/// Gradient endpoints, from the lowest remaining quota to the highest
+const GRADIENT_COLORS: [(u8, u8, u8); 3] = [(230, 70, 60), (230, 200, 50), (90, 200, 90)];
 
 /// CLI Proxy API Quota response structures
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 struct AuthFile {
     #[serde(rename = "type")]
@@ -70,6 +78,8 @@ struct GeminiQuotaResponse {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TrackedModel {
     Opus,
+    Sonnet,
+    Haiku,
     Gemini3Pro,
     Gemini3Flash,
 }
@@ -78,6 +88,8 @@ impl TrackedModel {
     pub fn alias_key(&self) -> &'static str {
         match self {
             Self::Opus => "opus_alias",
+            Self::Sonnet => "sonnet_alias",
+            Self::Haiku => "haiku_alias",
             Self::Gemini3Pro => "gemini3pro_alias",
             Self::Gemini3Flash => "gemini3flash_alias",
         }
@@ -86,14 +98,28 @@ impl TrackedModel {
     pub fn color_key(&self) -> &'static str {
         match self {
             Self::Opus => "opus_color",
+            Self::Sonnet => "sonnet_color",
+            Self::Haiku => "haiku_color",
             Self::Gemini3Pro => "gemini3pro_color",
             Self::Gemini3Flash => "gemini3flash_color",
         }
     }
 
+    pub fn percent_key(&self) -> &'static str {
+        match self {
+            Self::Opus => "opus_percent",
+            Self::Sonnet => "sonnet_percent",
+            Self::Haiku => "haiku_percent",
+            Self::Gemini3Pro => "gemini3pro_percent",
+            Self::Gemini3Flash => "gemini3flash_percent",
+        }
+    }
+
     pub fn default_alias(&self) -> &'static str {
         match self {
             Self::Opus => "opus",
+            Self::Sonnet => "sonnet",
+            Self::Haiku => "haiku",
             Self::Gemini3Pro => "3pro",
             Self::Gemini3Flash => "3flash",
         }
@@ -102,6 +128,8 @@ impl TrackedModel {
     pub fn default_color(&self) -> AnsiColor {
         match self {
             Self::Opus => AnsiColor::Color256 { c256: 214 },
+            Self::Sonnet => AnsiColor::Color256 { c256: 209 },
+            Self::Haiku => AnsiColor::Color256 { c256: 222 },
             Self::Gemini3Pro => AnsiColor::Color256 { c256: 129 },
             Self::Gemini3Flash => AnsiColor::Color256 { c256: 45 },
         }
@@ -110,13 +138,21 @@ impl TrackedModel {
     pub fn display_name(&self) -> &'static str {
         match self {
             Self::Opus => "Opus",
+            Self::Sonnet => "Sonnet",
+            Self::Haiku => "Haiku",
             Self::Gemini3Pro => "Gemini 3 Pro",
             Self::Gemini3Flash => "Gemini 3 Flash",
         }
     }
 
     pub fn all() -> &'static [TrackedModel] {
-        &[Self::Opus, Self::Gemini3Pro, Self::Gemini3Flash]
+        &[
+            Self::Opus,
+            Self::Sonnet,
+            Self::Haiku,
+            Self::Gemini3Pro,
+            Self::Gemini3Flash,
+        ]
     }
 }
 
@@ -124,7 +160,6 @@ impl TrackedModel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CliProxyApiQuotaCache {
     quotas: Vec<ModelQuota>,
-    cached_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,12 +170,335 @@ struct ModelQuota {
     auth_type: String,
 }
 
-#[derive(Default)]
-pub struct CliProxyApiQuotaSegment;
+/// User-defined entry of the `tracked_models` option
+#[derive(Debug, Deserialize)]
+struct TrackedModelOption {
+    /// Substring matched against the model id or display name
+    id: String,
+    alias: Option<String>,
+    color: Option<AnsiColor>,
+}
+
+/// A model the segment reports on, either built-in or from `tracked_models`
+struct QuotaTracker {
+    matcher: QuotaMatcher,
+    percent_key: String,
+    alias: String,
+    color: Option<AnsiColor>,
+}
+
+enum QuotaMatcher {
+    Builtin(TrackedModel),
+    Substring(String),
+}
+
+/// How the accounts of one tracked model combine into a single percentage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregation {
+    Avg,
+    Min,
+    Max,
+    /// Total across accounts, which can exceed 100%
+    Sum,
+}
+
+impl Aggregation {
+    /// The `aggregation` option, `avg` when unset or unrecognized
+    fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        match options.get("aggregation").and_then(|v| v.as_str()) {
+            Some("min") => Self::Min,
+            Some("max") => Self::Max,
+            Some("sum") => Self::Sum,
+            Some("avg") | None => Self::Avg,
+            Some(other) => {
+                log::debug!("quota: unknown aggregation {:?}, using avg", other);
+                Self::Avg
+            }
+        }
+    }
+
+    fn apply(&self, fractions: &[f64]) -> Option<f64> {
+        if fractions.is_empty() {
+            return None;
+        }
+        let sum: f64 = fractions.iter().sum();
+        Some(match self {
+            Self::Avg => sum / fractions.len() as f64,
+            Self::Min => fractions.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => fractions.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Sum => sum,
+        })
+    }
+}
+
+impl QuotaTracker {
+    fn matches(&self, quota: &ModelQuota) -> bool {
+        match &self.matcher {
+            QuotaMatcher::Builtin(model) => {
+                CliProxyApiQuotaSegment::tracked_model_for_quota(quota) == Some(*model)
+            }
+            QuotaMatcher::Substring(pattern) => {
+                let id = CliProxyApiQuotaSegment::normalize_model_text(&quota.model_id);
+                let name = CliProxyApiQuotaSegment::normalize_model_text(&quota.display_name);
+                id.contains(pattern.as_str()) || name.contains(pattern.as_str())
+            }
+        }
+    }
+}
+
+pub struct CliProxyApiQuotaSegment {
+    request_timeout: Option<Duration>,
+    max_retries: u32,
+    retry_backoff: Duration,
+    background: Option<AnsiColor>,
+    project_regex: Option<Regex>,
+    user_agent: Option<String>,
+    proxy_url: Option<String>,
+    tls_config: Option<Arc<rustls::ClientConfig>>,
+}
+
+impl Default for CliProxyApiQuotaSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CliProxyApiQuotaSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            request_timeout: None,
+            max_retries: 0,
+            retry_backoff: Duration::from_millis(200),
+            background: None,
+            project_regex: None,
+            user_agent: None,
+            proxy_url: None,
+            tls_config: None,
+        }
+    }
+
+    /// Segment background carried by the quota text itself, e.g. for powerline themes
+    pub fn with_background(mut self, background: Option<AnsiColor>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Regex tried first on gemini-cli auth file names, capturing the project in
+    /// a `project` group or else the first group
+    ///
+    /// An invalid pattern is logged and ignored.
+    pub fn with_project_regex(mut self, pattern: Option<&str>) -> Self {
+        self.project_regex = pattern.and_then(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::debug!("ignoring invalid project_regex {:?}: {}", pattern, e);
+                None
+            }
+        });
+        self
+    }
+
+    /// User-Agent sent with antigravity quota calls instead of the built-in one
+    ///
+    /// The API may answer an outdated User-Agent with no models at all.
+    pub fn with_user_agent(mut self, user_agent: Option<&str>) -> Self {
+        self.user_agent = user_agent
+            .map(str::trim)
+            .filter(|ua| !ua.is_empty())
+            .map(str::to_string);
+        self
+    }
+
+    /// Proxy for every quota request, e.g. `http://proxy:3128` or `socks5://host:1080`
+    ///
+    /// When unset, `HTTPS_PROXY`/`HTTP_PROXY` (matching the host's scheme) are used
+    /// unless `NO_PROXY` or a loopback host exempts it.
+    pub fn with_proxy_url(mut self, proxy_url: Option<&str>) -> Self {
+        self.proxy_url = proxy_url
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string);
+        self
+    }
+
+    /// PEM file of extra CA certificates trusted alongside the built-in roots
+    ///
+    /// A file that can't be read or holds no certificate is logged and ignored.
+    pub fn with_ca_cert_path(mut self, ca_cert_path: Option<&str>) -> Self {
+        self.tls_config = ca_cert_path
+            .filter(|path| !path.trim().is_empty())
+            .and_then(|path| match Self::load_tls_config(path) {
+                Ok(config) => Some(Arc::new(config)),
+                Err(e) => {
+                    log::debug!("ignoring ca_cert_path {:?}: {}", path, e);
+                    None
+                }
+            });
+        self
+    }
+
+    fn load_tls_config(path: &str) -> Result<rustls::ClientConfig, String> {
+        use rustls_pki_types::pem::PemObject;
+        use rustls_pki_types::CertificateDer;
+
+        let mut roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let mut added = 0;
+        for cert in CertificateDer::pem_file_iter(path).map_err(|e| e.to_string())? {
+            let cert = cert.map_err(|e| e.to_string())?;
+            roots.add(cert).map_err(|e| e.to_string())?;
+            added += 1;
+        }
+        if added == 0 {
+            return Err("no certificates found".to_string());
+        }
+
+        // Same provider and protocol versions ureq uses for its default config
+        rustls::ClientConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+            .with_protocol_versions(&[&rustls::version::TLS12, &rustls::version::TLS13])
+            .map_err(|e| e.to_string())
+            .map(|builder| builder.with_root_certificates(roots).with_no_client_auth())
+    }
+
+    /// Agent for requests to `host`, with the configured proxy and CA certificates
+    fn agent(&self, host: &str) -> ureq::Agent {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(proxy_url) = self.proxy_for(host) {
+            match ureq::Proxy::new(&proxy_url) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => log::debug!("ignoring invalid proxy {:?}: {}", proxy_url, e),
+            }
+        }
+        if let Some(tls_config) = &self.tls_config {
+            builder = builder.tls_config(tls_config.clone());
+        }
+        builder.build()
+    }
+
+    /// Proxy from `proxy_url` or the environment, `None` for a direct connection
+    fn proxy_for(&self, host: &str) -> Option<String> {
+        if let Some(proxy_url) = &self.proxy_url {
+            return Some(proxy_url.clone());
+        }
+
+        let (scheme, rest) = host.split_once("://").unwrap_or(("http", host));
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let hostname = match authority.rsplit_once('@') {
+            Some((_, hostname)) => hostname,
+            None => authority,
+        };
+        let hostname = if let Some(bracketed) = hostname.strip_prefix('[') {
+            bracketed.split(']').next().unwrap_or_default()
+        } else {
+            hostname.split(':').next().unwrap_or_default()
+        }
+        .to_lowercase();
+
+        // The management API usually runs locally, which a proxy can't reach
+        if Self::is_loopback(&hostname) || Self::bypasses_proxy(&hostname) {
+            return None;
+        }
+
+        if scheme.eq_ignore_ascii_case("https") {
+            Self::env_var(&["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"])
+        } else {
+            Self::env_var(&["HTTP_PROXY", "http_proxy"])
+        }
+    }
+
+    fn is_loopback(hostname: &str) -> bool {
+        hostname == "localhost"
+            || hostname
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+
+    /// Whether `NO_PROXY` lists the host, a parent domain of it, or `*`
+    fn bypasses_proxy(hostname: &str) -> bool {
+        let Some(no_proxy) = Self::env_var(&["NO_PROXY", "no_proxy"]) else {
+            return false;
+        };
+        no_proxy.split(',').any(|entry| {
+            let entry = entry.trim().trim_start_matches('.').to_lowercase();
+            entry == "*"
+                || (!entry.is_empty()
+                    && (hostname == entry || hostname.ends_with(&format!(".{}", entry))))
+        })
+    }
+
+    /// First of `names` set to a non-empty value
+    fn env_var(names: &[&str]) -> Option<String> {
+        names
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.trim().is_empty())
+    }
+
+    /// Segment configured from its `timeout_ms`, `max_retries`, `retry_backoff_ms`,
+    /// `project_regex`, `user_agent`, `proxy_url` and `ca_cert_path` options
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let timeout_ms = options.get("timeout_ms").and_then(|v| v.as_u64());
+        let max_retries = options
+            .get("max_retries")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let retry_backoff_ms = options
+            .get("retry_backoff_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(200);
+
+        Self::new()
+            .with_timeout_ms(timeout_ms)
+            .with_retries(max_retries as u32, retry_backoff_ms)
+            .with_project_regex(options.get("project_regex").and_then(|v| v.as_str()))
+            .with_user_agent(options.get("user_agent").and_then(|v| v.as_str()))
+            .with_proxy_url(options.get("proxy_url").and_then(|v| v.as_str()))
+            .with_ca_cert_path(options.get("ca_cert_path").and_then(|v| v.as_str()))
+    }
+
+    /// Retry network errors and 5xx responses, doubling the backoff after each attempt
+    pub fn with_retries(mut self, max_retries: u32, retry_backoff_ms: u64) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = Duration::from_millis(retry_backoff_ms);
+        self
+    }
+
+    /// Send a request, retrying transient failures up to `max_retries` times
+    fn send_with_retry<F>(&self, mut send: F) -> Option<ureq::Response>
+    where
+        F: FnMut() -> Result<ureq::Response, Box<ureq::Error>>,
+    {
+        let mut backoff = self.retry_backoff;
+        for attempt in 0..=self.max_retries {
+            let retryable = match send().map_err(|e| *e) {
+                Ok(response) => return Some(response),
+                Err(ureq::Error::Status(code, response)) => {
+                    log::debug!(
+                        "quota request to {} failed: HTTP {}",
+                        response.get_url(),
+                        code
+                    );
+                    code >= 500
+                }
+                Err(ureq::Error::Transport(e)) => {
+                    log::debug!("quota request failed: {}", e);
+                    true
+                }
+            };
+            if !retryable || attempt == self.max_retries {
+                break;
+            }
+            std::thread::sleep(backoff);
+            backoff = backoff.saturating_mul(2);
+        }
+        None
+    }
+
+    /// Cap each HTTP request instead of the built-in 5s/10s timeouts
+    pub fn with_timeout_ms(mut self, timeout_ms: Option<u64>) -> Self {
+        self.request_timeout = timeout_ms.map(Duration::from_millis);
+        self
     }
 
     fn antigravity_user_agent() -> String {
@@ -178,6 +536,12 @@ impl CliProxyApiQuotaSegment {
         if id.contains("opus") || name.contains("opus") {
             return Some(TrackedModel::Opus);
         }
+        if id.contains("sonnet") || name.contains("sonnet") {
+            return Some(TrackedModel::Sonnet);
+        }
+        if id.contains("haiku") || name.contains("haiku") {
+            return Some(TrackedModel::Haiku);
+        }
         if id.contains("gemini-3-pro") || name.contains("gemini 3 pro") {
             return Some(TrackedModel::Gemini3Pro);
         }
@@ -192,7 +556,11 @@ impl CliProxyApiQuotaSegment {
         Self::tracked_model_for(&quota.model_id, &quota.display_name)
     }
 
-    fn get_alias(&self, options: &HashMap<String, serde_json::Value>, model: TrackedModel) -> String {
+    fn get_alias(
+        &self,
+        options: &HashMap<String, serde_json::Value>,
+        model: TrackedModel,
+    ) -> String {
         options
             .get(model.alias_key())
             .and_then(|v| v.as_str())
@@ -200,7 +568,11 @@ impl CliProxyApiQuotaSegment {
             .to_string()
     }
 
-    fn get_color(&self, options: &HashMap<String, serde_json::Value>, model: TrackedModel) -> AnsiColor {
+    fn get_color(
+        &self,
+        options: &HashMap<String, serde_json::Value>,
+        model: TrackedModel,
+    ) -> AnsiColor {
         options
             .get(model.color_key())
             .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
@@ -209,7 +581,11 @@ impl CliProxyApiQuotaSegment {
 
     /// Apply ANSI foreground color to text (resets only foreground, keeps background)
     pub fn apply_foreground_color(text: &str, color: &AnsiColor) -> String {
-        let prefix = match color {
+        if !crate::core::color_enabled() {
+            return text.to_string();
+        }
+
+        let prefix = match &color.downgrade(crate::core::color_depth()) {
             AnsiColor::Color16 { c16 } => {
                 let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
                 format!("\x1b[{}m", code)
@@ -221,111 +597,260 @@ impl CliProxyApiQuotaSegment {
         format!("{}{}\x1b[39m", prefix, text)
     }
 
+    /// Apply ANSI background color to text (resets only background, keeps foreground)
+    pub fn apply_background_color(text: &str, color: &AnsiColor) -> String {
+        if !crate::core::color_enabled() {
+            return text.to_string();
+        }
+
+        let prefix = match &color.downgrade(crate::core::color_depth()) {
+            AnsiColor::Color16 { c16 } => {
+                let code = if *c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
+                format!("\x1b[{}m", code)
+            }
+            AnsiColor::Color256 { c256 } => format!("\x1b[48;5;{}m", c256),
+            AnsiColor::Rgb { r, g, b } => format!("\x1b[48;2;{};{};{}m", r, g, b),
+        };
+        // Use 49m so the reset can't clear a foreground set around the text
+        format!("{}{}\x1b[49m", prefix, text)
+    }
+
+    /// Apply optional foreground and background colors, each reset on its own
+    pub fn apply_colors(
+        text: &str,
+        foreground: Option<&AnsiColor>,
+        background: Option<&AnsiColor>,
+    ) -> String {
+        let text = match foreground {
+            Some(color) => Self::apply_foreground_color(text, color),
+            None => text.to_string(),
+        };
+        match background {
+            Some(color) => Self::apply_background_color(&text, color),
+            None => text,
+        }
+    }
+
+    /// Models to report on: the `tracked_models` option, or the built-in list when absent
+    fn get_trackers(&self, options: &HashMap<String, serde_json::Value>) -> Vec<QuotaTracker> {
+        let custom: Option<Vec<TrackedModelOption>> = options
+            .get("tracked_models")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+        match custom {
+            Some(entries) => entries
+                .into_iter()
+                .filter(|entry| !entry.id.trim().is_empty())
+                .map(|entry| {
+                    let pattern = Self::normalize_model_text(&entry.id);
+                    QuotaTracker {
+                        percent_key: format!("{}_percent", pattern),
+                        alias: entry.alias.unwrap_or_else(|| entry.id.clone()),
+                        color: entry.color,
+                        matcher: QuotaMatcher::Substring(pattern),
+                    }
+                })
+                .collect(),
+            None => TrackedModel::all()
+                .iter()
+                .map(|&model| QuotaTracker {
+                    matcher: QuotaMatcher::Builtin(model),
+                    percent_key: model.percent_key().to_string(),
+                    alias: self.get_alias(options, model),
+                    color: Some(self.get_color(options, model)),
+                })
+                .collect(),
+        }
+    }
+
+    /// Bucket boundaries (ascending percents) and one label per resulting level
+    fn get_levels(options: &HashMap<String, serde_json::Value>) -> (Vec<u8>, Vec<String>) {
+        let mut boundaries: Vec<u8> = options
+            .get("level_boundaries")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_u64())
+                    .map(|v| v.min(100) as u8)
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![34, 67]);
+        boundaries.sort_unstable();
+
+        let labels: Vec<String> = options
+            .get("level_labels")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Labels must cover every level, otherwise fall back to the dot meter
+        let labels = if labels.len() == boundaries.len() + 1 {
+            labels
+        } else {
+            (0..=boundaries.len())
+                .map(|filled| {
+                    let mut meter = "●".repeat(filled + 1);
+                    meter.push_str(&"○".repeat(boundaries.len() - filled));
+                    meter
+                })
+                .collect()
+        };
+
+        (boundaries, labels)
+    }
+
+    /// Percents at which the gradient is fully red, yellow and green
+    ///
+    /// Anything other than three values falls back to `[20, 50, 80]`.
+    fn get_gradient_breakpoints(options: &HashMap<String, serde_json::Value>) -> [u8; 3] {
+        let mut breakpoints: Vec<u8> = options
+            .get("gradient_breakpoints")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_u64())
+                    .map(|v| v.min(100) as u8)
+                    .collect()
+            })
+            .unwrap_or_default();
+        breakpoints.sort_unstable();
+
+        breakpoints.try_into().unwrap_or([20, 50, 80])
+    }
+
+    /// Color for a percentage, interpolated between the neighbouring breakpoints
+    fn gradient_color(percent: u8, breakpoints: &[u8; 3]) -> AnsiColor {
+        let segment = if percent < breakpoints[1] { 0 } else { 1 };
+        let (low, high) = (breakpoints[segment], breakpoints[segment + 1]);
+        let t = if high > low {
+            (percent.clamp(low, high) - low) as f64 / (high - low) as f64
+        } else if percent >= high {
+            1.0
+        } else {
+            0.0
+        };
+
+        let (from, to) = (GRADIENT_COLORS[segment], GRADIENT_COLORS[segment + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        AnsiColor::Rgb {
+            r: mix(from.0, to.0),
+            g: mix(from.1, to.1),
+            b: mix(from.2, to.2),
+        }
+    }
+
+    /// Level index for a percentage; a value equal to a boundary lands in the upper bucket
+    fn quota_level(percent: u8, boundaries: &[u8]) -> usize {
+        boundaries.iter().filter(|b| percent >= **b).count()
+    }
+
     fn format_tracked_output(
         &self,
         quotas: &[ModelQuota],
         options: &HashMap<String, serde_json::Value>,
         separator: &str,
-    ) -> String {
-        #[derive(Default)]
-        struct SumCount {
-            sum: f64,
-            count: u32,
-        }
-
-        let mut agg: HashMap<TrackedModel, SumCount> = HashMap::new();
+    ) -> (String, HashMap<String, String>) {
+        // Each quota counts toward the first tracker that matches it
+        let trackers = self.get_trackers(options);
+        let mut fractions: Vec<Vec<f64>> = trackers.iter().map(|_| Vec::new()).collect();
         for quota in quotas {
-            let Some(model) = Self::tracked_model_for_quota(quota) else {
+            let Some(index) = trackers.iter().position(|t| t.matches(quota)) else {
                 continue;
             };
-            let entry = agg.entry(model).or_default();
-            entry.sum += quota.remaining_fraction;
-            entry.count += 1;
+            fractions[index].push(quota.remaining_fraction);
         }
+        let aggregation = Aggregation::from_options(options);
+
+        let level_display = options.get("display").and_then(|v| v.as_str()) == Some("level");
+        let (boundaries, labels) = Self::get_levels(options);
+        let gradient = options
+            .get("color_gradient")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+            .then(|| Self::get_gradient_breakpoints(options));
+        // Only quotas inside [min_percent, max_percent] are rendered
+        let min_percent = options
+            .get("min_percent")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let max_percent = options
+            .get("max_percent")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(u64::MAX);
 
         let mut parts = Vec::new();
-        for model in [
-            TrackedModel::Opus,
-            TrackedModel::Gemini3Pro,
-            TrackedModel::Gemini3Flash,
-        ] {
-            let Some(entry) = agg.get(&model) else {
+        let mut percents = HashMap::new();
+        // Render in tracker order so output doesn't depend on API ordering
+        for (tracker, fractions) in trackers.iter().zip(&fractions) {
+            let Some(fraction) = aggregation.apply(fractions) else {
                 continue;
             };
-            if entry.count == 0 {
+
+            let percent = (fraction * 100.0).round().max(0.0) as u64;
+            percents.insert(tracker.percent_key.clone(), percent.to_string());
+            if percent < min_percent || percent > max_percent {
                 continue;
             }
 
-            let avg = entry.sum / entry.count as f64;
-            let percent = (avg * 100.0).round().clamp(0.0, 100.0) as u8;
-            let alias = self.get_alias(options, model);
-            let color = self.get_color(options, model);
-            let label = format!("{}:{}%", alias, percent);
-            parts.push(Self::apply_foreground_color(&label, &color));
-        }
-
-        parts.join(separator)
-    }
-
-    fn get_cache_path() -> Option<std::path::PathBuf> {
-        let home = dirs::home_dir()?;
-        Some(
-            home.join(".claude")
-                .join("ccline")
-                .join(".cli_proxy_api_quota_cache.json"),
-        )
-    }
-
-    fn load_cache(&self) -> Option<CliProxyApiQuotaCache> {
-        let cache_path = Self::get_cache_path()?;
-        if !cache_path.exists() {
-            return None;
-        }
-
-        let content = std::fs::read_to_string(&cache_path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
-
-    fn save_cache(&self, cache: &CliProxyApiQuotaCache) {
-        if let Some(cache_path) = Self::get_cache_path() {
-            if let Some(parent) = cache_path.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-            if let Ok(json) = serde_json::to_string_pretty(cache) {
-                let _ = std::fs::write(&cache_path, json);
-            }
+            // Levels and the gradient top out at a full quota
+            let capped = percent.min(100) as u8;
+            let label = if level_display {
+                let level = Self::quota_level(capped, &boundaries);
+                format!("{}:{}", tracker.alias, labels[level])
+            } else {
+                format!("{}:{}%", tracker.alias, percent)
+            };
+            // The gradient replaces the per-model color so a low quota stands out
+            let color = match &gradient {
+                Some(breakpoints) => Some(Self::gradient_color(capped, breakpoints)),
+                None => tracker.color.clone(),
+            };
+            parts.push(match &color {
+                Some(color) => Self::apply_foreground_color(&label, color),
+                None => label,
+            });
         }
-    }
 
-    fn is_cache_valid(&self, cache: &CliProxyApiQuotaCache, cache_duration: u64) -> bool {
-        if let Ok(cached_at) = DateTime::parse_from_rfc3339(&cache.cached_at) {
-            let now = Utc::now();
-            let elapsed = now.signed_duration_since(cached_at.with_timezone(&Utc));
-            elapsed.num_seconds() < cache_duration as i64
-        } else {
-            false
-        }
+        (parts.join(separator), percents)
     }
 
     fn get_auth_files(&self, host: &str, key: &str) -> Option<Vec<AuthFile>> {
         let url = format!("{}/v0/management/auth-files", host);
 
-        let agent = ureq::AgentBuilder::new().build();
-        let response = agent
-            .get(&url)
-            .set("Authorization", &format!("Bearer {}", key))
-            .timeout(std::time::Duration::from_secs(5))
-            .call()
-            .ok()?;
+        let agent = self.agent(host);
+        let response = self.send_with_retry(|| {
+            agent
+                .get(&url)
+                .set("Authorization", &format!("Bearer {}", key))
+                .timeout(self.request_timeout.unwrap_or(Duration::from_secs(5)))
+                .call()
+                .map_err(Box::new)
+        })?;
 
         if response.status() == 200 {
-            let resp: AuthFilesResponse = response.into_json().ok()?;
-            Some(resp.files)
+            match response.into_json::<AuthFilesResponse>() {
+                Ok(resp) => Some(resp.files),
+                Err(e) => {
+                    log::debug!("invalid auth-files response from {}: {}", url, e);
+                    None
+                }
+            }
         } else {
+            log::debug!(
+                "auth-files request to {} returned HTTP {}",
+                url,
+                response.status()
+            );
             None
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn api_call(
         &self,
         host: &str,
@@ -353,25 +878,38 @@ impl CliProxyApiQuotaSegment {
             "data": data
         });
 
-        let agent = ureq::AgentBuilder::new().build();
-        let response = agent
-            .post(&api_url)
-            .set("Authorization", &format!("Bearer {}", key))
-            .set("Content-Type", "application/json")
-            .timeout(std::time::Duration::from_secs(10))
-            .send_json(&payload)
-            .ok()?;
+        let agent = self.agent(host);
+        let response = self.send_with_retry(|| {
+            agent
+                .post(&api_url)
+                .set("Authorization", &format!("Bearer {}", key))
+                .set("Content-Type", "application/json")
+                .timeout(self.request_timeout.unwrap_or(Duration::from_secs(10)))
+                .send_json(&payload)
+                .map_err(Box::new)
+        })?;
 
         if response.status() == 200 {
-            response.into_json().ok()
+            match response.into_json() {
+                Ok(resp) => Some(resp),
+                Err(e) => {
+                    log::debug!("invalid api-call response for {}: {}", url, e);
+                    None
+                }
+            }
         } else {
+            log::debug!("api-call for {} returned HTTP {}", url, response.status());
             None
         }
     }
 
     fn get_antigravity_quota(&self, host: &str, key: &str, auth_index: &str) -> Vec<ModelQuota> {
         let mut extra_headers = HashMap::new();
-        extra_headers.insert("User-Agent".to_string(), Self::antigravity_user_agent());
+        let user_agent = self
+            .user_agent
+            .clone()
+            .unwrap_or_else(Self::antigravity_user_agent);
+        extra_headers.insert("User-Agent".to_string(), user_agent);
 
         let result = self.api_call(
             host,
@@ -387,7 +925,11 @@ impl CliProxyApiQuotaSegment {
 
         if let Some(response) = result {
             if let Some(body) = response.body {
-                if let Ok(models_resp) = serde_json::from_str::<AntigravityModelsResponse>(&body) {
+                let parsed = serde_json::from_str::<AntigravityModelsResponse>(&body);
+                if let Err(e) = &parsed {
+                    log::debug!("cannot parse antigravity models for {}: {}", auth_index, e);
+                }
+                if let Ok(models_resp) = parsed {
                     if let Some(models) = models_resp.models {
                         for (model_id, model_info) in models {
                             if let Some(quota_info) = model_info.quota_info {
@@ -397,10 +939,7 @@ impl CliProxyApiQuotaSegment {
                                         .clone()
                                         .unwrap_or_else(|| model_id.clone());
 
-                                    // Only keep Opus / Gemini 3 Pro / Gemini 3 Flash
-                                    if Self::tracked_model_for(&model_id, &display_name).is_none() {
-                                        continue;
-                                    }
+                                    // Keep every model, filtering happens per tracker at render time
 
                                     quotas.push(ModelQuota {
                                         model_id: model_id.clone(),
@@ -419,18 +958,65 @@ impl CliProxyApiQuotaSegment {
         quotas
     }
 
-    fn extract_project_from_name(&self, name: &str) -> Option<String> {
-        // gemini-gaakki@gmail.com-airy-lodge-481706-r3.json -> airy-lodge-481706-r3
-        let name = name.replace(".json", "");
-        let parts: Vec<&str> = name.split('-').collect();
-        if parts.len() >= 4 {
-            for (i, part) in parts.iter().enumerate() {
-                if part.contains('@') {
-                    return Some(parts[i + 1..].join("-"));
-                }
-            }
+    /// Google Cloud project for a gemini-cli account
+    ///
+    /// Tried in order: `project_regex`, the `<email>-<project>.json` file name written by
+    /// CLI Proxy API, then the `project_id` stored inside the auth file itself.
+    fn resolve_gemini_project(&self, host: &str, key: &str, name: &str) -> Option<String> {
+        let project = self
+            .project_from_regex(name)
+            .or_else(|| Self::extract_project_from_name(name))
+            .or_else(|| self.project_from_auth_file(host, key, name));
+        if project.is_none() {
+            log::debug!("no project found for gemini-cli auth file {:?}", name);
         }
-        None
+        project
+    }
+
+    fn project_from_regex(&self, name: &str) -> Option<String> {
+        let captures = self.project_regex.as_ref()?.captures(name)?;
+        captures
+            .name("project")
+            .or_else(|| captures.get(1))
+            .map(|m| m.as_str().to_string())
+            .filter(|project| !project.is_empty())
+    }
+
+    fn extract_project_from_name(name: &str) -> Option<String> {
+        // gemini-gaakki@gmail.com-airy-lodge-481706-r3.json -> airy-lodge-481706-r3
+        let name = name.strip_suffix(".json").unwrap_or(name);
+        let (_, domain_and_project) = name.split_once('@')?;
+        // The domain ends at the first `-` after one of its dots, so hyphenated
+        // domains such as `my-corp.com` keep their hyphen
+        let dot = domain_and_project.find('.')?;
+        let dash = dot + domain_and_project[dot..].find('-')?;
+        Some(domain_and_project[dash + 1..].to_string()).filter(|project| !project.is_empty())
+    }
+
+    /// `project_id` from the auth file contents, for names that don't embed it
+    fn project_from_auth_file(&self, host: &str, key: &str, name: &str) -> Option<String> {
+        let url = format!("{}/v0/management/auth-files/download", host);
+
+        let agent = self.agent(host);
+        let response = self.send_with_retry(|| {
+            agent
+                .get(&url)
+                .query("name", name)
+                .set("Authorization", &format!("Bearer {}", key))
+                .timeout(self.request_timeout.unwrap_or(Duration::from_secs(5)))
+                .call()
+                .map_err(Box::new)
+        })?;
+
+        let content: serde_json::Value = response
+            .into_json()
+            .map_err(|e| log::debug!("invalid auth file {:?}: {}", name, e))
+            .ok()?;
+        ["project_id", "project"]
+            .iter()
+            .find_map(|field| content.get(field).and_then(|v| v.as_str()))
+            .filter(|project| !project.is_empty())
+            .map(|project| project.to_string())
     }
 
     fn get_gemini_cli_quota(
@@ -456,16 +1042,17 @@ impl CliProxyApiQuotaSegment {
 
         if let Some(response) = result {
             if let Some(body) = response.body {
-                if let Ok(quota_resp) = serde_json::from_str::<GeminiQuotaResponse>(&body) {
+                let parsed = serde_json::from_str::<GeminiQuotaResponse>(&body);
+                if let Err(e) = &parsed {
+                    log::debug!("cannot parse gemini-cli quota for {}: {}", auth_index, e);
+                }
+                if let Ok(quota_resp) = parsed {
                     if let Some(buckets) = quota_resp.buckets {
                         for bucket in buckets {
                             if let (Some(model_id), Some(remaining)) =
                                 (bucket.model_id, bucket.remaining_fraction)
                             {
-                                // Only keep Opus / Gemini 3 Pro / Gemini 3 Flash
-                                if Self::tracked_model_for(&model_id, &model_id).is_none() {
-                                    continue;
-                                }
+                                // Keep every model, filtering happens per tracker at render time
 
                                 quotas.push(ModelQuota {
                                     model_id: model_id.clone(),
@@ -505,15 +1092,18 @@ impl CliProxyApiQuotaSegment {
             let quotas = match file.auth_type.as_str() {
                 "antigravity" => self.get_antigravity_quota(host, key, &file.auth_index),
                 "gemini-cli" => {
-                    if let Some(project) =
-                        self.extract_project_from_name(file.name.as_deref().unwrap_or(""))
+                    match self.resolve_gemini_project(host, key, file.name.as_deref().unwrap_or(""))
                     {
-                        self.get_gemini_cli_quota(host, key, &file.auth_index, &project)
-                    } else {
-                        Vec::new()
+                        Some(project) => {
+                            self.get_gemini_cli_quota(host, key, &file.auth_index, &project)
+                        }
+                        None => Vec::new(),
                     }
                 }
-                _ => Vec::new(),
+                other => {
+                    log::debug!("unsupported auth type {:?}, skipping", other);
+                    Vec::new()
+                }
             };
 
             all_quotas.extend(quotas);
@@ -527,7 +1117,10 @@ impl Segment for CliProxyApiQuotaSegment {
     fn collect(&self, _input: &InputData) -> Option<SegmentData> {
         // This method loads config from disk - use collect_with_options for better performance
         let config = crate::config::Config::load().ok()?;
-        let segment_config = config.segments.iter().find(|s| s.id == SegmentId::CliProxyApiQuota)?;
+        let segment_config = config
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::CliProxyApiQuota)?;
         self.collect_with_options(&segment_config.options)
     }
 
@@ -538,77 +1131,71 @@ impl Segment for CliProxyApiQuotaSegment {
 
 impl CliProxyApiQuotaSegment {
     /// Collect quota data using provided options (avoids loading config from disk)
-    pub fn collect_with_options(&self, options: &HashMap<String, serde_json::Value>) -> Option<SegmentData> {
-        let host = options
-            .get("host")
-            .and_then(|v| v.as_str())
-            .unwrap_or("http://localhost:8317");
-
-        let key = options
-            .get("key")
-            .and_then(|v| v.as_str())
-            .unwrap_or("nbkey");
+    pub fn collect_with_options(
+        &self,
+        options: &HashMap<String, serde_json::Value>,
+    ) -> Option<SegmentData> {
+        let (host, key, auth_type) = Self::endpoint_options(options);
 
         let cache_duration = options
             .get("cache_duration")
             .and_then(|v| v.as_u64())
             .unwrap_or(180);
 
-        let auth_type = options
-            .get("auth_type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("all");
-
         let separator = options
             .get("separator")
             .and_then(|v| v.as_str())
             .unwrap_or(" | ");
 
-        // Try to use cache first
-        let cached_data = self.load_cache();
-        let use_cached = cached_data
-            .as_ref()
-            .map(|cache| self.is_cache_valid(cache, cache_duration))
-            .unwrap_or(false);
+        let stale_symbol = options
+            .get("stale_symbol")
+            .and_then(|v| v.as_str())
+            .unwrap_or("⧖");
 
-        let (quotas, fetch_failed, using_stale_cache) = if use_cached {
-            (cached_data.unwrap().quotas, false, false)
-        } else {
-            let fetched = self.fetch_all_quotas(host, key, auth_type);
-            if !fetched.is_empty() {
-                let cache = CliProxyApiQuotaCache {
-                    quotas: fetched.clone(),
-                    cached_at: Utc::now().to_rfc3339(),
-                };
-                self.save_cache(&cache);
-                (fetched, false, false)
-            } else if let Some(cache) = cached_data {
-                // Fetch failed, fall back to stale cache
-                (cache.quotas, true, true)
-            } else {
-                // Fetch failed and no cache available
-                (Vec::new(), true, false)
-            }
-        };
+        let failure_threshold = options
+            .get("failure_threshold")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(3) as u32;
+        let failure_cooldown = options
+            .get("failure_cooldown")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(60);
+
+        let cache = NetworkCache::new(".cli_proxy_api_quota_cache.json", cache_duration)
+            .with_circuit_breaker(failure_threshold, failure_cooldown);
+        let cached = cache.get_or_fetch(|| {
+            let quotas = self.fetch_all_quotas(host, key, auth_type);
+            (!quotas.is_empty()).then_some(CliProxyApiQuotaCache { quotas })
+        });
 
         // If fetch failed and no data available, show error message
-        if fetch_failed && quotas.is_empty() {
-            let mut metadata = HashMap::new();
-            metadata.insert("raw_text".to_string(), "true".to_string());
+        let Some(cached) = cached else {
             return Some(SegmentData {
-                primary: "\x1b[90m获取额度失败\x1b[0m".to_string(),
+                primary: Self::apply_colors(
+                    "获取额度失败",
+                    Some(&STALE_COLOR),
+                    self.background.as_ref(),
+                ),
                 secondary: String::new(),
-                metadata,
+                metadata: HashMap::new(),
+                raw: true,
             });
-        }
+        };
+        let quotas = cached.value.quotas;
+        let using_stale_cache = cached.stale;
 
         if quotas.is_empty() {
+            super::skip_reason("no quotas returned");
             return None;
         }
 
-        let primary = self.format_tracked_output(&quotas, options, separator);
+        let (primary, percents) = self.format_tracked_output(&quotas, options, separator);
 
         if primary.is_empty() {
+            super::skip_reason(format!(
+                "none of {} quotas matched a tracked model in range",
+                quotas.len()
+            ));
             return None;
         }
 
@@ -617,21 +1204,326 @@ impl CliProxyApiQuotaSegment {
             // Remove all ANSI color codes and apply gray with prefix
             let ansi_regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
             let plain_text = ansi_regex.replace_all(&primary, "");
-            format!("\x1b[90m获取额度失败:{}\x1b[0m", plain_text)
+            let text = if stale_symbol.is_empty() {
+                format!("获取额度失败:{}", plain_text)
+            } else {
+                format!("获取额度失败:{} {}", plain_text, stale_symbol)
+            };
+            Self::apply_foreground_color(&text, &STALE_COLOR)
         } else {
             primary
         };
+        // Full resets would end the segment background mid-text, so only fg/bg resets are used
+        let display_primary = Self::apply_colors(&display_primary, None, self.background.as_ref());
 
-        let mut metadata = HashMap::new();
-        metadata.insert("raw_text".to_string(), "true".to_string());
-        if using_stale_cache {
-            metadata.insert("stale_cache".to_string(), "true".to_string());
-        }
+        // Exact percentages stay available even when rendering coarse levels
+        let mut metadata = percents;
+        metadata.insert("stale".to_string(), using_stale_cache.to_string());
 
         Some(SegmentData {
             primary: display_primary,
             secondary: String::new(),
             metadata,
+            raw: true,
         })
     }
+
+    /// Host, management key and auth type filter from the segment options
+    fn endpoint_options(options: &HashMap<String, serde_json::Value>) -> (&str, &str, &str) {
+        let host = options
+            .get("host")
+            .and_then(|v| v.as_str())
+            .unwrap_or("http://localhost:8317");
+
+        let key = options
+            .get("key")
+            .and_then(|v| v.as_str())
+            .unwrap_or("nbkey");
+
+        let auth_type = options
+            .get("auth_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("all");
+
+        (host, key, auth_type)
+    }
+
+    /// Auth files and parsed quotas straight from the API, bypassing the cache
+    ///
+    /// Each quota carries `tracked_as`, the alias it renders under or `null` when no
+    /// tracked model matches it.
+    pub fn dump(&self, options: &HashMap<String, serde_json::Value>) -> serde_json::Value {
+        let (host, key, auth_type) = Self::endpoint_options(options);
+        let auth_files = self.get_auth_files(host, key);
+        let trackers = self.get_trackers(options);
+
+        let quotas: Vec<serde_json::Value> = self
+            .fetch_all_quotas(host, key, auth_type)
+            .iter()
+            .map(|quota| {
+                let mut value = serde_json::to_value(quota).unwrap_or_default();
+                let tracked_as = trackers.iter().find(|t| t.matches(quota)).map(|t| &t.alias);
+                if let Some(object) = value.as_object_mut() {
+                    object.insert("tracked_as".to_string(), serde_json::json!(tracked_as));
+                }
+                value
+            })
+            .collect();
+
+        serde_json::json!({
+            "host": host,
+            "auth_type": auth_type,
+            "auth_files": auth_files,
+            "quotas": quotas,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(value: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn quota(model_id: &str, remaining_fraction: f64) -> ModelQuota {
+        ModelQuota {
+            model_id: model_id.to_string(),
+            display_name: model_id.to_string(),
+            remaining_fraction,
+            auth_type: "antigravity".to_string(),
+        }
+    }
+
+    #[test]
+    fn level_boundaries_belong_to_the_upper_bucket() {
+        let (boundaries, _) = CliProxyApiQuotaSegment::get_levels(&HashMap::new());
+        assert_eq!(boundaries, [34, 67]);
+
+        let levels: Vec<_> = [0, 33, 34, 35, 66, 67, 68, 100]
+            .iter()
+            .map(|&percent| CliProxyApiQuotaSegment::quota_level(percent, &boundaries))
+            .collect();
+        assert_eq!(levels, [0, 0, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn custom_boundaries_are_sorted_and_capped() {
+        let options = options(serde_json::json!({
+            "level_boundaries": [80, 20, 150],
+            "level_labels": ["low", "mid", "high", "full"],
+        }));
+        let (boundaries, labels) = CliProxyApiQuotaSegment::get_levels(&options);
+        assert_eq!(boundaries, [20, 80, 100]);
+        assert_eq!(labels, ["low", "mid", "high", "full"]);
+
+        let levels: Vec<_> = [19, 20, 21, 79, 80, 81, 99, 100]
+            .iter()
+            .map(|&percent| CliProxyApiQuotaSegment::quota_level(percent, &boundaries))
+            .collect();
+        assert_eq!(levels, [0, 1, 1, 1, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    fn labels_not_covering_every_level_fall_back_to_the_meter() {
+        let options = options(serde_json::json!({ "level_labels": ["low", "high"] }));
+        let (_, labels) = CliProxyApiQuotaSegment::get_levels(&options);
+        assert_eq!(labels, ["●○○", "●●○", "●●●"]);
+    }
+
+    #[test]
+    fn level_display_uses_the_rounded_percent() {
+        let options = options(serde_json::json!({
+            "display": "level",
+            "level_labels": ["low", "mid", "high"],
+            "tracked_models": [{ "id": "m", "alias": "m" }],
+        }));
+        let segment = CliProxyApiQuotaSegment::new();
+
+        for (fraction, expected) in [
+            (0.0, "m:low"),
+            (0.33, "m:low"),
+            (0.334, "m:low"),
+            (0.336, "m:mid"),
+            (0.34, "m:mid"),
+            (0.66, "m:mid"),
+            (0.67, "m:high"),
+            (1.0, "m:high"),
+            // Sum aggregation can exceed a full quota, which stays the top level
+            (1.5, "m:high"),
+        ] {
+            let (output, _) = segment.format_tracked_output(&[quota("m", fraction)], &options, " ");
+            assert_eq!(output, expected, "remaining fraction {}", fraction);
+        }
+    }
+
+    /// HTTP server answering one connection per scripted status, 0 dropping it unanswered
+    ///
+    /// Returns the base URL and the number of requests received so far.
+    fn mock_server(script: Vec<u16>) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        mock_server_with_body(script, r#"{"files":[]}"#)
+    }
+
+    /// [`mock_server`] answering its 200s with `body`
+    fn mock_server_with_body(
+        script: Vec<u16>,
+        body: &'static str,
+    ) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let counter = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for (status, stream) in script.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+                if status != 0 {
+                    let body = if status == 200 { body } else { "" };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} Mock\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                }
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn get(segment: &CliProxyApiQuotaSegment, url: &str) -> Option<ureq::Response> {
+        let agent = ureq::AgentBuilder::new().build();
+        segment.send_with_retry(|| {
+            agent
+                .get(url)
+                .timeout(Duration::from_secs(5))
+                .call()
+                .map_err(Box::new)
+        })
+    }
+
+    #[test]
+    fn transient_failures_are_retried_until_success() {
+        // A dropped connection and a 503, then the request goes through
+        let (url, requests) = mock_server(vec![0, 503, 200]);
+        let segment = CliProxyApiQuotaSegment::new().with_retries(2, 20);
+
+        let started_at = std::time::Instant::now();
+        let response = get(&segment, &url).expect("third attempt succeeds");
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 3);
+        // 20 ms, then the doubled 40 ms
+        assert!(started_at.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn retries_stop_at_max_retries() {
+        let (url, requests) = mock_server(vec![503, 503, 200]);
+        let segment = CliProxyApiQuotaSegment::new().with_retries(1, 1);
+
+        assert!(get(&segment, &url).is_none());
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let (url, requests) = mock_server(vec![401, 200]);
+        let segment = CliProxyApiQuotaSegment::new().with_retries(3, 1);
+
+        assert!(get(&segment, &url).is_none());
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn auth_files_request_goes_through_the_retries() {
+        let (url, requests) = mock_server(vec![502, 500, 200]);
+        // Loopback hosts never go through a proxy
+        let segment = CliProxyApiQuotaSegment::new().with_retries(2, 1);
+
+        let files = segment.get_auth_files(&url, "key");
+        assert_eq!(files.map(|files| files.len()), Some(0));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn project_is_taken_from_common_file_name_shapes() {
+        for (name, project) in [
+            (
+                "gemini-gaakki@gmail.com-airy-lodge-481706-r3.json",
+                Some("airy-lodge-481706-r3"),
+            ),
+            ("gemini-dev@my-corp.com-build-123.json", Some("build-123")),
+            (
+                "gemini-first.last@mail.example.co.uk-my-project.json",
+                Some("my-project"),
+            ),
+            ("gemini-x@gmail.com-all.json", Some("all")),
+            ("user@gmail.com-no-extension", Some("no-extension")),
+            // Nothing after the domain, or no email at all
+            ("gemini-user@gmail.com.json", None),
+            ("gemini-user@gmail.com-.json", None),
+            ("gemini-cli-1700000000.json", None),
+            ("gemini-user@localhost-project.json", None),
+        ] {
+            assert_eq!(
+                CliProxyApiQuotaSegment::extract_project_from_name(name).as_deref(),
+                project,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn project_regex_is_tried_first() {
+        let named = CliProxyApiQuotaSegment::new()
+            .with_project_regex(Some(r"^gcp_(?P<project>[a-z0-9-]+)_\d+\.json$"));
+        let project = named.project_from_regex("gcp_team-alpha_42.json");
+        assert_eq!(project.as_deref(), Some("team-alpha"));
+        assert_eq!(named.project_from_regex("gemini-a@b.com-p.json"), None);
+
+        // Without a `project` group the first group is used
+        let unnamed = CliProxyApiQuotaSegment::new().with_project_regex(Some(r"^([^.]+)\.json$"));
+        let project = unnamed.project_from_regex("proj-7.json");
+        assert_eq!(project.as_deref(), Some("proj-7"));
+
+        let invalid = CliProxyApiQuotaSegment::new().with_project_regex(Some("(unclosed"));
+        assert_eq!(invalid.project_from_regex("anything.json"), None);
+    }
+
+    #[test]
+    fn unparseable_names_fall_back_to_the_auth_file() {
+        let (url, requests) = mock_server_with_body(vec![200], r#"{"project_id":"from-file"}"#);
+        let segment = CliProxyApiQuotaSegment::new();
+
+        // A name with the project in it never downloads the file
+        assert_eq!(
+            segment
+                .resolve_gemini_project(&url, "key", "gemini-a@gmail.com-in-name.json")
+                .as_deref(),
+            Some("in-name")
+        );
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        assert_eq!(
+            segment
+                .resolve_gemini_project(&url, "key", "gemini-cli-1700000000.json")
+                .as_deref(),
+            Some("from-file")
+        );
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
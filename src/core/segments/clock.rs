@@ -0,0 +1,185 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{FixedOffset, Local, Utc};
+use std::collections::HashMap;
+
+const DEFAULT_FORMAT: &str = "%H:%M";
+
+/// Current wall-clock time, e.g. `14:05`
+pub struct ClockSegment {
+    format: String,
+    timezone: Option<String>,
+}
+
+impl Default for ClockSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSegment {
+    pub fn new() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            timezone: None,
+        }
+    }
+
+    /// strftime-style format; invalid formats fall back to `%H:%M`
+    pub fn with_format(mut self, format: &str) -> Self {
+        self.format = format.to_string();
+        self
+    }
+
+    /// `local` (default), `utc`, or a fixed offset such as `+09:00` / `UTC-5`
+    ///
+    /// IANA names like `Europe/Berlin` aren't supported and hide the segment,
+    /// since a fixed offset can't follow daylight saving time.
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        self.timezone = Some(timezone.to_string());
+        self
+    }
+
+    /// Chrono panics when displaying an invalid format, so validate it up front
+    fn effective_format(&self) -> &str {
+        let invalid = StrftimeItems::new(&self.format).any(|item| matches!(item, Item::Error));
+        if invalid || self.format.is_empty() {
+            DEFAULT_FORMAT
+        } else {
+            &self.format
+        }
+    }
+
+    /// Whether `timezone` is one `with_timezone` understands
+    pub fn supports_timezone(timezone: &str) -> bool {
+        Self::is_local(timezone) || Self::parse_offset(timezone).is_some()
+    }
+
+    fn is_local(timezone: &str) -> bool {
+        let tz = timezone.trim();
+        tz.is_empty() || tz.eq_ignore_ascii_case("local")
+    }
+
+    /// Parse `utc`, `+09:00`, `-0530`, `UTC+8` style names into a fixed offset
+    fn parse_offset(timezone: &str) -> Option<FixedOffset> {
+        let tz = timezone.trim();
+        let tz = tz
+            .strip_prefix("UTC")
+            .or_else(|| tz.strip_prefix("utc"))
+            .or_else(|| tz.strip_prefix("GMT"))
+            .unwrap_or(tz);
+        if tz.is_empty() {
+            return FixedOffset::east_opt(0);
+        }
+
+        let (sign, rest) = match tz.chars().next()? {
+            '+' => (1, &tz[1..]),
+            '-' => (-1, &tz[1..]),
+            _ => return None,
+        };
+        if !rest.is_ascii() {
+            return None;
+        }
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((h, m)) => (h, m),
+            None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+            None => (rest, "0"),
+        };
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+impl Segment for ClockSegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        let format = self.effective_format();
+
+        let (primary, timezone) = match self.timezone.as_deref() {
+            Some(tz) if !Self::is_local(tz) => match Self::parse_offset(tz) {
+                Some(offset) => (
+                    Utc::now().with_timezone(&offset).format(format).to_string(),
+                    offset.to_string(),
+                ),
+                None => {
+                    super::skip_reason(format!(
+                        "unsupported timezone {:?}: use local, utc or a fixed offset like +09:00",
+                        tz
+                    ));
+                    return None;
+                }
+            },
+            _ => (Local::now().format(format).to_string(), "local".to_string()),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("format".to_string(), format.to_string());
+        metadata.insert("timezone".to_string(), timezone);
+
+        Some(SegmentData {
+            primary,
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_offsets_parse() {
+        assert_eq!(ClockSegment::parse_offset("utc"), FixedOffset::east_opt(0));
+        assert_eq!(
+            ClockSegment::parse_offset("+09:00"),
+            FixedOffset::east_opt(9 * 3600)
+        );
+        assert_eq!(
+            ClockSegment::parse_offset("-0530"),
+            FixedOffset::east_opt(-(5 * 3600 + 30 * 60))
+        );
+        assert_eq!(
+            ClockSegment::parse_offset("UTC+8"),
+            FixedOffset::east_opt(8 * 3600)
+        );
+        assert_eq!(ClockSegment::parse_offset("+24:00"), None);
+    }
+
+    #[test]
+    fn iana_names_are_not_supported() {
+        assert!(ClockSegment::supports_timezone("local"));
+        assert!(ClockSegment::supports_timezone("UTC-5"));
+        assert!(!ClockSegment::supports_timezone("Europe/Berlin"));
+    }
+
+    #[test]
+    fn unsupported_timezone_hides_the_segment_with_a_reason() {
+        super::super::take_skip_reason();
+        let segment = ClockSegment::new().with_timezone("Europe/Berlin");
+
+        assert!(segment.collect(&InputData::default()).is_none());
+        let reason = super::super::take_skip_reason().unwrap();
+        assert!(reason.contains("Europe/Berlin"), "{}", reason);
+    }
+
+    #[test]
+    fn fixed_offset_is_reported_in_metadata() {
+        let data = ClockSegment::new()
+            .with_timezone("+09:00")
+            .collect(&InputData::default())
+            .unwrap();
+
+        assert_eq!(data.metadata["timezone"], "+09:00");
+    }
+}
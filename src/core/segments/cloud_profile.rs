@@ -0,0 +1,143 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use std::collections::HashMap;
+
+/// Cloud settings the segment can show, each read from the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudSource {
+    /// `$AWS_PROFILE`
+    AwsProfile,
+    /// `$AWS_REGION`, falling back to `$AWS_DEFAULT_REGION`
+    AwsRegion,
+    /// `$CLOUDSDK_CORE_PROJECT`
+    GcpProject,
+}
+
+impl CloudSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AwsProfile => "aws_profile",
+            Self::AwsRegion => "aws_region",
+            Self::GcpProject => "gcp_project",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "aws_profile" | "aws" => Some(Self::AwsProfile),
+            "aws_region" | "region" => Some(Self::AwsRegion),
+            "gcp_project" | "gcp" | "gcloud" => Some(Self::GcpProject),
+            _ => None,
+        }
+    }
+
+    fn env_vars(&self) -> &'static [&'static str] {
+        match self {
+            Self::AwsProfile => &["AWS_PROFILE"],
+            Self::AwsRegion => &["AWS_REGION", "AWS_DEFAULT_REGION"],
+            Self::GcpProject => &["CLOUDSDK_CORE_PROJECT"],
+        }
+    }
+
+    fn detect(&self) -> Option<String> {
+        self.env_vars().iter().find_map(|name| {
+            let value = std::env::var(name).ok()?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    }
+}
+
+/// Active AWS profile/region and GCP project, e.g. `aws prod us-east-1 gcp my-project`
+pub struct CloudProfileSegment {
+    show: Vec<CloudSource>,
+    icons: HashMap<String, String>,
+}
+
+impl Default for CloudProfileSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CloudProfileSegment {
+    pub fn new() -> Self {
+        let mut icons = HashMap::new();
+        icons.insert(
+            CloudSource::AwsProfile.as_str().to_string(),
+            "aws".to_string(),
+        );
+        icons.insert(
+            CloudSource::GcpProject.as_str().to_string(),
+            "gcp".to_string(),
+        );
+
+        Self {
+            show: vec![
+                CloudSource::AwsProfile,
+                CloudSource::AwsRegion,
+                CloudSource::GcpProject,
+            ],
+            icons,
+        }
+    }
+
+    /// Values to show and the order they are shown in
+    pub fn with_show(mut self, show: Vec<CloudSource>) -> Self {
+        self.show = show;
+        self
+    }
+
+    /// Prefix per value name (`aws_profile`, `aws_region`, `gcp_project`), empty for none
+    pub fn with_icons(mut self, icons: HashMap<String, String>) -> Self {
+        self.icons.extend(icons);
+        self
+    }
+}
+
+impl Segment for CloudProfileSegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        let mut parts = Vec::new();
+        let mut metadata = HashMap::new();
+        let mut sources = Vec::new();
+
+        for source in &self.show {
+            // The same value listed twice shouldn't render twice
+            if sources.contains(&source.as_str()) {
+                continue;
+            }
+            let Some(value) = source.detect() else {
+                continue;
+            };
+            sources.push(source.as_str());
+            metadata.insert(source.as_str().to_string(), value.clone());
+
+            match self
+                .icons
+                .get(source.as_str())
+                .filter(|icon| !icon.is_empty())
+            {
+                Some(icon) => parts.push(format!("{} {}", icon, value)),
+                None => parts.push(value),
+            }
+        }
+
+        if parts.is_empty() {
+            super::skip_reason("no AWS_PROFILE, AWS_REGION or CLOUDSDK_CORE_PROJECT set");
+            return None;
+        }
+
+        metadata.insert("sources".to_string(), sources.join(","));
+
+        Some(SegmentData {
+            primary: parts.join(" "),
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::CloudProfile
+    }
+}
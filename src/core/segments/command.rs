@@ -0,0 +1,238 @@
+use super::{Segment, SegmentData};
+use crate::config::{config_dir, InputData, SegmentId};
+use crate::core::KeyedCache;
+use crate::utils::{sanitize_output, SanitizeOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Cached result of one command in one working directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CommandOutput {
+    /// Sanitized stdout, None when the command failed or timed out
+    output: Option<String>,
+}
+
+/// Runs a user-specified shell command and shows its stdout
+///
+/// The command comes straight from config.toml and runs with the user's
+/// privileges through `sh -c` (`cmd /C` on Windows) on every refresh that
+/// misses the cache, which is why the segment ships disabled.
+pub struct CommandSegment {
+    command: String,
+    timeout: Duration,
+    cache_duration: u64,
+    /// Results per working directory and command, `None` disables caching
+    cache_path: Option<PathBuf>,
+    sanitize: SanitizeOptions,
+}
+
+impl Default for CommandSegment {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl CommandSegment {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            timeout: Duration::from_millis(500),
+            cache_duration: 10,
+            cache_path: config_dir().ok().map(|dir| dir.join(".command_cache.json")),
+            sanitize: SanitizeOptions::default(),
+        }
+    }
+
+    /// Kill the command if it hasn't exited by then
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Seconds a result is reused, 0 runs the command on every refresh
+    pub fn with_cache_duration(mut self, cache_duration: u64) -> Self {
+        self.cache_duration = cache_duration;
+        self
+    }
+
+    /// How stdout is joined and stripped
+    pub fn with_sanitize(mut self, sanitize: SanitizeOptions) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    fn shell_command(&self) -> Command {
+        if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", &self.command]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &self.command]);
+            cmd
+        }
+    }
+
+    /// Run the command in `working_dir`, returning its stdout if it succeeded in time
+    fn run(&self, working_dir: &str) -> Option<String> {
+        let mut cmd = self.shell_command();
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        if !working_dir.is_empty() && std::path::Path::new(working_dir).is_dir() {
+            cmd.current_dir(working_dir);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| super::skip_reason(format!("command failed to start: {}", e)))
+            .ok()?;
+
+        // Drain stdout on a thread so a chatty command can't block on a full pipe
+        let mut stdout = child.stdout.take()?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stdout.read_to_end(&mut buffer);
+            let _ = sender.send(buffer);
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                _ => {
+                    super::skip_reason(format!("command timed out after {:?}", self.timeout));
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        };
+
+        // A backgrounded grandchild may keep the pipe open, so don't wait past the deadline
+        let buffer = receiver
+            .recv_timeout(
+                deadline
+                    .saturating_duration_since(Instant::now())
+                    .max(Duration::from_millis(50)),
+            )
+            .ok()?;
+        if !status.success() {
+            super::skip_reason(format!("command exited with {}", status));
+            return None;
+        }
+
+        let output = sanitize_output(&String::from_utf8_lossy(&buffer), &self.sanitize);
+        (!output.is_empty()).then_some(output)
+    }
+
+    fn get_output(&self, working_dir: &str) -> Option<String> {
+        if self.cache_duration == 0 {
+            return self.run(working_dir);
+        }
+
+        // Failures are cached too, so a broken command doesn't slow every prompt
+        let cache = KeyedCache::with_path(self.cache_path.clone(), self.cache_duration);
+        let key = format!("{}\n{}", working_dir, self.command);
+        cache
+            .get_or_insert_with(
+                &key,
+                |_| true,
+                || CommandOutput {
+                    output: self.run(working_dir),
+                },
+            )
+            .output
+    }
+}
+
+impl Segment for CommandSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        if self.command.trim().is_empty() {
+            super::skip_reason("no command configured");
+            return None;
+        }
+
+        let primary = self.get_output(&input.workspace.current_dir)?;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("command".to_string(), self.command.clone());
+
+        Some(SegmentData {
+            primary,
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Command
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Segment whose command appends a line to `runs` before doing `then`
+    fn counting_segment(dir: &std::path::Path, then: &str) -> CommandSegment {
+        let runs = dir.join("runs");
+        let mut segment =
+            CommandSegment::new(&format!("echo run >> '{}'; {}", runs.display(), then));
+        segment.cache_path = Some(dir.join("cache.json"));
+        segment
+    }
+
+    fn runs(dir: &std::path::Path) -> usize {
+        std::fs::read_to_string(dir.join("runs"))
+            .unwrap_or_default()
+            .lines()
+            .count()
+    }
+
+    #[test]
+    fn output_is_cached_per_working_directory() {
+        let dir = crate::test_support::temp_dir("command-cache");
+        let segment = counting_segment(&dir, "echo ok");
+        let here = dir.display().to_string();
+
+        assert_eq!(segment.get_output(&here).as_deref(), Some("ok"));
+        assert_eq!(segment.get_output(&here).as_deref(), Some("ok"));
+        assert_eq!(runs(&dir), 1);
+
+        assert_eq!(segment.get_output("/").as_deref(), Some("ok"));
+        assert_eq!(runs(&dir), 2);
+    }
+
+    #[test]
+    fn failures_are_cached_too() {
+        let dir = crate::test_support::temp_dir("command-cache-failure");
+        let segment = counting_segment(&dir, "exit 1");
+        let here = dir.display().to_string();
+
+        assert_eq!(segment.get_output(&here), None);
+        assert_eq!(segment.get_output(&here), None);
+        assert_eq!(runs(&dir), 1);
+    }
+
+    #[test]
+    fn zero_cache_duration_runs_every_time() {
+        let dir = crate::test_support::temp_dir("command-no-cache");
+        let segment = counting_segment(&dir, "echo ok").with_cache_duration(0);
+        let here = dir.display().to_string();
+
+        segment.get_output(&here);
+        segment.get_output(&here);
+        assert_eq!(runs(&dir), 2);
+        assert!(!dir.join("cache.json").exists());
+    }
+}
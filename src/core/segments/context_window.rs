@@ -1,16 +1,173 @@
-use super::{Segment, SegmentData};
-use crate::config::{InputData, ModelConfig, SegmentId, TranscriptEntry};
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{AnsiColor, InputData, ModelConfig, SegmentId, TranscriptEntry};
+use crate::core::humanize;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
-#[derive(Default)]
-pub struct ContextWindowSegment;
+/// How the context usage percentage is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContextDisplayMode {
+    #[default]
+    Percent,
+    Bar,
+    Both,
+}
+
+impl ContextDisplayMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "percent" => Some(Self::Percent),
+            "bar" => Some(Self::Bar),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+/// Severity of the current context usage relative to the configured thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextSeverity {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl ContextSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Percentage thresholds and the colors used once usage crosses them
+#[derive(Debug, Clone)]
+pub struct ContextThresholds {
+    pub warn_threshold: f64,
+    pub critical_threshold: f64,
+    pub warn_color: AnsiColor,
+    pub critical_color: AnsiColor,
+}
+
+impl Default for ContextThresholds {
+    fn default() -> Self {
+        Self {
+            warn_threshold: 70.0,
+            critical_threshold: 90.0,
+            warn_color: AnsiColor::Color16 { c16: 11 },
+            critical_color: AnsiColor::Color16 { c16: 9 },
+        }
+    }
+}
+
+impl ContextThresholds {
+    /// Read `warn_threshold`, `critical_threshold`, `warn_color` and `critical_color` from segment options
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let defaults = Self::default();
+        let color = |key: &str, default: AnsiColor| {
+            options
+                .get(key)
+                .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            warn_threshold: options
+                .get("warn_threshold")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(defaults.warn_threshold),
+            critical_threshold: options
+                .get("critical_threshold")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(defaults.critical_threshold),
+            warn_color: color("warn_color", defaults.warn_color),
+            critical_color: color("critical_color", defaults.critical_color),
+        }
+    }
+
+    pub fn severity(&self, percentage: f64) -> ContextSeverity {
+        if percentage >= self.critical_threshold {
+            ContextSeverity::Critical
+        } else if percentage >= self.warn_threshold {
+            ContextSeverity::Warning
+        } else {
+            ContextSeverity::Normal
+        }
+    }
+
+    /// Color override for a severity, `None` keeps the configured text color
+    pub fn color_for(&self, severity: ContextSeverity) -> Option<&AnsiColor> {
+        match severity {
+            ContextSeverity::Normal => None,
+            ContextSeverity::Warning => Some(&self.warn_color),
+            ContextSeverity::Critical => Some(&self.critical_color),
+        }
+    }
+}
+
+pub struct ContextWindowSegment {
+    display_mode: ContextDisplayMode,
+    bar_width: usize,
+    ascii_bar: bool,
+    thresholds: ContextThresholds,
+}
+
+impl Default for ContextWindowSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ContextWindowSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            display_mode: ContextDisplayMode::Percent,
+            bar_width: 8,
+            ascii_bar: false,
+            thresholds: ContextThresholds::default(),
+        }
+    }
+
+    pub fn with_display_mode(mut self, display_mode: ContextDisplayMode) -> Self {
+        self.display_mode = display_mode;
+        self
+    }
+
+    pub fn with_bar_width(mut self, bar_width: usize) -> Self {
+        self.bar_width = bar_width.max(1);
+        self
+    }
+
+    /// Use `#`/`-` instead of block glyphs (for plain style mode)
+    pub fn with_ascii_bar(mut self, ascii_bar: bool) -> Self {
+        self.ascii_bar = ascii_bar;
+        self
+    }
+
+    pub fn with_thresholds(mut self, thresholds: ContextThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Render a usage bar like `[████░░░░]` for a percentage in 0..=100
+    fn render_bar(&self, percentage: f64) -> String {
+        let (filled_char, empty_char) = if self.ascii_bar {
+            ('#', '-')
+        } else {
+            ('█', '░')
+        };
+        let filled = ((percentage / 100.0) * self.bar_width as f64).round() as usize;
+        let filled = filled.min(self.bar_width);
+
+        let mut bar = String::from("[");
+        bar.extend(std::iter::repeat_n(filled_char, filled));
+        bar.extend(std::iter::repeat_n(empty_char, self.bar_width - filled));
+        bar.push(']');
+        bar
     }
 
     /// Get context limit for the specified model
@@ -25,29 +182,37 @@ impl Segment for ContextWindowSegment {
         // Dynamically determine context limit based on current model ID
         let context_limit = Self::get_context_limit_for_model(&input.model.id);
 
-        let context_used_token_opt = parse_transcript_usage(&input.transcript_path);
+        // A zero limit can't produce a meaningful percentage
+        let context_used_token_opt = if context_limit == 0 {
+            None
+        } else {
+            parse_transcript_usage(&input.transcript_path)
+        };
+
+        let mut severity = ContextSeverity::Normal;
 
         let (percentage_display, tokens_display) = match context_used_token_opt {
             Some(context_used_token) => {
-                let context_used_rate = (context_used_token as f64 / context_limit as f64) * 100.0;
+                let context_used_rate =
+                    ((context_used_token as f64 / context_limit as f64) * 100.0).clamp(0.0, 100.0);
+                severity = self.thresholds.severity(context_used_rate);
 
-                let percentage = if context_used_rate.fract() == 0.0 {
+                let percentage_text = if context_used_rate.fract() == 0.0 {
                     format!("{:.0}%", context_used_rate)
                 } else {
                     format!("{:.1}%", context_used_rate)
                 };
 
-                let tokens = if context_used_token >= 1000 {
-                    let k_value = context_used_token as f64 / 1000.0;
-                    if k_value.fract() == 0.0 {
-                        format!("{}k", k_value as u32)
-                    } else {
-                        format!("{:.1}k", k_value)
+                let percentage = match self.display_mode {
+                    ContextDisplayMode::Percent => percentage_text,
+                    ContextDisplayMode::Bar => self.render_bar(context_used_rate),
+                    ContextDisplayMode::Both => {
+                        format!("{} {}", self.render_bar(context_used_rate), percentage_text)
                     }
-                } else {
-                    context_used_token.to_string()
                 };
 
+                let tokens = humanize(u64::from(context_used_token), 1);
+
                 (percentage, tokens)
             }
             None => {
@@ -70,11 +235,19 @@ impl Segment for ContextWindowSegment {
         }
         metadata.insert("limit".to_string(), context_limit.to_string());
         metadata.insert("model".to_string(), input.model.id.clone());
+        metadata.insert("severity".to_string(), severity.as_str().to_string());
+
+        let mut primary = format!("{} · {} tokens", percentage_display, tokens_display);
+        // Past a threshold the severity color takes over from the configured text color
+        if let Some(color) = self.thresholds.color_for(severity) {
+            primary = AnsiColorHelper::apply_foreground_color(&primary, color);
+        }
 
         Some(SegmentData {
-            primary: format!("{} · {} tokens", percentage_display, tokens_display),
+            primary,
             secondary: String::new(),
             metadata,
+            raw: false,
         })
     }
 
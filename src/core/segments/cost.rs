@@ -1,43 +1,321 @@
-use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{AnsiColor, InputData, SegmentId};
+use crate::utils::{atomic_write, FileLock};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-#[derive(Default)]
-pub struct CostSegment;
+/// Where the currency symbol goes relative to the amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolPlacement {
+    #[default]
+    Prefix,
+    Suffix,
+}
+
+impl SymbolPlacement {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "prefix" => Some(Self::Prefix),
+            "suffix" => Some(Self::Suffix),
+            _ => None,
+        }
+    }
+}
+
+/// Cost of one session as counted towards the daily total
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SessionCost {
+    /// Latest cumulative cost Claude Code reported for the session
+    cost: f64,
+    /// Cost already spent before local midnight, for sessions running across days
+    #[serde(default)]
+    baseline: f64,
+}
+
+/// Per-session costs of the current local day, persisted across prompts
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DailyCost {
+    /// Local date as `YYYY-MM-DD`
+    date: String,
+    /// Keyed by transcript path, which is unique per session
+    sessions: HashMap<String, SessionCost>,
+}
+
+impl DailyCost {
+    const FILE_NAME: &'static str = ".daily_cost.json";
+    /// The statusline can't wait long, so past this the daily total is skipped
+    const LOCK_TIMEOUT: Duration = Duration::from_millis(200);
+
+    fn path() -> Option<PathBuf> {
+        Some(crate::config::config_dir().ok()?.join(Self::FILE_NAME))
+    }
+
+    /// Record the session's cost and return today's total across all sessions
+    ///
+    /// Concurrent prompts serialize on a lock file and the JSON is replaced with an
+    /// atomic rename, so readers never see a partial file.
+    fn record(session: &str, cost: f64) -> Option<f64> {
+        Self::record_at(&Self::path()?, session, cost)
+    }
+
+    fn record_at(path: &Path, session: &str, cost: f64) -> Option<f64> {
+        let _lock = Self::lock(path)?;
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let mut daily: DailyCost = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        if daily.date != today {
+            // Sessions still running after midnight only count what they spend from now on;
+            // ones that spent nothing the previous day are finished and dropped
+            daily.sessions = daily
+                .sessions
+                .into_iter()
+                .filter(|(_, entry)| entry.cost > entry.baseline)
+                .map(|(key, entry)| {
+                    let carried = SessionCost {
+                        baseline: entry.cost,
+                        ..entry
+                    };
+                    (key, carried)
+                })
+                .collect();
+            daily.date = today;
+        }
+
+        let entry = daily.sessions.entry(session.to_string()).or_default();
+        entry.cost = cost;
+        // A lower cost means the session restarted, so it starts counting from zero
+        if entry.baseline > cost {
+            entry.baseline = 0.0;
+        }
+
+        let total = daily
+            .sessions
+            .values()
+            .map(|entry| (entry.cost - entry.baseline).max(0.0))
+            .sum();
+
+        let json = serde_json::to_string_pretty(&daily).ok()?;
+        let _ = atomic_write(path, json);
+
+        Some(total)
+    }
+
+    /// Lock the daily file, polling so a busy lock can't stall the prompt
+    ///
+    /// The OS releases the lock when its holder exits, even after a crash.
+    fn lock(path: &Path) -> Option<FileLock> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let deadline = Instant::now() + Self::LOCK_TIMEOUT;
+        loop {
+            match FileLock::try_exclusive(path) {
+                Ok(Some(lock)) => return Some(lock),
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Ok(None) => {
+                    log::debug!("daily cost: {} is locked, skipping", path.display());
+                    return None;
+                }
+                Err(e) => {
+                    log::debug!("daily cost: cannot lock {}: {}", path.display(), e);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+pub struct CostSegment {
+    currency_symbol: String,
+    decimal_separator: String,
+    exchange_rate: f64,
+    symbol_placement: SymbolPlacement,
+    budget_limit: Option<f64>,
+    budget_color: AnsiColor,
+    over_budget_symbol: String,
+    show_rate: bool,
+    daily_total: bool,
+}
+
+impl Default for CostSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CostSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            currency_symbol: "$".to_string(),
+            decimal_separator: ".".to_string(),
+            exchange_rate: 1.0,
+            symbol_placement: SymbolPlacement::Prefix,
+            budget_limit: None,
+            budget_color: AnsiColor::Color16 { c16: 9 },
+            over_budget_symbol: "⚠".to_string(),
+            show_rate: false,
+            daily_total: false,
+        }
+    }
+
+    pub fn with_currency_symbol(mut self, symbol: &str) -> Self {
+        self.currency_symbol = symbol.to_string();
+        self
+    }
+
+    pub fn with_decimal_separator(mut self, separator: &str) -> Self {
+        self.decimal_separator = separator.to_string();
+        self
+    }
+
+    /// Multiplier applied to the USD cost before formatting
+    pub fn with_exchange_rate(mut self, exchange_rate: f64) -> Self {
+        self.exchange_rate = exchange_rate;
+        self
+    }
+
+    pub fn with_symbol_placement(mut self, placement: SymbolPlacement) -> Self {
+        self.symbol_placement = placement;
+        self
+    }
+
+    fn format_amount(&self, usd: f64) -> String {
+        let amount = usd * self.exchange_rate;
+        let number = if amount == 0.0 || amount < 0.01 {
+            "0".to_string()
+        } else {
+            format!("{:.2}", amount).replace('.', &self.decimal_separator)
+        };
+
+        match self.symbol_placement {
+            SymbolPlacement::Prefix => format!("{}{}", self.currency_symbol, number),
+            SymbolPlacement::Suffix => format!("{}{}", number, self.currency_symbol),
+        }
+    }
+
+    /// Session cost (USD, before conversion) above which the segment switches to the budget color
+    pub fn with_budget_limit(mut self, budget_limit: Option<f64>) -> Self {
+        self.budget_limit = budget_limit;
+        self
+    }
+
+    pub fn with_budget_color(mut self, budget_color: AnsiColor) -> Self {
+        self.budget_color = budget_color;
+        self
+    }
+
+    pub fn with_over_budget_symbol(mut self, symbol: &str) -> Self {
+        self.over_budget_symbol = symbol.to_string();
+        self
+    }
+
+    /// Show the cost per hour of session time as the secondary text
+    pub fn with_rate(mut self, show_rate: bool) -> Self {
+        self.show_rate = show_rate;
+        self
+    }
+
+    /// Append the total of every session today, as `session / today`
+    pub fn with_daily_total(mut self, daily_total: bool) -> Self {
+        self.daily_total = daily_total;
+        self
+    }
+
+    /// Seconds since `session_start`, falling back to the duration Claude Code reports
+    fn elapsed_seconds(input: &InputData) -> Option<u64> {
+        if let Some(started_at) = input
+            .session_start
+            .as_deref()
+            .and_then(|start| DateTime::parse_from_rfc3339(start).ok())
+        {
+            let elapsed = Utc::now().signed_duration_since(started_at.with_timezone(&Utc));
+            return Some(elapsed.num_seconds().max(0) as u64);
+        }
+
+        input
+            .cost
+            .as_ref()
+            .and_then(|cost| cost.total_duration_ms)
+            .map(|ms| ms / 1000)
     }
 }
 
 impl Segment for CostSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let cost_data = input.cost.as_ref()?;
+        let Some(cost_data) = input.cost.as_ref() else {
+            super::skip_reason("no cost in input");
+            return None;
+        };
 
         // Primary display: total cost
-        let primary = if let Some(cost) = cost_data.total_cost_usd {
-            if cost == 0.0 || cost < 0.01 {
-                "$0".to_string()
-            } else {
-                format!("${:.2}", cost)
-            }
+        let mut primary = if let Some(cost) = cost_data.total_cost_usd {
+            self.format_amount(cost)
         } else {
+            super::skip_reason("no cost.total_cost_usd in input");
             return None;
         };
 
-        // Secondary display: empty for cost segment
-        let secondary = String::new();
-
         let mut metadata = HashMap::new();
+
+        // Secondary display: burn rate when enabled and the session length is known
+        let rate_input = cost_data
+            .total_cost_usd
+            .filter(|_| self.show_rate)
+            .zip(Self::elapsed_seconds(input));
+        let secondary = match rate_input {
+            // The first minute's rate is mostly noise, and zero seconds can't be divided by
+            Some((_, elapsed)) if elapsed < 60 => "—".to_string(),
+            Some((cost, elapsed)) => {
+                let per_hour = cost / (elapsed as f64 / 3600.0);
+                metadata.insert("cost_per_hour".to_string(), per_hour.to_string());
+                format!("{}/hr", self.format_amount(per_hour))
+            }
+            None => String::new(),
+        };
+
         if let Some(cost) = cost_data.total_cost_usd {
             metadata.insert("cost".to_string(), cost.to_string());
+            metadata.insert(
+                "converted_cost".to_string(),
+                (cost * self.exchange_rate).to_string(),
+            );
+
+            let over_budget = self.budget_limit.map(|limit| cost > limit);
+            if let Some(over_budget) = over_budget {
+                metadata.insert("over_budget".to_string(), over_budget.to_string());
+            }
+            if over_budget == Some(true) && !self.over_budget_symbol.is_empty() {
+                primary = format!("{} {}", primary, self.over_budget_symbol);
+            }
+
+            if self.daily_total {
+                if let Some(today) = DailyCost::record(&input.transcript_path, cost) {
+                    metadata.insert("daily_total".to_string(), today.to_string());
+                    primary = format!("{} / {}", primary, self.format_amount(today));
+                }
+            }
+
+            // Colored last: text appended after the color's reset would lose the text color
+            if over_budget == Some(true) {
+                primary = AnsiColorHelper::apply_foreground_color(&primary, &self.budget_color);
+            }
         }
 
         Some(SegmentData {
             primary,
             secondary,
             metadata,
+            raw: false,
         })
     }
 
@@ -45,3 +323,97 @@ impl Segment for CostSegment {
         SegmentId::Cost
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn costing(total_cost_usd: f64) -> InputData {
+        serde_json::from_value(serde_json::json!({
+            "cost": {"total_cost_usd": total_cost_usd}
+        }))
+        .unwrap()
+    }
+
+    fn budgeted() -> CostSegment {
+        CostSegment::new()
+            .with_budget_limit(Some(5.0))
+            .with_budget_color(AnsiColor::Color16 { c16: 9 })
+            .with_over_budget_symbol("!")
+    }
+
+    #[test]
+    fn no_budget_limit_leaves_the_cost_alone() {
+        let data = CostSegment::new().collect(&costing(50.0)).unwrap();
+        assert_eq!(data.primary, "$50.00");
+        assert!(!data.metadata.contains_key("over_budget"));
+    }
+
+    #[test]
+    fn under_and_at_the_limit_are_within_budget() {
+        for cost in [1.25, 5.0] {
+            let data = budgeted().collect(&costing(cost)).unwrap();
+            assert_eq!(data.primary, format!("${:.2}", cost));
+            assert_eq!(data.metadata["over_budget"], "false");
+        }
+    }
+
+    #[test]
+    fn over_the_limit_adds_the_symbol_and_color() {
+        let data = budgeted().collect(&costing(5.01)).unwrap();
+
+        assert_eq!(data.metadata["over_budget"], "true");
+        assert_eq!(crate::utils::strip_ansi(&data.primary), "$5.01 !");
+        if crate::core::color_enabled() {
+            assert_eq!(data.primary, "\x1b[91m$5.01 !\x1b[39m");
+        }
+
+        let data = budgeted()
+            .with_over_budget_symbol("")
+            .collect(&costing(9.0))
+            .unwrap();
+        assert_eq!(crate::utils::strip_ansi(&data.primary), "$9.00");
+    }
+
+    #[test]
+    fn concurrent_sessions_are_all_counted() {
+        let path = crate::test_support::temp_dir("daily-cost").join(".daily_cost.json");
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for cost in 1..=5 {
+                        DailyCost::record_at(&path, &format!("session-{}", i), cost as f64)
+                            .expect("lock within the timeout");
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // Each session's latest cumulative cost is 5
+        assert_eq!(DailyCost::record_at(&path, "session-0", 5.0), Some(40.0));
+    }
+
+    #[test]
+    fn held_lock_skips_the_total() {
+        let path = crate::test_support::temp_dir("daily-cost-busy").join(".daily_cost.json");
+
+        let held = FileLock::exclusive(&path).unwrap();
+        assert_eq!(DailyCost::record_at(&path, "session", 1.0), None);
+        drop(held);
+        assert_eq!(DailyCost::record_at(&path, "session", 1.0), Some(1.0));
+    }
+
+    #[test]
+    fn latest_session_cost_replaces_the_previous_one() {
+        let path = crate::test_support::temp_dir("daily-cost-latest").join(".daily_cost.json");
+
+        assert_eq!(DailyCost::record_at(&path, "a", 2.0), Some(2.0));
+        assert_eq!(DailyCost::record_at(&path, "b", 1.0), Some(3.0));
+        assert_eq!(DailyCost::record_at(&path, "a", 0.5), Some(1.5));
+    }
+}
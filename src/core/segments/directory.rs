@@ -1,13 +1,151 @@
+use super::git::GitDirs;
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
 use std::collections::HashMap;
 
-#[derive(Default)]
-pub struct DirectorySegment;
+pub struct DirectorySegment {
+    shorten_home: bool,
+    max_components: Option<usize>,
+    truncation_symbol: String,
+    keep_root: bool,
+    repo_relative: bool,
+}
+
+impl Default for DirectorySegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl DirectorySegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            shorten_home: true,
+            max_components: None,
+            truncation_symbol: "…".to_string(),
+            keep_root: false,
+            repo_relative: false,
+        }
+    }
+
+    /// Replace a leading home directory with `~`
+    pub fn with_shorten_home(mut self, shorten_home: bool) -> Self {
+        self.shorten_home = shorten_home;
+        self
+    }
+
+    /// Show up to this many trailing path components instead of just the directory name
+    pub fn with_max_components(mut self, max_components: Option<usize>) -> Self {
+        self.max_components = max_components.filter(|&n| n > 0);
+        self
+    }
+
+    pub fn with_truncation_symbol(mut self, symbol: &str) -> Self {
+        self.truncation_symbol = symbol.to_string();
+        self
+    }
+
+    /// Keep the first component when truncating, e.g. `~/…/d/e`
+    pub fn with_keep_root(mut self, keep_root: bool) -> Self {
+        self.keep_root = keep_root;
+        self
+    }
+
+    /// Inside a git repository show `repo/sub/dir` relative to the repo root
+    pub fn with_repo_relative(mut self, repo_relative: bool) -> Self {
+        self.repo_relative = repo_relative;
+        self
+    }
+
+    /// Repo folder name plus the path beneath it, using the same root detection as `GitSegment`
+    fn repo_relative_path(current_dir: &str) -> Option<String> {
+        let git_dirs = GitDirs::discover(current_dir)?;
+        let repo_name = git_dirs
+            .work_tree
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+        let relative = std::path::Path::new(current_dir)
+            .strip_prefix(&git_dirs.work_tree)
+            .ok()?;
+
+        let mut parts = vec![repo_name];
+        parts.extend(
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string()),
+        );
+        Some(parts.join("/"))
+    }
+
+    /// Limit a path to `max_components`, e.g. `/a/b/c/d/e` with 3 becomes `…/c/d/e`
+    fn truncate_path(&self, path: &str, max_components: usize) -> String {
+        let separator = if path.contains('\\') && !path.contains('/') {
+            '\\'
+        } else {
+            '/'
+        };
+        // Splitting on the separator char keeps multibyte names intact
+        let components: Vec<&str> = path.split(separator).filter(|c| !c.is_empty()).collect();
+        if components.len() <= max_components {
+            return path.to_string();
+        }
+
+        let sep = separator.to_string();
+        if self.keep_root && max_components >= 2 {
+            let root_prefix = if path.starts_with(separator) {
+                sep.as_str()
+            } else {
+                ""
+            };
+            let tail = &components[components.len() - (max_components - 1)..];
+            format!(
+                "{}{}{}{}{}{}",
+                root_prefix,
+                components[0],
+                sep,
+                self.truncation_symbol,
+                sep,
+                tail.join(&sep)
+            )
+        } else {
+            let tail = &components[components.len() - max_components..];
+            format!("{}{}{}", self.truncation_symbol, sep, tail.join(&sep))
+        }
+    }
+
+    /// Abbreviate the home directory prefix (the user profile dir on Windows) to `~`
+    fn abbreviate_home(path: &str) -> String {
+        let Some(home) = dirs::home_dir() else {
+            return path.to_string();
+        };
+        let home = home.to_string_lossy();
+        let home = home.trim_end_matches(['/', '\\']);
+        if home.is_empty() {
+            return path.to_string();
+        }
+
+        // Windows paths are case-insensitive
+        let matches_home = if cfg!(windows) {
+            path.len() >= home.len()
+                && path.is_char_boundary(home.len())
+                && path[..home.len()].eq_ignore_ascii_case(home)
+        } else {
+            path.starts_with(home)
+        };
+        if !matches_home {
+            return path.to_string();
+        }
+
+        let rest = &path[home.len()..];
+        if rest.is_empty() {
+            "~".to_string()
+        } else if rest.starts_with(['/', '\\']) {
+            format!("~{}", rest)
+        } else {
+            // e.g. /home/al vs /home/alice
+            path.to_string()
+        }
     }
 
     /// Extract directory name from path, handling both Unix and Windows separators
@@ -39,18 +177,46 @@ impl DirectorySegment {
 impl Segment for DirectorySegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
         let current_dir = &input.workspace.current_dir;
+        if current_dir.is_empty() {
+            super::skip_reason("no workspace.current_dir in input");
+            return None;
+        }
 
-        // Handle cross-platform path separators manually for better compatibility
-        let dir_name = Self::extract_directory_name(current_dir);
+        let display_path = if self.shorten_home {
+            Self::abbreviate_home(current_dir)
+        } else {
+            current_dir.clone()
+        };
+
+        let repo_path = if self.repo_relative {
+            Self::repo_relative_path(current_dir)
+        } else {
+            None
+        };
+
+        let dir_name = match (&repo_path, self.max_components) {
+            (Some(repo_path), Some(max_components)) => {
+                self.truncate_path(repo_path, max_components)
+            }
+            (Some(repo_path), None) => repo_path.clone(),
+            (None, Some(max_components)) => self.truncate_path(&display_path, max_components),
+            // Handle cross-platform path separators manually for better compatibility
+            (None, None) => Self::extract_directory_name(&display_path),
+        };
 
         // Store the full path in metadata for potential use
         let mut metadata = HashMap::new();
         metadata.insert("full_path".to_string(), current_dir.clone());
+        metadata.insert("display_path".to_string(), display_path);
+        if let Some(repo_path) = repo_path {
+            metadata.insert("repo_path".to_string(), repo_path);
+        }
 
         Some(SegmentData {
             primary: dir_name,
             secondary: String::new(),
             metadata,
+            raw: false,
         })
     }
 
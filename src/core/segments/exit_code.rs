@@ -0,0 +1,117 @@
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{AnsiColor, InputData, SegmentId};
+use std::collections::HashMap;
+
+/// Exit status of the last command: green `✓ 0`, red `✗ 1`
+///
+/// The status comes from the input's `exit_code`, falling back to an environment
+/// variable the shell exports; without either the segment stays hidden.
+pub struct ExitCodeSegment {
+    env_var: String,
+    success_icon: String,
+    failure_icon: String,
+    success_color: AnsiColor,
+    failure_color: AnsiColor,
+}
+
+impl Default for ExitCodeSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExitCodeSegment {
+    pub fn new() -> Self {
+        Self {
+            env_var: "CCLINE_EXIT_CODE".to_string(),
+            success_icon: "✓".to_string(),
+            failure_icon: "✗".to_string(),
+            success_color: AnsiColor::Color16 { c16: 10 },
+            failure_color: AnsiColor::Color16 { c16: 9 },
+        }
+    }
+
+    /// Environment variable read when the input has no `exit_code`, empty to disable
+    pub fn with_env_var(mut self, env_var: &str) -> Self {
+        self.env_var = env_var.to_string();
+        self
+    }
+
+    pub fn with_success_icon(mut self, success_icon: &str) -> Self {
+        self.success_icon = success_icon.to_string();
+        self
+    }
+
+    pub fn with_failure_icon(mut self, failure_icon: &str) -> Self {
+        self.failure_icon = failure_icon.to_string();
+        self
+    }
+
+    pub fn with_success_color(mut self, success_color: AnsiColor) -> Self {
+        self.success_color = success_color;
+        self
+    }
+
+    pub fn with_failure_color(mut self, failure_color: AnsiColor) -> Self {
+        self.failure_color = failure_color;
+        self
+    }
+
+    /// Status and where it was read from
+    fn status(&self, input: &InputData) -> Result<(i32, &'static str), String> {
+        if let Some(code) = input.exit_code {
+            return Ok((code, "input"));
+        }
+        if self.env_var.is_empty() {
+            return Err("no exit_code in input".to_string());
+        }
+
+        let value = std::env::var(&self.env_var)
+            .map_err(|_| format!("no exit_code in input and {} not set", self.env_var))?;
+        value
+            .trim()
+            .parse()
+            .map(|code| (code, "env"))
+            .map_err(|_| format!("{}={:?} is not an exit code", self.env_var, value))
+    }
+}
+
+impl Segment for ExitCodeSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let (code, source) = match self.status(input) {
+            Ok(status) => status,
+            Err(reason) => {
+                super::skip_reason(reason);
+                return None;
+            }
+        };
+
+        let (icon, color) = if code == 0 {
+            (&self.success_icon, &self.success_color)
+        } else {
+            (&self.failure_icon, &self.failure_color)
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("exit_code".to_string(), code.to_string());
+        metadata.insert("success".to_string(), (code == 0).to_string());
+        metadata.insert("source".to_string(), source.to_string());
+        if !icon.is_empty() {
+            metadata.insert(
+                "dynamic_icon".to_string(),
+                AnsiColorHelper::apply_foreground_color(icon, color),
+            );
+        }
+
+        Some(SegmentData {
+            primary: AnsiColorHelper::apply_foreground_color(&code.to_string(), color),
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ExitCode
+    }
+}
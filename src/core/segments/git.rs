@@ -1,6 +1,10 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{IconConfig, InputData, SegmentId};
+use crate::core::{relative_age, KeyedCache};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug)]
@@ -10,17 +14,147 @@ pub struct GitInfo {
     pub ahead: u32,
     pub behind: u32,
     pub sha: Option<String>,
+    pub is_worktree: bool,
+    /// Host of the `origin` remote, `Some("")` for remotes without one (local paths)
+    pub remote_host: Option<String>,
+    /// HEAD commit time in seconds since the epoch
+    pub commit_time: Option<i64>,
+    /// Upstream ref such as `origin/main`
+    pub upstream: Option<String>,
+    /// Tag pointing exactly at HEAD
+    pub tag: Option<String>,
+    /// Submodules that are modified, uninitialized or conflicted
+    pub dirty_submodules: Option<usize>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum GitStatus {
     Clean,
     Dirty,
     Conflicts,
 }
 
+/// Repository metadata locations, resolved by following `.git` files in linked worktrees
+#[derive(Debug, Clone)]
+pub struct GitDirs {
+    /// Root of the working tree (the directory containing `.git`)
+    pub work_tree: PathBuf,
+    /// Per-worktree git directory holding HEAD
+    pub git_dir: PathBuf,
+    /// Shared git directory holding refs and objects
+    pub common_dir: PathBuf,
+    /// Whether `work_tree` is a linked worktree created by `git worktree add`
+    pub is_worktree: bool,
+}
+
+impl GitDirs {
+    /// Walk up from `start` until a `.git` directory or `.git` file is found
+    pub fn discover<P: AsRef<Path>>(start: P) -> Option<Self> {
+        for dir in start.as_ref().ancestors() {
+            let dot_git = dir.join(".git");
+
+            if dot_git.is_dir() {
+                return Some(Self {
+                    work_tree: dir.to_path_buf(),
+                    git_dir: dot_git.clone(),
+                    common_dir: dot_git,
+                    is_worktree: false,
+                });
+            }
+
+            if dot_git.is_file() {
+                let git_dir = Self::read_gitdir_pointer(&dot_git)?;
+                let common_dir = Self::read_common_dir(&git_dir);
+                return Some(Self {
+                    work_tree: dir.to_path_buf(),
+                    is_worktree: common_dir.is_some(),
+                    common_dir: common_dir.unwrap_or_else(|| git_dir.clone()),
+                    git_dir,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Parse `gitdir: <path>` from a `.git` file (path may be relative to the file)
+    fn read_gitdir_pointer(dot_git: &Path) -> Option<PathBuf> {
+        let content = std::fs::read_to_string(dot_git).ok()?;
+        let target = content
+            .lines()
+            .find_map(|line| line.strip_prefix("gitdir:"))?
+            .trim();
+        if target.is_empty() {
+            return None;
+        }
+
+        let target = PathBuf::from(target);
+        if target.is_absolute() {
+            Some(target)
+        } else {
+            Some(dot_git.parent()?.join(target))
+        }
+    }
+
+    /// Linked worktrees point back to the main repository through a `commondir` file
+    fn read_common_dir(git_dir: &Path) -> Option<PathBuf> {
+        let content = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+        let common = PathBuf::from(content.trim());
+        if common.is_absolute() {
+            Some(common)
+        } else {
+            Some(git_dir.join(common))
+        }
+    }
+
+    /// Branch name read straight from HEAD, `None` when detached
+    pub fn head_branch(&self) -> Option<String> {
+        let head = std::fs::read_to_string(self.git_dir.join("HEAD")).ok()?;
+        head.trim()
+            .strip_prefix("ref: refs/heads/")
+            .map(|branch| branch.to_string())
+    }
+}
+
+/// Cached `git status` result of one repository, keyed by its root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GitStatusCacheEntry {
+    head_sha: String,
+    head_mtime: u64,
+    status: GitStatus,
+    ahead: u32,
+    behind: u32,
+}
+
+/// Identifies the repository state a cache entry is valid for
+struct RepoState {
+    root: String,
+    head_sha: String,
+    head_mtime: u64,
+}
+
+/// Hosting providers recognized in remote URLs, keyed by the name used in `host_icons`
+const KNOWN_HOSTS: [(&str, &str); 4] = [
+    ("github.com", "github"),
+    ("gitlab.com", "gitlab"),
+    ("bitbucket.org", "bitbucket"),
+    ("codeberg.org", "codeberg"),
+];
+
 pub struct GitSegment {
     show_sha: bool,
+    sha_prefix: String,
+    show_commit_age: bool,
+    show_upstream: bool,
+    /// Icon put before a tag on HEAD, `None` skips looking up tags
+    tag_icon: Option<IconConfig>,
+    show_submodules: bool,
+    status_cache: Option<KeyedCache<GitStatusCacheEntry>>,
+    state_icons: HashMap<String, IconConfig>,
+    use_nerd_font: bool,
+    /// Icons per provider or host name, `None` skips looking up the remote
+    host_icons: Option<HashMap<String, IconConfig>>,
+    host_icon_in_branch: bool,
 }
 
 impl Default for GitSegment {
@@ -31,7 +165,19 @@ impl Default for GitSegment {
 
 impl GitSegment {
     pub fn new() -> Self {
-        Self { show_sha: false }
+        Self {
+            show_sha: false,
+            sha_prefix: String::new(),
+            show_commit_age: false,
+            show_upstream: false,
+            tag_icon: None,
+            show_submodules: false,
+            status_cache: None,
+            state_icons: HashMap::new(),
+            use_nerd_font: false,
+            host_icons: None,
+            host_icon_in_branch: true,
+        }
     }
 
     pub fn with_sha(mut self, show_sha: bool) -> Self {
@@ -39,21 +185,229 @@ impl GitSegment {
         self
     }
 
+    /// Text put before the short SHA, e.g. `@` for `@1a2b3c4`
+    pub fn with_sha_prefix(mut self, sha_prefix: &str) -> Self {
+        self.sha_prefix = sha_prefix.to_string();
+        self
+    }
+
+    /// Show how long ago HEAD was committed, e.g. `3h ago`
+    pub fn with_commit_age(mut self, show_commit_age: bool) -> Self {
+        self.show_commit_age = show_commit_age;
+        self
+    }
+
+    /// Show the upstream ref after the branch when its name differs, e.g. `→origin/main`
+    pub fn with_upstream(mut self, show_upstream: bool) -> Self {
+        self.show_upstream = show_upstream;
+        self
+    }
+
+    /// Show the tag HEAD is exactly on after the branch, e.g. `main 🏷v1.2.0`
+    ///
+    /// `tag_icon` replaces the default tag glyph; an empty icon shows the bare tag.
+    pub fn with_tags(mut self, show_tags: bool, tag_icon: Option<IconConfig>) -> Self {
+        self.tag_icon = show_tags.then(|| {
+            tag_icon.unwrap_or_else(|| IconConfig {
+                plain: "🏷".to_string(),
+                nerd_font: "\u{f02b}".to_string(),
+            })
+        });
+        self
+    }
+
+    /// Warn with `⊕N` when submodules are out of sync with the recorded commit
+    pub fn with_submodules(mut self, show_submodules: bool) -> Self {
+        self.show_submodules = show_submodules;
+        self
+    }
+
+    /// Reuse `git status` results for `cache_duration` seconds (disabled when `None`)
+    pub fn with_status_cache(mut self, cache_duration: Option<u64>) -> Self {
+        self.status_cache = cache_duration
+            .map(|cache_duration| KeyedCache::new(".git_status_cache.json", cache_duration));
+        self
+    }
+
+    /// Icons replacing the branch glyph per state (`clean`, `dirty`, `conflict`)
+    pub fn with_state_icons(
+        mut self,
+        state_icons: HashMap<String, IconConfig>,
+        use_nerd_font: bool,
+    ) -> Self {
+        self.state_icons = state_icons;
+        self.use_nerd_font = use_nerd_font;
+        self
+    }
+
+    /// Look up `origin` and show an icon for its host (`github`, `gitlab`, `bitbucket`,
+    /// `codeberg`, any other host name, or `generic` for the rest)
+    ///
+    /// `host_icons` is merged over the built-in icons. The icon is always exposed as
+    /// `host_icon` metadata and prepended to the branch when `in_branch` is set.
+    pub fn with_host_icons(
+        mut self,
+        host_icons: HashMap<String, IconConfig>,
+        in_branch: bool,
+        use_nerd_font: bool,
+    ) -> Self {
+        let mut icons = Self::default_host_icons();
+        icons.extend(host_icons);
+        self.host_icons = Some(icons);
+        self.host_icon_in_branch = in_branch;
+        self.use_nerd_font = use_nerd_font;
+        self
+    }
+
+    fn default_host_icons() -> HashMap<String, IconConfig> {
+        // Nerd Fonts has no Codeberg glyph, so it shares the generic git icon
+        [
+            ("github", "🐙", "\u{f02a4}"),
+            ("gitlab", "🦊", "\u{f0ba0}"),
+            ("bitbucket", "🪣", "\u{f00a8}"),
+            ("codeberg", "⛰️", "\u{f02a2}"),
+            ("generic", "🌐", "\u{f02a2}"),
+        ]
+        .into_iter()
+        .map(|(name, plain, nerd_font)| {
+            (
+                name.to_string(),
+                IconConfig {
+                    plain: plain.to_string(),
+                    nerd_font: nerd_font.to_string(),
+                },
+            )
+        })
+        .collect()
+    }
+
+    /// Icon for a remote host: an exact host entry, then its provider, then `generic`
+    fn host_icon(&self, host: &str) -> Option<String> {
+        let icons = self.host_icons.as_ref()?;
+        let provider = KNOWN_HOSTS
+            .iter()
+            .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .map(|(_, provider)| *provider);
+
+        let icon = icons
+            .get(host)
+            .filter(|_| !host.is_empty())
+            .or_else(|| provider.and_then(|provider| icons.get(provider)))
+            .or_else(|| icons.get("generic"))?;
+        let glyph = if self.use_nerd_font {
+            &icon.nerd_font
+        } else {
+            &icon.plain
+        };
+        (!glyph.is_empty()).then(|| glyph.clone())
+    }
+
+    /// Host part of the `origin` URL, `None` when the repository has no `origin`
+    fn get_remote_host(&self, working_dir: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args(["--no-optional-locks", "remote", "get-url", "origin"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let url = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (!url.is_empty()).then(|| Self::parse_remote_host(&url))
+    }
+
+    /// Extract the lowercased host from `https://`, `ssh://` and scp-style `git@host:path` URLs
+    ///
+    /// Local paths and `file://` URLs have no host and yield an empty string.
+    fn parse_remote_host(url: &str) -> String {
+        let authority = match url.split_once("://") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or_default(),
+            // scp-like syntax needs a colon before any slash, otherwise it's a path
+            None => match url.split_once(':') {
+                Some((authority, _)) if !authority.contains('/') && authority.len() > 1 => {
+                    authority
+                }
+                _ => "",
+            },
+        };
+
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = match host.strip_prefix('[') {
+            // IPv6 literal, e.g. `[::1]:22`
+            Some(rest) => rest.split(']').next().unwrap_or_default(),
+            None => host.split(':').next().unwrap_or_default(),
+        };
+        host.to_lowercase()
+    }
+
+    /// Override for the status, `None` keeps the configured segment icon
+    fn state_icon(&self, status: GitStatus) -> Option<String> {
+        let key = match status {
+            GitStatus::Clean => "clean",
+            GitStatus::Dirty => "dirty",
+            GitStatus::Conflicts => "conflict",
+        };
+        let icon = self.state_icons.get(key)?;
+        let glyph = if self.use_nerd_font {
+            &icon.nerd_font
+        } else {
+            &icon.plain
+        };
+        (!glyph.is_empty()).then(|| glyph.clone())
+    }
+
     fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
         if !self.is_git_repository(working_dir) {
+            super::skip_reason(format!("{} is not inside a git repository", working_dir));
             return None;
         }
 
+        let git_dirs = GitDirs::discover(working_dir);
         let branch = self
             .get_branch(working_dir)
+            .or_else(|| git_dirs.as_ref().and_then(|dirs| dirs.head_branch()))
             .unwrap_or_else(|| "detached".to_string());
-        let status = self.get_status(working_dir);
-        let (ahead, behind) = self.get_ahead_behind(working_dir);
+        let (status, ahead, behind) = match &self.status_cache {
+            Some(cache) => self.get_cached_status(working_dir, git_dirs.as_ref(), cache),
+            None => {
+                let (ahead, behind) = self.get_ahead_behind(working_dir);
+                (self.get_status(working_dir), ahead, behind)
+            }
+        };
         let sha = if self.show_sha {
             self.get_sha(working_dir)
         } else {
             None
         };
+        let commit_time = if self.show_commit_age {
+            self.get_commit_time(working_dir)
+        } else {
+            None
+        };
+        let upstream = if self.show_upstream {
+            self.get_upstream(working_dir)
+        } else {
+            None
+        };
+        let tag = if self.tag_icon.is_some() {
+            self.get_tag(working_dir)
+        } else {
+            None
+        };
+        // Most repositories have no submodules, so don't spawn git for them
+        let dirty_submodules = match &git_dirs {
+            Some(dirs) if self.show_submodules && dirs.work_tree.join(".gitmodules").is_file() => {
+                self.get_dirty_submodules(working_dir)
+            }
+            _ => None,
+        };
+        let remote_host = if self.host_icons.is_some() {
+            self.get_remote_host(working_dir)
+        } else {
+            None
+        };
 
         Some(GitInfo {
             branch,
@@ -61,6 +415,12 @@ impl GitSegment {
             ahead,
             behind,
             sha,
+            is_worktree: git_dirs.is_some_and(|dirs| dirs.is_worktree),
+            remote_host,
+            commit_time,
+            upstream,
+            tag,
+            dirty_submodules,
         })
     }
 
@@ -151,6 +511,152 @@ impl GitSegment {
         }
     }
 
+    /// Get status and ahead/behind counts, reusing cached results when HEAD is unchanged
+    fn get_cached_status(
+        &self,
+        working_dir: &str,
+        git_dirs: Option<&GitDirs>,
+        cache: &KeyedCache<GitStatusCacheEntry>,
+    ) -> (GitStatus, u32, u32) {
+        let Some(repo) = git_dirs.and_then(|dirs| self.get_repo_state(working_dir, dirs)) else {
+            let (ahead, behind) = self.get_ahead_behind(working_dir);
+            return (self.get_status(working_dir), ahead, behind);
+        };
+
+        let entry = cache.get_or_insert_with(
+            &repo.root,
+            |entry| entry.head_sha == repo.head_sha && entry.head_mtime == repo.head_mtime,
+            || {
+                let (ahead, behind) = self.get_ahead_behind(working_dir);
+                GitStatusCacheEntry {
+                    head_sha: repo.head_sha.clone(),
+                    head_mtime: repo.head_mtime,
+                    status: self.get_status(working_dir),
+                    ahead,
+                    behind,
+                }
+            },
+        );
+
+        (entry.status, entry.ahead, entry.behind)
+    }
+
+    fn get_repo_state(&self, working_dir: &str, git_dirs: &GitDirs) -> Option<RepoState> {
+        let output = Command::new("git")
+            .args(["--no-optional-locks", "rev-parse", "HEAD"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let head_sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
+
+        // HEAD is rewritten on checkout, so its mtime catches branch switches.
+        // Linked worktrees keep their own HEAD inside the per-worktree git dir.
+        let head_mtime = std::fs::metadata(git_dirs.git_dir.join("HEAD"))
+            .and_then(|m| m.modified())
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+
+        Some(RepoState {
+            root: git_dirs.work_tree.to_string_lossy().to_string(),
+            head_sha,
+            head_mtime,
+        })
+    }
+
+    /// Abbreviated upstream ref, `None` when the branch tracks nothing
+    fn get_upstream(&self, working_dir: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args([
+                "--no-optional-locks",
+                "rev-parse",
+                "--abbrev-ref",
+                "@{upstream}",
+            ])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let upstream = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (!upstream.is_empty()).then_some(upstream)
+    }
+
+    /// First tag (by name) pointing at HEAD, `None` between tags
+    ///
+    /// Like `git describe --exact-match --tags`, but stable when several tags share a commit.
+    fn get_tag(&self, working_dir: &str) -> Option<String> {
+        let output = Command::new("git")
+            .args([
+                "--no-optional-locks",
+                "tag",
+                "--points-at",
+                "HEAD",
+                "--sort=refname",
+            ])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let tags = String::from_utf8(output.stdout).ok()?;
+        tags.lines()
+            .map(str::trim)
+            .find(|tag| !tag.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Number of submodules `git submodule status` doesn't report as in sync
+    ///
+    /// The status prefix is `-` when not checked out, `+` when the checked-out commit
+    /// differs from the superproject and `U` on merge conflicts.
+    fn get_dirty_submodules(&self, working_dir: &str) -> Option<usize> {
+        let output = Command::new("git")
+            .args(["--no-optional-locks", "submodule", "status"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let status = String::from_utf8(output.stdout).ok()?;
+        Some(
+            status
+                .lines()
+                .filter(|line| line.starts_with(['-', '+', 'U']))
+                .count(),
+        )
+    }
+
+    /// Committer timestamp of HEAD, `None` in a repository without commits
+    fn get_commit_time(&self, working_dir: &str) -> Option<i64> {
+        let output = Command::new("git")
+            .args(["--no-optional-locks", "log", "-1", "--format=%ct", "HEAD"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+    }
+
     fn get_sha(&self, working_dir: &str) -> Option<String> {
         let output = Command::new("git")
             .args(["--no-optional-locks", "rev-parse", "--short=7", "HEAD"])
@@ -184,8 +690,54 @@ impl Segment for GitSegment {
         if let Some(ref sha) = git_info.sha {
             metadata.insert("sha".to_string(), sha.clone());
         }
+        metadata.insert("worktree".to_string(), git_info.is_worktree.to_string());
+        if let Some(icon) = self.state_icon(git_info.status) {
+            metadata.insert("dynamic_icon".to_string(), icon);
+        }
+
+        let host_icon = git_info
+            .remote_host
+            .as_deref()
+            .and_then(|host| self.host_icon(host));
+        if let Some(host) = git_info
+            .remote_host
+            .as_ref()
+            .filter(|host| !host.is_empty())
+        {
+            metadata.insert("remote_host".to_string(), host.clone());
+        }
 
-        let primary = git_info.branch;
+        let mut primary = match host_icon {
+            Some(icon) => {
+                metadata.insert("host_icon".to_string(), icon.clone());
+                if self.host_icon_in_branch {
+                    format!("{} {}", icon, git_info.branch)
+                } else {
+                    git_info.branch.clone()
+                }
+            }
+            None => git_info.branch.clone(),
+        };
+
+        if let Some(upstream) = git_info.upstream {
+            // `origin/main` tracking `main` is the common case and not worth the space
+            let upstream_branch = upstream
+                .split_once('/')
+                .map_or(upstream.as_str(), |(_, branch)| branch);
+            if upstream_branch != git_info.branch {
+                primary = format!("{} →{}", primary, upstream);
+            }
+            metadata.insert("upstream".to_string(), upstream);
+        }
+        if let (Some(tag), Some(icon)) = (git_info.tag, &self.tag_icon) {
+            let glyph = if self.use_nerd_font {
+                &icon.nerd_font
+            } else {
+                &icon.plain
+            };
+            primary = format!("{} {}{}", primary, glyph, tag);
+            metadata.insert("tag".to_string(), tag);
+        }
         let mut status_parts = Vec::new();
 
         match git_info.status {
@@ -201,14 +753,29 @@ impl Segment for GitSegment {
             status_parts.push(format!("↓{}", git_info.behind));
         }
 
+        if let Some(count) = git_info.dirty_submodules {
+            metadata.insert("dirty_submodules".to_string(), count.to_string());
+            if count > 0 {
+                status_parts.push(format!("⊕{}", count));
+            }
+        }
+
         if let Some(ref sha) = git_info.sha {
-            status_parts.push(sha.clone());
+            status_parts.push(format!("{}{}", self.sha_prefix, sha));
+        }
+
+        if let Some(commit_time) = git_info.commit_time {
+            let age = relative_age(Utc::now().timestamp() - commit_time);
+            metadata.insert("commit_epoch".to_string(), commit_time.to_string());
+            metadata.insert("commit_age".to_string(), age.clone());
+            status_parts.push(age);
         }
 
         Some(SegmentData {
             primary,
             secondary: status_parts.join(" "),
             metadata,
+            raw: false,
         })
     }
 
@@ -216,3 +783,96 @@ impl Segment for GitSegment {
         SegmentId::Git
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run git in `dir` with a fixed identity, panicking on failure
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=ccline",
+                "-c",
+                "user.email=ccline@example.com",
+            ])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn init_repo(name: &str) -> PathBuf {
+        let repo = crate::test_support::temp_dir(name);
+        git(&repo, &["init", "-q"]);
+        std::fs::write(repo.join("README"), "readme\n").unwrap();
+        git(&repo, &["add", "README"]);
+        git(&repo, &["commit", "-q", "-m", "initial"]);
+        repo
+    }
+
+    #[test]
+    fn status_cache_is_reused_until_head_changes() {
+        let repo = init_repo("git-status-cache");
+        let cache_dir = crate::test_support::temp_dir("git-status-cache-file");
+        let cache = KeyedCache::with_path(Some(cache_dir.join("cache.json")), 60);
+        let segment = GitSegment::new();
+        let working_dir = repo.to_str().unwrap();
+        let status = || {
+            let dirs = GitDirs::discover(&repo).unwrap();
+            segment
+                .get_cached_status(working_dir, Some(&dirs), &cache)
+                .0
+        };
+        assert_eq!(status(), GitStatus::Clean);
+
+        // Same HEAD within cache_duration: the cached result is reused
+        std::fs::write(repo.join("untracked"), "new\n").unwrap();
+        assert_eq!(segment.get_status(working_dir), GitStatus::Dirty);
+        assert_eq!(status(), GitStatus::Clean);
+
+        // A new commit moves HEAD, so the status is computed again
+        git(&repo, &["add", "untracked"]);
+        git(&repo, &["commit", "-q", "-m", "second"]);
+        std::fs::write(repo.join("untracked"), "changed\n").unwrap();
+        assert_eq!(status(), GitStatus::Dirty);
+    }
+
+    #[test]
+    fn linked_worktree_resolves_its_own_head() {
+        let repo = init_repo("git-worktree");
+        let worktree = repo.with_file_name(format!(
+            "{}-linked",
+            repo.file_name().unwrap().to_string_lossy()
+        ));
+        let _ = std::fs::remove_dir_all(&worktree);
+        let path = worktree.to_str().unwrap();
+        git(&repo, &["worktree", "add", "-q", "-b", "feature", path]);
+
+        let dirs = GitDirs::discover(&worktree).unwrap();
+        assert!(dirs.is_worktree);
+        assert_eq!(dirs.work_tree, worktree);
+        // HEAD is per worktree, refs and objects stay in the main repository
+        let git_dir = dirs.git_dir.canonicalize().unwrap();
+        let common_dir = dirs.common_dir.canonicalize().unwrap();
+        assert_eq!(common_dir, repo.join(".git").canonicalize().unwrap());
+        assert!(git_dir.starts_with(common_dir.join("worktrees")));
+        assert!(git_dir.join("HEAD").is_file());
+        assert_eq!(dirs.head_branch().as_deref(), Some("feature"));
+
+        let input: InputData = serde_json::from_value(serde_json::json!({
+            "workspace": { "current_dir": path }
+        }))
+        .unwrap();
+        let data = GitSegment::new().collect(&input).unwrap();
+        assert_eq!(data.metadata["worktree"], "true");
+        assert_eq!(data.metadata["branch"], "feature");
+
+        let main = GitDirs::discover(&repo).unwrap();
+        assert!(!main.is_worktree);
+        let _ = std::fs::remove_dir_all(&worktree);
+    }
+}
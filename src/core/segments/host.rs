@@ -0,0 +1,113 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Machine name, by default only inside SSH sessions, e.g. `build-01`
+pub struct HostSegment {
+    ssh_only: bool,
+    fqdn: bool,
+}
+
+impl Default for HostSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostSegment {
+    pub fn new() -> Self {
+        Self {
+            ssh_only: true,
+            fqdn: false,
+        }
+    }
+
+    /// Render only when `$SSH_CONNECTION` shows the session is remote
+    pub fn with_ssh_only(mut self, ssh_only: bool) -> Self {
+        self.ssh_only = ssh_only;
+        self
+    }
+
+    /// Show the fully qualified name instead of the part before the first dot
+    pub fn with_fqdn(mut self, fqdn: bool) -> Self {
+        self.fqdn = fqdn;
+        self
+    }
+
+    /// Client address from `$SSH_CONNECTION` (`$SSH_CLIENT` on older servers)
+    fn ssh_client() -> Option<String> {
+        ["SSH_CONNECTION", "SSH_CLIENT"].iter().find_map(|name| {
+            let value = std::env::var(name).ok()?;
+            value.split_whitespace().next().map(|ip| ip.to_string())
+        })
+    }
+
+    fn hostname(&self) -> Option<String> {
+        if self.fqdn {
+            // Resolving the domain needs the hostname tool; fall back to the plain name
+            if let Some(name) = Self::command_output(&["-f"]) {
+                return Some(name);
+            }
+        }
+
+        if cfg!(windows) {
+            if let Some(name) = std::env::var("COMPUTERNAME")
+                .ok()
+                .filter(|n| !n.trim().is_empty())
+            {
+                return Some(name.trim().to_string());
+            }
+        }
+
+        std::fs::read_to_string("/proc/sys/kernel/hostname")
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| Self::command_output(&[]))
+    }
+
+    fn command_output(args: &[&str]) -> Option<String> {
+        let output = Command::new("hostname").args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!name.is_empty()).then_some(name)
+    }
+}
+
+impl Segment for HostSegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        let ssh_client = Self::ssh_client();
+        if self.ssh_only && ssh_client.is_none() {
+            super::skip_reason("not in an SSH session (ssh_only)");
+            return None;
+        }
+
+        let Some(hostname) = self.hostname() else {
+            super::skip_reason("hostname unavailable");
+            return None;
+        };
+        let short = hostname.split('.').next().unwrap_or(&hostname).to_string();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("hostname".to_string(), hostname.clone());
+        metadata.insert("short_hostname".to_string(), short.clone());
+        metadata.insert("ssh".to_string(), ssh_client.is_some().to_string());
+        if let Some(client) = ssh_client {
+            metadata.insert("ssh_client".to_string(), client);
+        }
+
+        Some(SegmentData {
+            primary: if self.fqdn { hostname } else { short },
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Host
+    }
+}
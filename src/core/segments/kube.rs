@@ -0,0 +1,338 @@
+use super::{Segment, SegmentData};
+use crate::config::{config_dir, InputData, SegmentId};
+use crate::core::KeyedCache;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Context parsed from one kubeconfig, cached under the kubeconfig's path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KubeContext {
+    /// Modification time of the kubeconfig in nanoseconds since the epoch
+    mtime: u64,
+    context: String,
+    namespace: Option<String>,
+}
+
+pub struct KubeSegment {
+    show_namespace: bool,
+    cache_duration: u64,
+    cache_path: Option<PathBuf>,
+}
+
+impl Default for KubeSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KubeSegment {
+    pub fn new() -> Self {
+        Self {
+            show_namespace: true,
+            cache_duration: 5,
+            cache_path: config_dir()
+                .ok()
+                .map(|dir| dir.join(".kube_context_cache.json")),
+        }
+    }
+
+    /// Show the context's namespace after its name when one is set
+    pub fn with_namespace(mut self, show_namespace: bool) -> Self {
+        self.show_namespace = show_namespace;
+        self
+    }
+
+    /// Seconds a parsed kubeconfig stays valid, 0 re-reads it on every prompt
+    pub fn with_cache_duration(mut self, cache_duration: u64) -> Self {
+        self.cache_duration = cache_duration;
+        self
+    }
+
+    /// First entry of `$KUBECONFIG`, falling back to `~/.kube/config`
+    fn kubeconfig_path() -> Option<PathBuf> {
+        if let Ok(paths) = std::env::var("KUBECONFIG") {
+            if let Some(first) = std::env::split_paths(&paths).find(|p| !p.as_os_str().is_empty()) {
+                return Some(first);
+            }
+        }
+
+        Some(dirs::home_dir()?.join(".kube").join("config"))
+    }
+
+    fn get_context(&self) -> Option<KubeContext> {
+        self.context_from(&Self::kubeconfig_path()?)
+    }
+
+    /// Context of the kubeconfig at `path`, reused while the file's mtime is unchanged
+    fn context_from(&self, path: &Path) -> Option<KubeContext> {
+        // No kubeconfig means no cluster work, so the segment stays hidden
+        let mtime = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|e| super::skip_reason(format!("cannot read {}: {}", path.display(), e)))
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+
+        let cache = (self.cache_duration > 0)
+            .then(|| KeyedCache::with_path(self.cache_path.clone(), self.cache_duration));
+        let key = path.to_string_lossy();
+        if let Some(cached) = cache
+            .as_ref()
+            .and_then(|cache| cache.get(&key, |entry: &KubeContext| entry.mtime == mtime))
+        {
+            return Some(cached);
+        }
+
+        let content = std::fs::read_to_string(path).ok()?;
+        let Some((context, namespace)) = Self::parse_kubeconfig(&content) else {
+            super::skip_reason(format!("no current-context in {}", path.display()));
+            return None;
+        };
+        let context = KubeContext {
+            mtime,
+            context,
+            namespace,
+        };
+
+        if let Some(cache) = &cache {
+            cache.insert(&key, context.clone());
+        }
+        Some(context)
+    }
+
+    /// Pull `current-context` and that context's namespace out of a kubeconfig
+    ///
+    /// Only the block-style YAML written by kubectl is understood.
+    fn parse_kubeconfig(content: &str) -> Option<(String, Option<String>)> {
+        let mut current_context = None;
+        let mut in_contexts = false;
+        // (name, namespace) of every entry in the top-level `contexts:` list
+        let mut contexts: Vec<(Option<String>, Option<String>)> = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim_end();
+            if trimmed.trim_start().is_empty() || trimmed.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let top_level = !trimmed.starts_with([' ', '\t', '-']);
+            if top_level {
+                in_contexts = false;
+                if let Some(value) = trimmed.strip_prefix("current-context:") {
+                    current_context = Some(Self::yaml_scalar(value)).filter(|v| !v.is_empty());
+                } else if trimmed == "contexts:" {
+                    in_contexts = true;
+                }
+                continue;
+            }
+
+            if !in_contexts {
+                continue;
+            }
+
+            let mut item = trimmed.trim_start();
+            if let Some(rest) = item.strip_prefix("- ").or_else(|| item.strip_prefix('-')) {
+                contexts.push((None, None));
+                item = rest.trim_start();
+            }
+
+            let Some(entry) = contexts.last_mut() else {
+                continue;
+            };
+            if let Some(value) = item.strip_prefix("name:") {
+                entry.0 = Some(Self::yaml_scalar(value));
+            } else if let Some(value) = item.strip_prefix("namespace:") {
+                entry.1 = Some(Self::yaml_scalar(value)).filter(|v| !v.is_empty());
+            }
+        }
+
+        let context = current_context?;
+        let namespace = contexts
+            .into_iter()
+            .find(|(name, _)| name.as_deref() == Some(context.as_str()))
+            .and_then(|(_, namespace)| namespace);
+
+        Some((context, namespace))
+    }
+
+    /// Strip surrounding quotes and trailing comments from a YAML scalar
+    fn yaml_scalar(value: &str) -> String {
+        let value = value.trim();
+        for quote in ['"', '\''] {
+            if let Some(inner) = value
+                .strip_prefix(quote)
+                .and_then(|v| v.split(quote).next())
+            {
+                return inner.to_string();
+            }
+        }
+        value
+            .split(" #")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+impl Segment for KubeSegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        let kube = self.get_context()?;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("context".to_string(), kube.context.clone());
+        // kubectl falls back to `default` when the context sets no namespace
+        metadata.insert(
+            "namespace".to_string(),
+            kube.namespace
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+        );
+
+        let secondary = match kube.namespace {
+            Some(namespace) if self.show_namespace => namespace,
+            _ => String::new(),
+        };
+
+        Some(SegmentData {
+            primary: kube.context,
+            secondary,
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Kube
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECONFIG: &str = r#"apiVersion: v1
+clusters:
+- cluster:
+    server: https://staging.example.com
+  name: staging
+contexts:
+- context:
+    cluster: staging
+    namespace: "web" # quoted, with a comment
+    user: admin
+  name: staging
+- name: 'prod-eu'
+  context:
+    cluster: prod
+    namespace: payments
+# current-context below is the one kubectl uses
+current-context: prod-eu   # trailing comment
+kind: Config
+users:
+- name: admin
+  user:
+    namespace: not-a-context
+"#;
+
+    fn parse(content: &str) -> Option<(String, Option<String>)> {
+        KubeSegment::parse_kubeconfig(content)
+    }
+
+    #[test]
+    fn current_context_picks_its_own_namespace() {
+        assert_eq!(
+            parse(KUBECONFIG),
+            Some(("prod-eu".to_string(), Some("payments".to_string())))
+        );
+
+        let staging =
+            KUBECONFIG.replace("current-context: prod-eu", "current-context: \"staging\"");
+        assert_eq!(
+            parse(&staging),
+            Some(("staging".to_string(), Some("web".to_string())))
+        );
+    }
+
+    #[test]
+    fn context_without_namespace_has_none() {
+        let content =
+            "contexts:\n- context:\n    cluster: dev\n  name: dev\ncurrent-context: dev\n";
+        assert_eq!(parse(content), Some(("dev".to_string(), None)));
+
+        // A context missing from the list still renders its name
+        let content = "contexts: []\ncurrent-context: gone\n";
+        assert_eq!(parse(content), Some(("gone".to_string(), None)));
+    }
+
+    #[test]
+    fn missing_or_empty_current_context_is_none() {
+        assert_eq!(parse("apiVersion: v1\ncontexts:\n- name: a\n"), None);
+        assert_eq!(parse("current-context: \"\"\n"), None);
+        assert_eq!(parse("current-context:\n"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn yaml_scalars_lose_quotes_and_comments() {
+        assert_eq!(KubeSegment::yaml_scalar(" plain "), "plain");
+        assert_eq!(KubeSegment::yaml_scalar("\"a # b\" # c"), "a # b");
+        assert_eq!(KubeSegment::yaml_scalar("'single'"), "single");
+        assert_eq!(KubeSegment::yaml_scalar("value # note"), "value");
+        assert_eq!(KubeSegment::yaml_scalar("a#b"), "a#b");
+    }
+
+    #[test]
+    fn cache_is_reused_until_the_kubeconfig_changes() {
+        let dir = crate::test_support::temp_dir("kube-cache");
+        let kubeconfig = dir.join("config");
+        std::fs::write(&kubeconfig, "current-context: first\n").unwrap();
+        let mtime = std::fs::metadata(&kubeconfig).unwrap().modified().unwrap();
+
+        let segment = KubeSegment {
+            cache_path: Some(dir.join("cache.json")),
+            ..KubeSegment::new().with_cache_duration(60)
+        };
+        assert_eq!(segment.context_from(&kubeconfig).unwrap().context, "first");
+
+        // Same mtime: the cached context is served without parsing the file
+        std::fs::write(&kubeconfig, "current-context: second\n").unwrap();
+        let file = std::fs::File::options()
+            .write(true)
+            .open(&kubeconfig)
+            .unwrap();
+        file.set_modified(mtime).unwrap();
+        drop(file);
+        assert_eq!(segment.context_from(&kubeconfig).unwrap().context, "first");
+
+        // Any other mtime re-reads it, as kubectl switching contexts does
+        let file = std::fs::File::options()
+            .write(true)
+            .open(&kubeconfig)
+            .unwrap();
+        file.set_modified(mtime + std::time::Duration::from_secs(1))
+            .unwrap();
+        drop(file);
+        assert_eq!(segment.context_from(&kubeconfig).unwrap().context, "second");
+
+        // Without caching every call parses the file
+        let uncached = KubeSegment {
+            cache_path: Some(dir.join("cache.json")),
+            ..KubeSegment::new().with_cache_duration(0)
+        };
+        std::fs::write(&kubeconfig, "current-context: third\n").unwrap();
+        assert_eq!(uncached.context_from(&kubeconfig).unwrap().context, "third");
+    }
+
+    #[test]
+    fn missing_kubeconfig_hides_the_segment() {
+        let dir = crate::test_support::temp_dir("kube-missing");
+        let segment = KubeSegment {
+            cache_path: None,
+            ..KubeSegment::new()
+        };
+        assert!(segment.context_from(&dir.join("config")).is_none());
+    }
+}
@@ -1,17 +1,45 @@
+pub mod battery;
+pub mod cli_proxy_api_quota;
+pub mod clock;
+pub mod cloud_profile;
+pub mod command;
 pub mod context_window;
 pub mod cost;
-pub mod cli_proxy_api_quota;
 pub mod directory;
+pub mod exit_code;
 pub mod git;
+pub mod host;
+pub mod kube;
 pub mod model;
+pub mod model_context;
 pub mod output_style;
+pub mod rate_limit;
 pub mod session;
 pub mod update;
 pub mod usage;
+pub mod venv;
 
 use crate::config::{InputData, SegmentId};
+use std::cell::RefCell;
 use std::collections::HashMap;
 
+thread_local! {
+    static SKIP_REASON: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Record why the running `collect` returns `None`, shown by `--explain` and `--verbose`
+///
+/// Segments are collected on their own threads, so the reason stays with the segment
+/// that reported it. The last reason reported wins.
+pub fn skip_reason(reason: impl Into<String>) {
+    SKIP_REASON.with(|slot| *slot.borrow_mut() = Some(reason.into()));
+}
+
+/// Take the reason recorded by [`skip_reason`] on this thread, if any
+pub fn take_skip_reason() -> Option<String> {
+    SKIP_REASON.with(|slot| slot.borrow_mut().take())
+}
+
 // New Segment trait for data collection only
 pub trait Segment {
     fn collect(&self, input: &InputData) -> Option<SegmentData>;
@@ -23,18 +51,32 @@ pub struct SegmentData {
     pub primary: String,
     pub secondary: String,
     pub metadata: HashMap<String, String>,
+    /// Text is already styled by the segment and is emitted without color or bold wrapping
+    pub raw: bool,
 }
 
 // Re-export all segment types
-pub use context_window::ContextWindowSegment;
-pub use cost::CostSegment;
+pub use battery::BatterySegment;
 pub use cli_proxy_api_quota::{CliProxyApiQuotaSegment, TrackedModel};
+pub use clock::ClockSegment;
+pub use cloud_profile::{CloudProfileSegment, CloudSource};
+pub use command::CommandSegment;
+pub use context_window::{
+    ContextDisplayMode, ContextSeverity, ContextThresholds, ContextWindowSegment,
+};
+pub use cost::{CostSegment, SymbolPlacement};
 // Re-export helper function for ANSI color formatting
 pub use cli_proxy_api_quota::CliProxyApiQuotaSegment as AnsiColorHelper;
 pub use directory::DirectorySegment;
+pub use exit_code::ExitCodeSegment;
 pub use git::GitSegment;
+pub use host::HostSegment;
+pub use kube::KubeSegment;
 pub use model::ModelSegment;
-pub use output_style::OutputStyleSegment;
+pub use model_context::ModelContextSegment;
+pub use output_style::{OutputStyleSegment, StyleIcon};
+pub use rate_limit::RateLimitSegment;
 pub use session::SessionSegment;
 pub use update::UpdateSegment;
 pub use usage::UsageSegment;
+pub use venv::{VenvSegment, VenvSource};
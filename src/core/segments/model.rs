@@ -1,26 +1,197 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, ModelConfig, SegmentId};
+use crate::config::{IconConfig, InputData, ModelConfig, SegmentId};
+use crate::utils::{display_width, truncate_to_width};
 use std::collections::HashMap;
 
-#[derive(Default)]
-pub struct ModelSegment;
+const DEFAULT_UNKNOWN_LABEL: &str = "unknown model";
+
+/// Providers recognized from the model id prefix
+const KNOWN_PROVIDERS: &[&str] = &["claude", "gemini", "gpt", "glm", "kimi", "qwen"];
+
+/// Trailing release labels dropped before the name itself is shortened
+const RELEASE_TAGS: &[&str] = &["latest", "preview", "exp", "experimental", "beta"];
+
+pub struct ModelSegment {
+    unknown_label: String,
+    provider_icons: HashMap<String, IconConfig>,
+    use_nerd_font: bool,
+    max_length: Option<usize>,
+    ellipsis: String,
+}
+
+impl Default for ModelSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ModelSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            unknown_label: DEFAULT_UNKNOWN_LABEL.to_string(),
+            provider_icons: HashMap::new(),
+            use_nerd_font: false,
+            max_length: None,
+            ellipsis: "…".to_string(),
+        }
+    }
+
+    /// Label shown when Claude Code reports an empty model id
+    pub fn with_unknown_label(mut self, label: &str) -> Self {
+        self.unknown_label = label.to_string();
+        self
+    }
+
+    /// Icons prepended per detected provider; a `default` entry covers unrecognized ids
+    pub fn with_provider_icons(
+        mut self,
+        provider_icons: HashMap<String, IconConfig>,
+        use_nerd_font: bool,
+    ) -> Self {
+        self.provider_icons = provider_icons;
+        self.use_nerd_font = use_nerd_font;
+        self
+    }
+
+    /// Shorten names wider than `max_length` columns (provider icon included)
+    ///
+    /// Dated snapshots and bracketed suffixes go first, then the leading family word
+    /// is abbreviated, and only then is the name cut with `ellipsis`.
+    pub fn with_max_length(mut self, max_length: usize, ellipsis: &str) -> Self {
+        self.max_length = Some(max_length);
+        self.ellipsis = ellipsis.to_string();
+        self
+    }
+
+    /// Apply the shortening steps in order, stopping as soon as the name fits
+    fn shorten_name(&self, name: &str, max_width: usize) -> String {
+        let mut name = name.trim().to_string();
+        let steps: [fn(&str) -> Option<String>; 3] = [
+            Self::strip_bracketed_suffix,
+            Self::strip_version_suffix,
+            Self::abbreviate_family,
+        ];
+
+        for step in steps {
+            if display_width(&name) <= max_width {
+                return name;
+            }
+            if let Some(shorter) = step(&name) {
+                name = shorter;
+            }
+        }
+
+        truncate_to_width(&name, max_width, &self.ellipsis)
+    }
+
+    /// `Claude 3.5 Sonnet (20241022)` → `Claude 3.5 Sonnet`, also for `[1m]`-style tags
+    fn strip_bracketed_suffix(name: &str) -> Option<String> {
+        let mut name = name.trim_end();
+        while let Some(open) = match name.chars().last()? {
+            ')' => name.rfind('('),
+            ']' => name.rfind('['),
+            _ => None,
+        } {
+            let stripped = name[..open].trim_end();
+            if stripped.is_empty() {
+                break;
+            }
+            name = stripped;
+        }
+        Some(name.to_string())
+    }
+
+    /// `claude-3-5-sonnet-20241022` → `claude-3-5-sonnet`, also release tags like `-latest`
+    fn strip_version_suffix(name: &str) -> Option<String> {
+        let mut name = name;
+        while let Some(index) = name.rfind([' ', '-', '_', '@']) {
+            let token = &name[index + 1..];
+            let is_date = token.len() == 8 && token.bytes().all(|b| b.is_ascii_digit());
+            let is_tag = RELEASE_TAGS.contains(&token.to_lowercase().as_str());
+            if !(is_date || is_tag) || index == 0 {
+                break;
+            }
+            name = &name[..index];
+        }
+        Some(name.to_string())
+    }
+
+    /// `Claude 3.5 Sonnet` → `C 3.5 Sonnet`; short families like `gpt` stay as they are
+    fn abbreviate_family(name: &str) -> Option<String> {
+        let index = name.find([' ', '-', '_'])?;
+        let family = &name[..index];
+        if family.chars().count() <= 3 || !family.chars().all(char::is_alphabetic) {
+            return None;
+        }
+        let initial = family.chars().next()?;
+        Some(format!("{}{}", initial, &name[index..]))
+    }
+
+    /// Infer the provider from the model id, ignoring routing prefixes like `openrouter/`
+    fn detect_provider(model_id: &str) -> Option<&'static str> {
+        let id = model_id.to_lowercase();
+        let name = id.rsplit('/').next().unwrap_or(&id);
+        KNOWN_PROVIDERS
+            .iter()
+            .find(|provider| name.starts_with(*provider))
+            .copied()
+    }
+
+    fn provider_icon(&self, provider: Option<&str>) -> Option<&str> {
+        let icon = provider
+            .and_then(|p| self.provider_icons.get(p))
+            .or_else(|| self.provider_icons.get("default"))?;
+        let glyph = if self.use_nerd_font {
+            &icon.nerd_font
+        } else {
+            &icon.plain
+        };
+        (!glyph.is_empty()).then_some(glyph.as_str())
     }
 }
 
 impl Segment for ModelSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let model_id = input.model.id.trim();
+        let is_unknown = model_id.is_empty();
+
         let mut metadata = HashMap::new();
         metadata.insert("model_id".to_string(), input.model.id.clone());
         metadata.insert("display_name".to_string(), input.model.display_name.clone());
+        metadata.insert("unknown".to_string(), is_unknown.to_string());
+
+        let provider = Self::detect_provider(model_id);
+        metadata.insert(
+            "provider".to_string(),
+            provider.unwrap_or("unknown").to_string(),
+        );
+
+        // Always render something so the icon keeps the layout stable
+        let mut primary = if is_unknown {
+            self.unknown_label.clone()
+        } else {
+            self.format_model_name(model_id, &input.model.display_name)
+        };
+
+        let icon = self.provider_icon(provider);
+        if let Some(max_length) = self.max_length {
+            let icon_width = icon.map_or(0, |icon| display_width(icon) + 1);
+            let shortened = self.shorten_name(&primary, max_length.saturating_sub(icon_width));
+            if shortened != primary {
+                metadata.insert("full_name".to_string(), primary);
+            }
+            primary = shortened;
+        }
+
+        if let Some(icon) = icon {
+            primary = format!("{} {}", icon, primary);
+        }
 
         Some(SegmentData {
-            primary: self.format_model_name(&input.model.id, &input.model.display_name),
+            primary,
             secondary: String::new(),
             metadata,
+            raw: false,
         })
     }
 
@@ -36,9 +207,51 @@ impl ModelSegment {
         // Try to get display name from external config first
         if let Some(config_name) = model_config.get_display_name(id) {
             config_name
-        } else {
+        } else if !display_name.trim().is_empty() {
             // Fallback to Claude Code's official display_name for unrecognized models
             display_name.to_string()
+        } else {
+            // No display name either, show the raw model id
+            id.to_string()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(segment: &ModelSegment, input: serde_json::Value) -> SegmentData {
+        let input: InputData = serde_json::from_value(input).unwrap();
+        segment.collect(&input).unwrap()
+    }
+
+    #[test]
+    fn empty_and_missing_ids_render_the_unknown_label() {
+        let segment = ModelSegment::new().with_unknown_label("no model");
+        let payloads = [
+            serde_json::json!({"model": {"id": ""}}),
+            serde_json::json!({"model": {"id": "   "}}),
+            serde_json::json!({"model": {"display_name": "Sonnet 4"}}),
+            serde_json::json!({"model": {}}),
+            serde_json::json!({}),
+        ];
+
+        for payload in payloads {
+            let data = collect(&segment, payload.clone());
+            assert_eq!(data.primary, "no model", "{}", payload);
+            assert_eq!(data.metadata["unknown"], "true", "{}", payload);
+            assert_eq!(data.metadata["provider"], "unknown", "{}", payload);
+        }
+    }
+
+    #[test]
+    fn unmatched_ids_are_not_unknown() {
+        let data = collect(
+            &ModelSegment::new(),
+            serde_json::json!({"model": {"id": "ccline-test-unmatched", "display_name": ""}}),
+        );
+        assert_eq!(data.primary, "ccline-test-unmatched");
+        assert_eq!(data.metadata["unknown"], "false");
+    }
+}
@@ -0,0 +1,167 @@
+use super::{
+    AnsiColorHelper, ContextThresholds, ContextWindowSegment, ModelSegment, Segment, SegmentData,
+};
+use crate::config::{InputData, SegmentId};
+
+/// Compact badge fusing the model name with its context usage, e.g. `Opus 52%`
+pub struct ModelContextSegment {
+    model: ModelSegment,
+    context: ContextWindowSegment,
+    thresholds: ContextThresholds,
+}
+
+impl Default for ModelContextSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModelContextSegment {
+    pub fn new() -> Self {
+        Self {
+            model: ModelSegment::new(),
+            context: ContextWindowSegment::new(),
+            thresholds: ContextThresholds::default(),
+        }
+    }
+
+    /// Use a preconfigured model collector (e.g. with a custom unknown label)
+    pub fn with_model(mut self, model: ModelSegment) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn with_thresholds(mut self, thresholds: ContextThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+}
+
+impl Segment for ModelContextSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let model_data = self.model.collect(input)?;
+
+        let mut metadata = model_data.metadata;
+
+        // Context usage is optional, without it the badge is just the model name
+        let percentage = self
+            .context
+            .collect(input)
+            .and_then(|data| {
+                metadata.insert(
+                    "limit".to_string(),
+                    data.metadata.get("limit").cloned().unwrap_or_default(),
+                );
+                data.metadata.get("percentage")?.parse::<f64>().ok()
+            })
+            .filter(|p| p.is_finite())
+            .map(|p| p.clamp(0.0, 100.0));
+
+        let secondary = match percentage {
+            Some(percentage) => {
+                let severity = self.thresholds.severity(percentage);
+                metadata.insert("percentage".to_string(), percentage.to_string());
+                metadata.insert("severity".to_string(), severity.as_str().to_string());
+
+                // Only the percentage is recolored, the name keeps the base text color
+                let text = format!("{:.0}%", percentage);
+                match self.thresholds.color_for(severity) {
+                    Some(color) => AnsiColorHelper::apply_foreground_color(&text, color),
+                    None => text,
+                }
+            }
+            None => String::new(),
+        };
+
+        Some(SegmentData {
+            primary: model_data.primary,
+            secondary,
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ModelContext
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(transcript: &std::path::Path) -> InputData {
+        serde_json::from_value(serde_json::json!({
+            "model": {"id": "ccline-test-model", "display_name": "Test Model"},
+            "transcript_path": transcript,
+        }))
+        .unwrap()
+    }
+
+    /// Transcript whose last assistant turn used `tokens` of the default 200k limit
+    fn transcript(name: &str, tokens: u32) -> std::path::PathBuf {
+        let path = crate::test_support::temp_dir(name).join("session.jsonl");
+        let line = serde_json::json!({
+            "type": "assistant",
+            "message": {"usage": {"input_tokens": tokens, "output_tokens": 0}},
+        });
+        std::fs::write(&path, format!("{}\n", line)).unwrap();
+        path
+    }
+
+    #[test]
+    fn missing_context_shows_just_the_model_name() {
+        let missing = crate::test_support::temp_dir("model-context-missing").join("none.jsonl");
+        let input = input(&missing);
+
+        let data = ModelContextSegment::new().collect(&input).unwrap();
+        let model = ModelSegment::new().collect(&input).unwrap();
+
+        assert_eq!(data.primary, model.primary);
+        assert!(data.secondary.is_empty());
+        assert!(!data.metadata.contains_key("percentage"));
+        assert!(!data.metadata.contains_key("severity"));
+    }
+
+    #[test]
+    fn only_the_percentage_is_recolored_by_threshold() {
+        let thresholds = ContextThresholds::default();
+        let cases = [
+            ("model-context-normal", 20_000, "10%", "normal", None),
+            (
+                "model-context-warn",
+                150_000,
+                "75%",
+                "warning",
+                Some(&thresholds.warn_color),
+            ),
+            (
+                "model-context-critical",
+                190_000,
+                "95%",
+                "critical",
+                Some(&thresholds.critical_color),
+            ),
+        ];
+
+        for (name, tokens, text, severity, color) in cases {
+            let input = input(&transcript(name, tokens));
+            let data = ModelContextSegment::new().collect(&input).unwrap();
+
+            // The name carries no color of its own, so the configured text color applies
+            assert_eq!(
+                data.primary,
+                ModelSegment::new().collect(&input).unwrap().primary
+            );
+            assert!(!data.primary.contains('\x1b'), "{:?}", data.primary);
+
+            assert_eq!(crate::utils::strip_ansi(&data.secondary), text);
+            assert_eq!(data.metadata["severity"], severity);
+            let expected = match color {
+                Some(color) => AnsiColorHelper::apply_foreground_color(text, color),
+                None => text.to_string(),
+            };
+            assert_eq!(data.secondary, expected, "{}", name);
+        }
+    }
+}
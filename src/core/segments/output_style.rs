@@ -1,30 +1,130 @@
-use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{AnsiColor, IconConfig, InputData, SegmentId};
 use std::collections::HashMap;
 
-#[derive(Default)]
-pub struct OutputStyleSegment;
+/// Icon and text color shown for one output style
+#[derive(Debug, Clone)]
+pub struct StyleIcon {
+    pub icon: Option<IconConfig>,
+    pub color: Option<AnsiColor>,
+}
+
+/// Active Claude Code output style, with a per-style icon and color
+pub struct OutputStyleSegment {
+    /// Keyed by lowercase style name, `generic` covers unknown styles
+    style_icons: HashMap<String, StyleIcon>,
+    use_nerd_font: bool,
+}
+
+impl Default for OutputStyleSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl OutputStyleSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            style_icons: Self::default_style_icons(),
+            use_nerd_font: false,
+        }
+    }
+
+    /// Merge `style_icons` over the built-in set; `default` keeps the segment icon
+    ///
+    /// An override setting only the icon or only the color keeps the built-in other half.
+    pub fn with_style_icons(
+        mut self,
+        style_icons: HashMap<String, StyleIcon>,
+        use_nerd_font: bool,
+    ) -> Self {
+        for (name, style_icon) in style_icons {
+            let name = name.to_lowercase();
+            let merged = match self.style_icons.remove(&name) {
+                Some(builtin) => StyleIcon {
+                    icon: style_icon.icon.or(builtin.icon),
+                    color: style_icon.color.or(builtin.color),
+                },
+                None => style_icon,
+            };
+            self.style_icons.insert(name, merged);
+        }
+        self.use_nerd_font = use_nerd_font;
+        self
+    }
+
+    fn default_style_icons() -> HashMap<String, StyleIcon> {
+        [
+            ("explanatory", "📖", "\u{f02d}", Some(4)),
+            ("learning", "🎓", "\u{f19d}", Some(2)),
+            ("concise", "✂", "\u{f066}", Some(3)),
+            ("generic", "💬", "\u{f075}", None),
+        ]
+        .into_iter()
+        .map(|(name, plain, nerd_font, c16)| {
+            (
+                name.to_string(),
+                StyleIcon {
+                    icon: Some(IconConfig {
+                        plain: plain.to_string(),
+                        nerd_font: nerd_font.to_string(),
+                    }),
+                    color: c16.map(|c16| AnsiColor::Color16 { c16 }),
+                },
+            )
+        })
+        .collect()
+    }
+
+    /// Entry for a style name; the built-in `default` style has none
+    fn style_icon(&self, name: &str) -> Option<&StyleIcon> {
+        let name = name.to_lowercase();
+        match self.style_icons.get(&name) {
+            Some(icon) => Some(icon),
+            None if name == "default" => None,
+            None => self.style_icons.get("generic"),
+        }
     }
 }
 
 impl Segment for OutputStyleSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let output_style = input.output_style.as_ref()?;
-
-        // Primary display: style name
-        let primary = output_style.name.clone();
+        let Some(output_style) = input
+            .output_style
+            .as_ref()
+            .filter(|style| !style.name.is_empty())
+        else {
+            super::skip_reason("no output_style in input");
+            return None;
+        };
 
         let mut metadata = HashMap::new();
         metadata.insert("style_name".to_string(), output_style.name.clone());
 
+        // Primary display: style name
+        let mut primary = output_style.name.clone();
+
+        if let Some(style_icon) = self.style_icon(&output_style.name) {
+            if let Some(icon) = &style_icon.icon {
+                let glyph = if self.use_nerd_font {
+                    &icon.nerd_font
+                } else {
+                    &icon.plain
+                };
+                if !glyph.is_empty() {
+                    metadata.insert("dynamic_icon".to_string(), glyph.clone());
+                }
+            }
+            if let Some(color) = &style_icon.color {
+                primary = AnsiColorHelper::apply_foreground_color(&primary, color);
+            }
+        }
+
         Some(SegmentData {
             primary,
             secondary: String::new(),
             metadata,
+            raw: false,
         })
     }
 
@@ -0,0 +1,103 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use std::collections::HashMap;
+
+pub struct RateLimitSegment {
+    show_countdown: bool,
+}
+
+impl Default for RateLimitSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimitSegment {
+    pub fn new() -> Self {
+        Self {
+            show_countdown: true,
+        }
+    }
+
+    pub fn with_countdown(mut self, show_countdown: bool) -> Self {
+        self.show_countdown = show_countdown;
+        self
+    }
+
+    fn format_retry_after(seconds: u64) -> String {
+        if seconds < 60 {
+            format!("{}s", seconds)
+        } else if seconds < 3600 {
+            let minutes = seconds / 60;
+            let secs = seconds % 60;
+            if secs == 0 {
+                format!("{}m", minutes)
+            } else {
+                format!("{}m{}s", minutes, secs)
+            }
+        } else {
+            let hours = seconds / 3600;
+            let minutes = (seconds % 3600) / 60;
+            if minutes == 0 {
+                format!("{}h", hours)
+            } else {
+                format!("{}h{}m", hours, minutes)
+            }
+        }
+    }
+}
+
+impl Segment for RateLimitSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let Some(rate_limit) = input.rate_limit.as_ref() else {
+            super::skip_reason("no rate_limit in input");
+            return None;
+        };
+
+        // Nothing to show while requests are flowing normally
+        if !rate_limit.limited && !rate_limit.retrying {
+            super::skip_reason("not rate limited");
+            return None;
+        }
+
+        let state = if rate_limit.limited {
+            "rate_limited"
+        } else {
+            "retrying"
+        };
+
+        let primary = if rate_limit.limited {
+            "rate limited".to_string()
+        } else {
+            match rate_limit.retry_attempt {
+                Some(attempt) => format!("retrying #{}", attempt),
+                None => "retrying".to_string(),
+            }
+        };
+
+        let secondary = match rate_limit.retry_after_seconds {
+            Some(seconds) if self.show_countdown => Self::format_retry_after(seconds),
+            _ => String::new(),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("state".to_string(), state.to_string());
+        if let Some(seconds) = rate_limit.retry_after_seconds {
+            metadata.insert("retry_after_seconds".to_string(), seconds.to_string());
+        }
+        if let Some(attempt) = rate_limit.retry_attempt {
+            metadata.insert("retry_attempt".to_string(), attempt.to_string());
+        }
+
+        Some(SegmentData {
+            primary,
+            secondary,
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::RateLimit
+    }
+}
@@ -1,13 +1,81 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{Cost, InputData, SegmentId};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
 #[derive(Default)]
-pub struct SessionSegment;
+pub struct SessionSegment {
+    show_duration: bool,
+    show_messages: bool,
+}
 
 impl SessionSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Show wall-clock time since the session started instead of the reported duration
+    pub fn with_duration(mut self, show_duration: bool) -> Self {
+        self.show_duration = show_duration;
+        self
+    }
+
+    /// Append the number of prompts in the conversation, e.g. `✉12`
+    pub fn with_messages(mut self, show_messages: bool) -> Self {
+        self.show_messages = show_messages;
+        self
+    }
+
+    /// Count the user turns in a transcript
+    ///
+    /// Tool results and meta entries are also recorded as `user` lines, so only
+    /// entries carrying text the user typed are counted.
+    fn count_messages(transcript_path: &str) -> Option<usize> {
+        if transcript_path.is_empty() {
+            return None;
+        }
+        let content = std::fs::read_to_string(transcript_path).ok()?;
+
+        let count = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|entry| entry.get("type").and_then(|v| v.as_str()) == Some("user"))
+            .filter(|entry| entry.get("isMeta").and_then(|v| v.as_bool()) != Some(true))
+            .filter(|entry| match entry.pointer("/message/content") {
+                Some(serde_json::Value::String(_)) => true,
+                Some(serde_json::Value::Array(items)) => items
+                    .iter()
+                    .any(|item| item.get("type").and_then(|v| v.as_str()) == Some("text")),
+                _ => false,
+            })
+            .count();
+
+        Some(count)
+    }
+
+    fn elapsed_seconds(session_start: &str) -> Option<u64> {
+        let started_at = DateTime::parse_from_rfc3339(session_start).ok()?;
+        let elapsed = Utc::now().signed_duration_since(started_at.with_timezone(&Utc));
+        // Clock skew can put the start slightly in the future
+        Some(elapsed.num_seconds().max(0) as u64)
+    }
+
+    fn format_elapsed(seconds: u64) -> String {
+        if seconds < 60 {
+            "<1m".to_string()
+        } else if seconds < 3600 {
+            format!("{}m", seconds / 60)
+        } else if seconds < 86_400 {
+            let hours = seconds / 3600;
+            let minutes = (seconds % 3600) / 60;
+            if minutes == 0 {
+                format!("{}h", hours)
+            } else {
+                format!("{}h{}m", hours, minutes)
+            }
+        } else {
+            format!("{}d", seconds / 86_400)
+        }
     }
 
     fn format_duration(ms: u64) -> String {
@@ -38,17 +106,37 @@ impl SessionSegment {
 
 impl Segment for SessionSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let cost_data = input.cost.as_ref()?;
+        let elapsed_seconds = if self.show_duration {
+            input
+                .session_start
+                .as_deref()
+                .and_then(Self::elapsed_seconds)
+        } else {
+            None
+        };
+
+        let default_cost = Cost::default();
+        let cost_data = match (input.cost.as_ref(), elapsed_seconds) {
+            (Some(cost), _) => cost,
+            (None, Some(_)) => &default_cost,
+            (None, None) => {
+                super::skip_reason("no cost or session_start in input");
+                return None;
+            }
+        };
 
-        // Primary display: total duration
-        let primary = if let Some(duration) = cost_data.total_duration_ms {
+        // Primary display: elapsed session time, or total duration
+        let primary = if let Some(seconds) = elapsed_seconds {
+            Self::format_elapsed(seconds)
+        } else if let Some(duration) = cost_data.total_duration_ms {
             Self::format_duration(duration)
         } else {
+            super::skip_reason("no cost.total_duration_ms in input");
             return None;
         };
 
         // Secondary display: line changes if available
-        let secondary = match (cost_data.total_lines_added, cost_data.total_lines_removed) {
+        let mut secondary = match (cost_data.total_lines_added, cost_data.total_lines_removed) {
             (Some(added), Some(removed)) if added > 0 || removed > 0 => {
                 format!("+{} -{}", added, removed)
             }
@@ -62,6 +150,20 @@ impl Segment for SessionSegment {
         };
 
         let mut metadata = HashMap::new();
+        if self.show_messages {
+            if let Some(count) = Self::count_messages(&input.transcript_path) {
+                metadata.insert("message_count".to_string(), count.to_string());
+                let messages = format!("✉{}", count);
+                secondary = if secondary.is_empty() {
+                    messages
+                } else {
+                    format!("{} {}", secondary, messages)
+                };
+            }
+        }
+        if let Some(seconds) = elapsed_seconds {
+            metadata.insert("elapsed_seconds".to_string(), seconds.to_string());
+        }
         if let Some(duration) = cost_data.total_duration_ms {
             metadata.insert("duration_ms".to_string(), duration.to_string());
         }
@@ -79,6 +181,7 @@ impl Segment for SessionSegment {
             primary,
             secondary,
             metadata,
+            raw: false,
         })
     }
 
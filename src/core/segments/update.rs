@@ -1,25 +1,154 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{config_dir, InputData, SegmentId};
 use crate::updater::UpdateState;
+#[cfg(feature = "self-update")]
+use crate::utils::atomic_write;
+#[cfg(feature = "self-update")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "self-update")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Default)]
-pub struct UpdateSegment;
+/// Cache of the latest release check, so GitHub is hit at most once per cache_duration
+#[cfg(feature = "self-update")]
+#[derive(Debug, Serialize, Deserialize)]
+struct UpdateCheckCache {
+    repo: String,
+    /// Newer release version, or None when already up to date
+    latest_version: Option<String>,
+    cached_at: String,
+}
+
+pub struct UpdateSegment {
+    repo: String,
+    cache_duration: u64,
+}
+
+impl Default for UpdateSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl UpdateSegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            repo: "Haleclipse/CCometixLine".to_string(),
+            cache_duration: 3600,
+        }
+    }
+
+    /// GitHub repository slug (`owner/name`) to check releases of
+    pub fn with_repo(mut self, repo: &str) -> Self {
+        self.repo = repo.to_string();
+        self
+    }
+
+    /// Seconds a release check result stays valid
+    pub fn with_cache_duration(mut self, cache_duration: u64) -> Self {
+        self.cache_duration = cache_duration;
+        self
+    }
+
+    #[cfg(feature = "self-update")]
+    fn get_cache_path() -> Option<std::path::PathBuf> {
+        Some(config_dir().ok()?.join(".update_check_cache.json"))
+    }
+
+    #[cfg(feature = "self-update")]
+    fn load_cache(&self) -> Option<UpdateCheckCache> {
+        let cache_path = Self::get_cache_path()?;
+        if !cache_path.exists() {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(&cache_path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    #[cfg(feature = "self-update")]
+    fn save_cache(&self, cache: &UpdateCheckCache) {
+        if let Some(cache_path) = Self::get_cache_path() {
+            if let Some(parent) = cache_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if let Ok(json) = serde_json::to_string_pretty(cache) {
+                let _ = atomic_write(&cache_path, json);
+            }
+        }
+    }
+
+    #[cfg(feature = "self-update")]
+    fn is_cache_valid(&self, cache: &UpdateCheckCache) -> bool {
+        if cache.repo != self.repo {
+            return false;
+        }
+        if let Ok(cached_at) = DateTime::parse_from_rfc3339(&cache.cached_at) {
+            let now = Utc::now();
+            let elapsed = now.signed_duration_since(cached_at.with_timezone(&Utc));
+            elapsed.num_seconds() < self.cache_duration as i64
+        } else {
+            false
+        }
+    }
+
+    /// Latest release version if it is newer than the running build
+    #[cfg(feature = "self-update")]
+    fn latest_version(&self) -> Option<String> {
+        use crate::updater::github::check_for_updates_in;
+
+        if let Some(cache) = self.load_cache().filter(|c| self.is_cache_valid(c)) {
+            return cache.latest_version;
+        }
+
+        // Network failures produce no output and are retried on the next render
+        let release = check_for_updates_in(&self.repo, std::time::Duration::from_secs(3)).ok()?;
+        let latest_version = release.map(|r| r.version());
+
+        self.save_cache(&UpdateCheckCache {
+            repo: self.repo.clone(),
+            latest_version: latest_version.clone(),
+            cached_at: Utc::now().to_rfc3339(),
+        });
+
+        latest_version
+    }
+
+    #[cfg(not(feature = "self-update"))]
+    fn latest_version(&self) -> Option<String> {
+        None
     }
 }
 
 impl Segment for UpdateSegment {
     fn collect(&self, _input: &InputData) -> Option<SegmentData> {
-        // Load update state and check for update status
-        let update_state = UpdateState::load();
+        // A self-update in progress or just finished takes precedence over the release check
+        if let Some(status_text) = UpdateState::load().status_text() {
+            return Some(SegmentData {
+                primary: status_text,
+                secondary: String::new(),
+                metadata: HashMap::new(),
+                raw: false,
+            });
+        }
+
+        let Some(latest_version) = self.latest_version() else {
+            super::skip_reason("no newer release, or the update check failed");
+            return None;
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "current_version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        );
+        metadata.insert("latest_version".to_string(), latest_version.clone());
 
-        update_state.status_text().map(|status_text| SegmentData {
-            primary: status_text,
+        Some(SegmentData {
+            primary: format!("⇡ v{}", latest_version),
             secondary: String::new(),
-            metadata: std::collections::HashMap::new(),
+            metadata,
+            raw: false,
         })
     }
 
@@ -1,6 +1,7 @@
-use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
-use crate::utils::credentials;
+use super::{AnsiColorHelper, Segment, SegmentData};
+use crate::config::{config_dir, AnsiColor, InputData, NormalizedUsage, SegmentId};
+use crate::core::humanize;
+use crate::utils::{atomic_write, credentials};
 use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -47,6 +48,51 @@ impl UsageSegment {
         }
     }
 
+    /// Render per-category token counts like `in:12k out:3k cache:40k`
+    fn format_token_breakdown(
+        usage: &NormalizedUsage,
+        options: Option<&HashMap<String, serde_json::Value>>,
+    ) -> String {
+        let option = |key: &str| options.and_then(|opts| opts.get(key));
+
+        let compact = option("compact").and_then(|v| v.as_bool()).unwrap_or(false);
+        if compact {
+            return humanize(u64::from(usage.total_for_cost()), 1);
+        }
+
+        let order: Vec<String> = option("token_order")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_else(|| vec!["in".into(), "out".into(), "cache".into()]);
+        let colors = option("token_colors").and_then(|v| v.as_object());
+
+        let mut parts = Vec::new();
+        for category in &order {
+            let tokens = match category.as_str() {
+                "in" => usage.input_tokens,
+                "out" => usage.output_tokens,
+                "cache" => usage.cache_read_input_tokens,
+                "cache_write" => usage.cache_creation_input_tokens,
+                _ => continue,
+            };
+            let label = format!("{}:{}", category, humanize(u64::from(tokens), 1));
+            let color = colors
+                .and_then(|map| map.get(category))
+                .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok());
+            parts.push(match color {
+                Some(color) => AnsiColorHelper::apply_foreground_color(&label, &color),
+                None => label,
+            });
+        }
+
+        parts.join(" ")
+    }
+
     fn format_reset_time(reset_time_str: Option<&str>) -> String {
         if let Some(time_str) = reset_time_str {
             if let Ok(dt) = DateTime::parse_from_rfc3339(time_str) {
@@ -65,13 +111,25 @@ impl UsageSegment {
         "?".to_string()
     }
 
+    fn token_metadata(usage: Option<&NormalizedUsage>) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(usage) = usage {
+            metadata.insert("input_tokens".to_string(), usage.input_tokens.to_string());
+            metadata.insert("output_tokens".to_string(), usage.output_tokens.to_string());
+            metadata.insert(
+                "cache_read_tokens".to_string(),
+                usage.cache_read_input_tokens.to_string(),
+            );
+            metadata.insert(
+                "cache_creation_tokens".to_string(),
+                usage.cache_creation_input_tokens.to_string(),
+            );
+        }
+        metadata
+    }
+
     fn get_cache_path() -> Option<std::path::PathBuf> {
-        let home = dirs::home_dir()?;
-        Some(
-            home.join(".claude")
-                .join("ccline")
-                .join(".api_usage_cache.json"),
-        )
+        Some(config_dir().ok()?.join(".api_usage_cache.json"))
     }
 
     fn load_cache(&self) -> Option<ApiUsageCache> {
@@ -90,7 +148,7 @@ impl UsageSegment {
                 let _ = std::fs::create_dir_all(parent);
             }
             if let Ok(json) = serde_json::to_string_pretty(cache) {
-                let _ = std::fs::write(&cache_path, json);
+                let _ = atomic_write(&cache_path, json);
             }
         }
     }
@@ -170,24 +228,55 @@ impl UsageSegment {
             .set("User-Agent", &user_agent)
             .timeout(std::time::Duration::from_secs(timeout_secs))
             .call()
+            .map_err(|e| log::debug!("usage request to {} failed: {}", url, e))
             .ok()?;
 
         if response.status() == 200 {
-            response.into_json().ok()
+            response
+                .into_json()
+                .map_err(|e| log::debug!("invalid usage response from {}: {}", url, e))
+                .ok()
         } else {
+            log::debug!(
+                "usage request to {} returned HTTP {}",
+                url,
+                response.status()
+            );
             None
         }
     }
 }
 
 impl Segment for UsageSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
-        let token = credentials::get_oauth_token()?;
-
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
         // Load config from file to get segment options
         let config = crate::config::Config::load().ok()?;
         let segment_config = config.segments.iter().find(|s| s.id == SegmentId::Usage);
 
+        let show_tokens = segment_config
+            .and_then(|sc| sc.options.get("show_tokens"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let token_usage = input
+            .usage
+            .clone()
+            .filter(|_| show_tokens)
+            .map(|usage| usage.normalize());
+        let breakdown = token_usage
+            .as_ref()
+            .map(|usage| Self::format_token_breakdown(usage, segment_config.map(|sc| &sc.options)));
+
+        let Some(token) = credentials::get_oauth_token() else {
+            super::skip_reason("no OAuth token found");
+            // Without OAuth credentials only the token breakdown can be shown
+            return breakdown.map(|primary| SegmentData {
+                primary,
+                secondary: String::new(),
+                metadata: Self::token_metadata(token_usage.as_ref()),
+                raw: false,
+            });
+        };
+
         let api_base_url = segment_config
             .and_then(|sc| sc.options.get("api_base_url"))
             .and_then(|v| v.as_str())
@@ -240,7 +329,13 @@ impl Segment for UsageSegment {
                             cache.resets_at,
                         )
                     } else {
-                        return None;
+                        super::skip_reason("usage request failed and nothing is cached");
+                        return breakdown.map(|primary| SegmentData {
+                            primary,
+                            secondary: String::new(),
+                            metadata: Self::token_metadata(token_usage.as_ref()),
+                            raw: false,
+                        });
                     }
                 }
             }
@@ -249,9 +344,12 @@ impl Segment for UsageSegment {
         let dynamic_icon = Self::get_circle_icon(seven_day_util / 100.0);
         let five_hour_percent = five_hour_util.round() as u8;
         let primary = format!("{}%", five_hour_percent);
-        let secondary = format!("· {}", Self::format_reset_time(resets_at.as_deref()));
+        let mut secondary = format!("· {}", Self::format_reset_time(resets_at.as_deref()));
+        if let Some(breakdown) = &breakdown {
+            secondary = format!("{} · {}", secondary, breakdown);
+        }
 
-        let mut metadata = HashMap::new();
+        let mut metadata = Self::token_metadata(token_usage.as_ref());
         metadata.insert("dynamic_icon".to_string(), dynamic_icon);
         metadata.insert(
             "five_hour_utilization".to_string(),
@@ -266,6 +364,7 @@ impl Segment for UsageSegment {
             primary,
             secondary,
             metadata,
+            raw: false,
         })
     }
 
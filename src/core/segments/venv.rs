@@ -0,0 +1,184 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Places an active environment can be detected from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VenvSource {
+    /// `$VIRTUAL_ENV` set by `python -m venv`, virtualenv, poetry, uv, ...
+    Virtualenv,
+    /// `$CONDA_DEFAULT_ENV` set by `conda activate`
+    Conda,
+    /// `.nvmrc` or `package.json` `engines.node` in the working directory
+    Node,
+}
+
+impl VenvSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Virtualenv => "virtualenv",
+            Self::Conda => "conda",
+            Self::Node => "node",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "virtualenv" | "venv" | "python" => Some(Self::Virtualenv),
+            "conda" => Some(Self::Conda),
+            "node" | "nvm" => Some(Self::Node),
+            _ => None,
+        }
+    }
+}
+
+/// Active Python/Conda environment and the project's Node version, e.g. `myproject ⬢ 20`
+pub struct VenvSegment {
+    order: Vec<VenvSource>,
+    icons: HashMap<String, String>,
+    show_conda_base: bool,
+}
+
+impl Default for VenvSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VenvSegment {
+    pub fn new() -> Self {
+        let mut icons = HashMap::new();
+        icons.insert(VenvSource::Node.as_str().to_string(), "⬢".to_string());
+
+        Self {
+            order: vec![VenvSource::Virtualenv, VenvSource::Conda, VenvSource::Node],
+            icons,
+            show_conda_base: false,
+        }
+    }
+
+    /// Sources to check and the order their results are shown in
+    pub fn with_order(mut self, order: Vec<VenvSource>) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Prefix per source name (`virtualenv`, `conda`, `node`), empty for none
+    pub fn with_icons(mut self, icons: HashMap<String, String>) -> Self {
+        self.icons.extend(icons);
+        self
+    }
+
+    /// Conda activates `base` in every shell by default, so it is hidden unless asked for
+    pub fn with_conda_base(mut self, show_conda_base: bool) -> Self {
+        self.show_conda_base = show_conda_base;
+        self
+    }
+
+    fn detect(&self, source: VenvSource, current_dir: &str) -> Option<String> {
+        match source {
+            VenvSource::Virtualenv => {
+                let env = std::env::var("VIRTUAL_ENV").ok()?;
+                Self::virtualenv_name(Path::new(env.trim()))
+            }
+            VenvSource::Conda => {
+                let env = std::env::var("CONDA_DEFAULT_ENV").ok()?;
+                let env = env.trim();
+                if env.is_empty() || (env == "base" && !self.show_conda_base) {
+                    None
+                } else {
+                    // Envs activated by path report the full prefix
+                    Some(
+                        Path::new(env)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| env.to_string()),
+                    )
+                }
+            }
+            VenvSource::Node => Self::node_version(Path::new(current_dir)),
+        }
+    }
+
+    /// Name of a virtualenv, using the project directory for `.venv`-style folders
+    fn virtualenv_name(path: &Path) -> Option<String> {
+        let name = path.file_name()?.to_string_lossy().to_string();
+        if matches!(name.as_str(), ".venv" | "venv" | "env" | ".env") {
+            if let Some(project) = path.parent().and_then(|p| p.file_name()) {
+                return Some(project.to_string_lossy().to_string());
+            }
+        }
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Node version requested by `.nvmrc`, falling back to `package.json` engines
+    fn node_version(dir: &Path) -> Option<String> {
+        if let Ok(content) = std::fs::read_to_string(dir.join(".nvmrc")) {
+            let version = content.lines().next().unwrap_or_default().trim();
+            if !version.is_empty() {
+                return Some(version.to_string());
+            }
+        }
+
+        let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+        let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let version = package.get("engines")?.get("node")?.as_str()?.trim();
+        (!version.is_empty()).then(|| version.to_string())
+    }
+}
+
+impl Segment for VenvSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let mut parts = Vec::new();
+        let mut metadata = HashMap::new();
+        let mut sources = Vec::new();
+
+        for source in &self.order {
+            let Some(value) = self.detect(*source, &input.workspace.current_dir) else {
+                continue;
+            };
+            // The same source listed twice shouldn't render twice
+            if sources.contains(&source.as_str()) {
+                continue;
+            }
+            sources.push(source.as_str());
+
+            let key = match source {
+                VenvSource::Node => "node_version",
+                _ => "env",
+            };
+            metadata
+                .entry(key.to_string())
+                .or_insert_with(|| value.clone());
+            metadata.insert(source.as_str().to_string(), value.clone());
+
+            match self
+                .icons
+                .get(source.as_str())
+                .filter(|icon| !icon.is_empty())
+            {
+                Some(icon) => parts.push(format!("{} {}", icon, value)),
+                None => parts.push(value),
+            }
+        }
+
+        if parts.is_empty() {
+            super::skip_reason("no virtual environment detected");
+            return None;
+        }
+
+        metadata.insert("sources".to_string(), sources.join(","));
+
+        Some(SegmentData {
+            primary: parts.join(" "),
+            secondary: String::new(),
+            metadata,
+            raw: false,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Venv
+    }
+}
@@ -1,54 +1,116 @@
-use crate::config::{AnsiColor, Config, SegmentConfig, StyleMode};
+use crate::config::{AnsiColor, ColorDepth, Config, SegmentAlign, SegmentConfig, StyleMode};
 use crate::core::segments::SegmentData;
+use crate::utils::{display_width, strip_ansi, truncate_to_width};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(0);
+static BENCHMARK: AtomicBool = AtomicBool::new(false);
+static EXPLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Globally enable or disable ANSI colors, including colors embedded by segments
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+}
 
-/// Strip ANSI escape sequences and return visible text length
-fn visible_width(text: &str) -> usize {
-    let mut visible = String::new();
-    let mut in_escape = false;
-    let mut chars = text.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '\x1b' {
-            // Start of ANSI escape sequence
-            in_escape = true;
-            // Skip the [ character
-            if chars.peek() == Some(&'[') {
-                chars.next();
-            }
-        } else if in_escape {
-            // Skip until we find the end of the escape sequence (letter)
-            if ch.is_alphabetic() {
-                in_escape = false;
-            }
-        } else {
-            // Regular character
-            visible.push(ch);
-        }
-    }
+pub fn color_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Set the color depth segments downgrade their embedded colors to
+pub fn set_color_depth(depth: ColorDepth) {
+    let value = match depth.resolve() {
+        ColorDepth::Color256 => 1,
+        ColorDepth::Color16 => 2,
+        _ => 0,
+    };
+    COLOR_DEPTH.store(value, Ordering::Relaxed);
+}
+
+/// Report how long each segment took to collect on stderr (`--benchmark`)
+pub fn set_benchmark(enabled: bool) {
+    BENCHMARK.store(enabled, Ordering::Relaxed);
+}
 
-    visible.chars().count()
+/// Report on stderr why each configured segment rendered or not (`--explain`)
+pub fn set_explain(enabled: bool) {
+    EXPLAIN.store(enabled, Ordering::Relaxed);
+}
+
+pub fn color_depth() -> ColorDepth {
+    match COLOR_DEPTH.load(Ordering::Relaxed) {
+        1 => ColorDepth::Color256,
+        2 => ColorDepth::Color16,
+        _ => ColorDepth::Truecolor,
+    }
 }
 
 pub struct StatusLineGenerator {
     config: Config,
+    no_color: bool,
+    color_depth: ColorDepth,
 }
 
 impl StatusLineGenerator {
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    pub fn new(mut config: Config) -> Self {
+        let color_depth = config.style.color_depth.resolve();
+        config.style.mode = config.style.mode.resolve();
+        Self {
+            config,
+            no_color: false,
+            color_depth,
+        }
+    }
+
+    /// Produce plain text without any ANSI escape sequences
+    pub fn with_no_color(mut self, no_color: bool) -> Self {
+        self.no_color = no_color;
+        self
     }
 
     pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
+        let statusline = self.generate_colored(segments);
+        if self.no_color {
+            // Segments may embed their own escapes (raw), so strip the final line
+            strip_ansi(&statusline)
+        } else {
+            statusline
+        }
+    }
+
+    fn generate_colored(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
+        let mut lines: BTreeMap<usize, Vec<&(SegmentConfig, SegmentData)>> = BTreeMap::new();
+        for segment in segments.iter().filter(|(config, _)| config.enabled) {
+            lines.entry(segment.0.line).or_default().push(segment);
+        }
+
+        // Lines whose segments all came back empty are skipped rather than left blank
+        lines
+            .into_values()
+            .map(|line| self.generate_line(&line))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render one statusline row, fitted to `style.max_width` on its own
+    fn generate_line(&self, segments: &[&(SegmentConfig, SegmentData)]) -> String {
         let mut output = Vec::new();
-        let enabled_segments: Vec<_> = segments
-            .into_iter()
-            .filter(|(config, _)| config.enabled)
-            .collect();
+        let mut rendered_configs = Vec::new();
 
-        for (config, data) in enabled_segments.iter() {
+        // Left-aligned segments first, so the right group is always a suffix
+        let (left, right): (Vec<_>, Vec<_>) = segments
+            .iter()
+            .map(|segment| (&segment.0, &segment.1))
+            .partition(|(config, _)| config.align == SegmentAlign::Left);
+
+        for (config, data) in left.into_iter().chain(right) {
             let rendered = self.render_segment(config, data);
             if !rendered.is_empty() {
                 output.push(rendered);
+                // Keep configs aligned with the output so skipped segments don't shift colors
+                rendered_configs.push(config);
             }
         }
 
@@ -56,39 +118,141 @@ impl StatusLineGenerator {
             return String::new();
         }
 
+        let mut statusline = self.layout_segments(&output, &rendered_configs);
+
+        if let Some(max_width) = self.config.style.max_width {
+            // Drop the least important segment until the line fits
+            while output.len() > 1 && display_width(&statusline) > max_width {
+                let drop_index = Self::lowest_priority_index(&rendered_configs);
+                output.remove(drop_index);
+                rendered_configs.remove(drop_index);
+                statusline = self.layout_segments(&output, &rendered_configs);
+            }
+
+            // A single segment can still be too wide
+            statusline = truncate_to_width(&statusline, max_width, "…");
+        }
+
+        // Always end each line on a full reset so no background bleeds to the edge of the terminal
+        if !statusline.ends_with("\x1b[0m") {
+            statusline.push_str("\x1b[0m");
+        }
+
+        statusline
+    }
+
+    /// Join the left group, then pad so the right group ends at `style.max_width`
+    ///
+    /// Without a width, or when both groups don't fit side by side, everything is
+    /// joined with the regular separator.
+    fn layout_segments(&self, rendered_segments: &[String], configs: &[&SegmentConfig]) -> String {
+        let split = configs
+            .iter()
+            .take_while(|config| config.align == SegmentAlign::Left)
+            .count();
+
+        let (Some(width), true) = (self.config.style.max_width, split < configs.len()) else {
+            return self.join_segments(rendered_segments, configs);
+        };
+
+        let right = self.join_segments(&rendered_segments[split..], &configs[split..]);
+        if split == 0 {
+            let padding = width.saturating_sub(display_width(&right));
+            return format!("{}{}", " ".repeat(padding), right);
+        }
+
+        let left = self.join_segments(&rendered_segments[..split], &configs[..split]);
+        let used = display_width(&left) + display_width(&right);
+        if used >= width {
+            return self.join_segments(rendered_segments, configs);
+        }
+
+        // Reset first so a trailing background or arrow color doesn't fill the gap
+        format!("{}\x1b[0m{}{}", left, " ".repeat(width - used), right)
+    }
+
+    fn join_segments(&self, rendered_segments: &[String], configs: &[&SegmentConfig]) -> String {
         // Handle Powerline arrow separators with color transition
-        if self.config.style.separator == "\u{e0b0}" {
-            self.join_with_powerline_arrows(&output, &enabled_segments)
+        if self.uses_powerline_arrows() {
+            self.join_with_powerline_arrows(rendered_segments, configs)
         } else {
             // For all other separators, use white color and simple join
-            self.join_with_white_separators(&output)
+            self.join_with_white_separators(rendered_segments)
         }
     }
 
+    /// Index of the segment to drop first: lowest `priority` option, later in config order on ties
+    fn lowest_priority_index(configs: &[&SegmentConfig]) -> usize {
+        configs
+            .iter()
+            .enumerate()
+            .min_by_key(|(index, config)| {
+                let priority = config
+                    .options
+                    .get("priority")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0);
+                (priority, std::cmp::Reverse(*index))
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Serialize the collected segments as a JSON array for external renderers
+    pub fn generate_json(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
+        let entries: Vec<serde_json::Value> = segments
+            .into_iter()
+            .filter(|(config, _)| config.enabled)
+            .map(|(config, data)| {
+                let (primary, secondary) = if self.no_color {
+                    (strip_ansi(&data.primary), strip_ansi(&data.secondary))
+                } else {
+                    (data.primary, data.secondary)
+                };
+                serde_json::json!({
+                    "id": config.id,
+                    "align": config.align,
+                    "line": config.line,
+                    "primary": primary,
+                    "secondary": secondary,
+                    "metadata": data.metadata,
+                })
+            })
+            .collect();
+
+        serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+    }
+
     /// Generate statusline for TUI preview with proper width calculation
     /// This method handles ANSI escape sequences properly for ratatui rendering
+    ///
+    /// Every statusline row is kept, so segments on `line: 1` and below show up too.
     #[cfg(feature = "tui")]
     pub fn generate_for_tui(
         &self,
         segments: Vec<(SegmentConfig, SegmentData)>,
-    ) -> ratatui::text::Line<'static> {
+    ) -> ratatui::text::Text<'static> {
         use ansi_to_tui::IntoText;
-        use ratatui::text::{Line, Span};
+        use ratatui::text::{Line, Span, Text};
 
         // Use the same generate method and convert to TUI
         let full_output = self.generate(segments);
 
-        if let Ok(text) = full_output.into_text() {
-            if let Some(line) = text.lines.into_iter().next() {
-                return line;
-            }
+        match full_output.into_text() {
+            Ok(text) => text,
+            // Fallback to raw text
+            Err(_) => Text::from(
+                full_output
+                    .lines()
+                    .map(|line| Line::from(vec![Span::raw(line.to_string())]))
+                    .collect::<Vec<_>>(),
+            ),
         }
-
-        // Fallback to raw text
-        Line::from(vec![Span::raw(full_output)])
     }
 
     /// Generate TUI-optimized text with intelligent wrapping by segment for preview
+    ///
+    /// Each configured `line` is wrapped on its own, as `generate` renders it.
     pub fn generate_for_tui_preview(
         &self,
         segments: Vec<(SegmentConfig, SegmentData)>,
@@ -97,20 +261,47 @@ impl StatusLineGenerator {
         use ansi_to_tui::IntoText;
         use ratatui::text::{Line, Span, Text};
 
-        let enabled_segments: Vec<_> = segments
-            .into_iter()
-            .filter(|(config, _)| config.enabled)
+        let mut rows: BTreeMap<usize, Vec<&(SegmentConfig, SegmentData)>> = BTreeMap::new();
+        for segment in segments.iter().filter(|(config, _)| config.enabled) {
+            rows.entry(segment.0.line).or_default().push(segment);
+        }
+
+        let lines: Vec<String> = rows
+            .into_values()
+            .flat_map(|row| self.wrap_row_for_tui(&row, max_width))
             .collect();
 
-        if enabled_segments.is_empty() {
-            return Text::from(vec![Line::default()]);
+        // Convert string lines to ratatui Text
+        let mut tui_lines = Vec::new();
+        for line in lines {
+            if let Ok(text) = line.into_text() {
+                for tui_line in text.lines {
+                    tui_lines.push(tui_line);
+                }
+            } else {
+                tui_lines.push(Line::from(vec![Span::raw(line)]));
+            }
+        }
+
+        // Ensure we have at least one line
+        if tui_lines.is_empty() {
+            tui_lines.push(Line::default());
         }
 
+        Text::from(tui_lines)
+    }
+
+    /// Wrap one statusline row into preview lines no wider than `max_width`
+    fn wrap_row_for_tui(
+        &self,
+        row: &[&(SegmentConfig, SegmentData)],
+        max_width: u16,
+    ) -> Vec<String> {
         // Render each segment individually
         let mut rendered_segments = Vec::new();
         let mut segment_configs = Vec::new();
 
-        for (config, data) in &enabled_segments {
+        for (config, data) in row.iter().map(|segment| (&segment.0, &segment.1)) {
             let rendered = self.render_segment(config, data);
             if !rendered.is_empty() {
                 rendered_segments.push(rendered);
@@ -119,13 +310,13 @@ impl StatusLineGenerator {
         }
 
         if rendered_segments.is_empty() {
-            return Text::from(vec![Line::default()]);
+            return Vec::new();
         }
 
         // Pre-calculate separators between segments
         let mut separators = Vec::new();
         for i in 0..rendered_segments.len().saturating_sub(1) {
-            let separator = if self.config.style.separator == "\u{e0b0}" {
+            let separator = if self.uses_powerline_arrows() {
                 // Powerline arrows with color transition
                 let prev_bg = segment_configs
                     .get(i)
@@ -136,7 +327,7 @@ impl StatusLineGenerator {
                 self.create_powerline_arrow(prev_bg, curr_bg)
             } else {
                 // Regular separators with white color
-                format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
+                format!("\x1b[37m{}\x1b[0m", self.config.style.separator())
             };
             separators.push(separator);
         }
@@ -149,7 +340,7 @@ impl StatusLineGenerator {
 
         for i in 0..rendered_segments.len() {
             let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
+            let segment_width = display_width(segment);
 
             // Check if adding this segment would exceed max_width
             if current_width > 0 && current_width + segment_width > max_w {
@@ -166,12 +357,12 @@ impl StatusLineGenerator {
             // Handle separator if not the last segment
             if i < separators.len() {
                 let separator = &separators[i];
-                let separator_width = visible_width(separator);
+                let separator_width = display_width(separator);
 
                 // Check if next segment exists
                 if i + 1 < rendered_segments.len() {
                     let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
+                    let next_width = display_width(next_segment);
 
                     // Check if separator AND next segment both fit
                     if current_width + separator_width + next_width <= max_w {
@@ -194,24 +385,7 @@ impl StatusLineGenerator {
             lines.push(current_line);
         }
 
-        // Convert string lines to ratatui Text
-        let mut tui_lines = Vec::new();
-        for line in lines {
-            if let Ok(text) = line.into_text() {
-                for tui_line in text.lines {
-                    tui_lines.push(tui_line);
-                }
-            } else {
-                tui_lines.push(Line::from(vec![Span::raw(line)]));
-            }
-        }
-
-        // Ensure we have at least one line
-        if tui_lines.is_empty() {
-            tui_lines.push(Line::default());
-        }
-
-        Text::from(tui_lines)
+        lines
     }
 
     fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> String {
@@ -220,11 +394,13 @@ impl StatusLineGenerator {
         } else {
             self.get_icon(config)
         };
-        let use_raw_text = data
-            .metadata
-            .get("raw_text")
-            .map(|v| v == "true")
-            .unwrap_or(false);
+        let primary = Self::fit_primary(config, &data.primary);
+        // Joins primary and secondary, e.g. `main ⋮ detail`
+        let inner_separator = config
+            .options
+            .get("inner_separator")
+            .and_then(|v| v.as_str())
+            .unwrap_or(" ");
 
         // Apply background color to the entire segment if set
         if let Some(bg_color) = &config.colors.background {
@@ -238,21 +414,23 @@ impl StatusLineGenerator {
                 icon.clone()
             };
 
-            let text_styled = (if use_raw_text {
-                data.primary.clone()
+            let text_styled = (if data.raw {
+                primary.clone()
             } else {
                 self.apply_style(
-                    &data.primary,
+                    &primary,
                     config.colors.text.as_ref(),
                     config.styles.text_bold,
                 )
             })
-            .replace("\x1b[0m", "");
+            .replace("\x1b[0m", "")
+            // A background reset inside the text would leave the rest of the segment bare
+            .replace("\x1b[49m", &bg_code);
 
-            let mut segment_content = format!(" {} {} ", icon_colored, text_styled);
+            let mut segment_content = format!(" {} {}", icon_colored, text_styled);
 
             if !data.secondary.is_empty() {
-                let secondary_styled = (if use_raw_text {
+                let secondary_styled = (if data.raw {
                     data.secondary.clone()
                 } else {
                     self.apply_style(
@@ -261,20 +439,23 @@ impl StatusLineGenerator {
                         config.styles.text_bold,
                     )
                 })
-                .replace("\x1b[0m", "");
-                segment_content.push_str(&format!("{} ", secondary_styled));
+                .replace("\x1b[0m", "")
+                .replace("\x1b[49m", &bg_code);
+                segment_content.push_str(inner_separator);
+                segment_content.push_str(&secondary_styled);
             }
+            segment_content.push(' ');
 
             // Apply background to the entire content and reset at the end
             format!("{}{}\x1b[49m", bg_code, segment_content)
         } else {
             // No background color, use original logic
             let icon_colored = self.apply_color(&icon, config.colors.icon.as_ref());
-            let text_styled = if use_raw_text {
-                data.primary.clone()
+            let text_styled = if data.raw {
+                primary.clone()
             } else {
                 self.apply_style(
-                    &data.primary,
+                    &primary,
                     config.colors.text.as_ref(),
                     config.styles.text_bold,
                 )
@@ -283,7 +464,7 @@ impl StatusLineGenerator {
             let mut segment = format!("{} {}", icon_colored, text_styled);
 
             if !data.secondary.is_empty() {
-                let secondary_styled = if use_raw_text {
+                let secondary_styled = if data.raw {
                     data.secondary.clone()
                 } else {
                     self.apply_style(
@@ -292,23 +473,59 @@ impl StatusLineGenerator {
                         config.styles.text_bold,
                     )
                 };
-                segment.push_str(&format!(" {}", secondary_styled));
+                segment.push_str(inner_separator);
+                segment.push_str(&secondary_styled);
             }
 
             segment
         }
     }
 
+    /// Apply the `max_length`/`ellipsis` and `min_length` options to a segment's primary text
+    fn fit_primary(config: &SegmentConfig, primary: &str) -> String {
+        let option_usize = |key: &str| {
+            config
+                .options
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+        };
+
+        let mut text = match option_usize("max_length") {
+            Some(max_length) => {
+                let ellipsis = config
+                    .options
+                    .get("ellipsis")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("…");
+                truncate_to_width(primary, max_length, ellipsis)
+            }
+            None => primary.to_string(),
+        };
+
+        if let Some(min_length) = option_usize("min_length") {
+            let width = display_width(&text);
+            if width < min_length {
+                text.push_str(&" ".repeat(min_length - width));
+            }
+        }
+
+        text
+    }
+
     fn get_icon(&self, config: &SegmentConfig) -> String {
         match self.config.style.mode {
             StyleMode::Plain => config.icon.plain.clone(),
             StyleMode::NerdFont => config.icon.nerd_font.clone(),
             StyleMode::Powerline => config.icon.nerd_font.clone(), // Future: use Powerline icons
+            // Resolved in new(), kept for exhaustiveness
+            StyleMode::Auto => config.icon.nerd_font.clone(),
         }
     }
 
     fn apply_color(&self, text: &str, color: Option<&AnsiColor>) -> String {
-        match color {
+        let color = color.map(|c| c.downgrade(self.color_depth));
+        match color.as_ref() {
             Some(AnsiColor::Color16 { c16 }) => {
                 let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
                 format!("\x1b[{}m{}\x1b[0m", code, text)
@@ -332,7 +549,8 @@ impl StatusLineGenerator {
         }
 
         // Add color codes
-        match color {
+        let color = color.map(|c| c.downgrade(self.color_depth));
+        match color.as_ref() {
             Some(AnsiColor::Color16 { c16 }) => {
                 let color_code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
                 codes.push(color_code.to_string());
@@ -360,7 +578,7 @@ impl StatusLineGenerator {
     }
 
     fn apply_background_color(&self, color: &AnsiColor) -> String {
-        match color {
+        match &color.downgrade(self.color_depth) {
             AnsiColor::Color16 { c16 } => {
                 let code = if *c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
                 format!("\x1b[{}m", code)
@@ -381,43 +599,48 @@ impl StatusLineGenerator {
         }
 
         // Use white color for separator
-        let white_separator = format!("\x1b[37m{}\x1b[0m", self.config.style.separator);
+        let white_separator = format!("\x1b[37m{}\x1b[0m", self.config.style.separator());
         rendered_segments.join(&white_separator)
     }
 
+    /// Whether segments are joined by colored Powerline arrows
+    fn uses_powerline_arrows(&self) -> bool {
+        // A literal arrow separator keeps working for configs predating the flag
+        self.config.style.separator() == "\u{e0b0}"
+            || (self.config.style.powerline && self.config.style.mode != StyleMode::Plain)
+    }
+
     /// Join segments with Powerline arrow separators with proper color transitions
     fn join_with_powerline_arrows(
         &self,
         rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
+        segment_configs: &[&SegmentConfig],
     ) -> String {
         if rendered_segments.is_empty() {
             return String::new();
         }
 
-        if rendered_segments.len() == 1 {
-            return rendered_segments[0].clone();
-        }
+        let background = |i: usize| {
+            segment_configs
+                .get(i)
+                .and_then(|config| config.colors.background.as_ref())
+        };
 
         let mut result = rendered_segments[0].clone();
 
-        for (i, _) in rendered_segments.iter().enumerate().skip(1) {
-            let prev_bg = segment_configs
-                .get(i - 1)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-            let curr_bg = segment_configs
-                .get(i)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-
+        for (i, segment) in rendered_segments.iter().enumerate().skip(1) {
             // Create Powerline arrow with color transition
-            let arrow = self.create_powerline_arrow(prev_bg, curr_bg);
+            let arrow = self.create_powerline_arrow(background(i - 1), background(i));
 
             result.push_str(&arrow);
-            result.push_str(&rendered_segments[i]);
+            result.push_str(segment);
+        }
+
+        // Trailing cap fades the last background into the terminal background
+        if let Some(last_bg) = background(rendered_segments.len() - 1) {
+            result.push_str(&self.create_powerline_arrow(Some(last_bg), None));
         }
 
-        // Reset colors at the end
-        result.push_str("\x1b[0m");
         result
     }
 
@@ -456,7 +679,7 @@ impl StatusLineGenerator {
 
     /// Convert AnsiColor to foreground color code
     fn color_to_foreground_code(&self, color: &AnsiColor) -> String {
-        match color {
+        match &color.downgrade(self.color_depth) {
             AnsiColor::Color16 { c16 } => {
                 let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
                 format!("\x1b[{}m", code)
@@ -471,72 +694,1046 @@ impl StatusLineGenerator {
     }
 }
 
+/// Most segments collected at once; the rest queue until a worker is free
+const MAX_COLLECT_WORKERS: usize = 8;
+
+/// A segment's data, why it is empty and how long it took
+type Collected = (Option<SegmentData>, Option<String>, std::time::Duration);
+
+/// Run `collect` for every segment on a bounded pool of worker threads
+///
+/// Returns one receiver per segment in the same order. The workers are detached
+/// rather than scoped so a caller can stop waiting for a segment that overruns
+/// its `timeout_ms`; joining would make the slowest segment the deadline. A
+/// panicking segment disconnects its receiver and the worker moves on.
+fn spawn_collection<F>(
+    segments: Vec<SegmentConfig>,
+    collect: F,
+) -> Vec<std::sync::mpsc::Receiver<Collected>>
+where
+    F: Fn(&SegmentConfig) -> Option<SegmentData> + Send + Sync + 'static,
+{
+    use std::collections::VecDeque;
+    use std::panic::AssertUnwindSafe;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Instant;
+
+    let mut receivers = Vec::with_capacity(segments.len());
+    let mut jobs = VecDeque::with_capacity(segments.len());
+    for segment_config in segments {
+        let (tx, rx) = mpsc::channel();
+        jobs.push_back((segment_config, tx));
+        receivers.push(rx);
+    }
+
+    let workers = jobs.len().min(MAX_COLLECT_WORKERS);
+    let jobs = Arc::new(Mutex::new(jobs));
+    let collect = Arc::new(collect);
+    for _ in 0..workers {
+        let jobs = Arc::clone(&jobs);
+        let collect = Arc::clone(&collect);
+        std::thread::spawn(move || loop {
+            let Some((segment_config, tx)) = jobs
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .pop_front()
+            else {
+                break;
+            };
+
+            let started_at = Instant::now();
+            let data = std::panic::catch_unwind(AssertUnwindSafe(|| collect(&segment_config)));
+            // Taken even after a panic so it can't leak into the next job
+            let reason = crate::core::segments::take_skip_reason();
+            if let Ok(data) = data {
+                let _ = tx.send((data, reason, started_at.elapsed()));
+            }
+        });
+    }
+
+    receivers
+}
+
 pub fn collect_all_segments(
     config: &Config,
     input: &crate::config::InputData,
 ) -> Vec<(SegmentConfig, SegmentData)> {
-    use crate::core::segments::*;
+    use std::time::{Duration, Instant};
+
+    // Skip disabled segments to avoid unnecessary API requests
+    let enabled: Vec<&SegmentConfig> = config.segments.iter().filter(|s| s.enabled).collect();
+
+    let started_at = Instant::now();
+    // Collect concurrently so network-bound segments don't add up
+    let receivers = {
+        let config = config.clone();
+        let input = input.clone();
+        spawn_collection(
+            enabled.iter().map(|s| (*s).clone()).collect(),
+            move |segment_config| collect_segment(&config, segment_config, &input),
+        )
+    };
+
+    let benchmark = BENCHMARK.load(Ordering::Relaxed);
+    let explain = EXPLAIN.load(Ordering::Relaxed);
+    // (segment, result, reason) rows, disabled segments shown in config order too
+    let mut explanations: Vec<(&SegmentConfig, &str, String)> = Vec::new();
+
+    // Join in config order to keep the output order stable
+    let segments = enabled
+        .iter()
+        .zip(receivers)
+        .filter_map(|(segment_config, rx)| {
+            let timeout = segment_config
+                .options
+                .get("timeout_ms")
+                .and_then(|v| v.as_u64())
+                .map(Duration::from_millis);
+
+            // A timed-out or panicked segment is dropped rather than rendered as an error
+            let received = match timeout {
+                Some(timeout) => rx
+                    .recv_timeout(timeout.saturating_sub(started_at.elapsed()))
+                    .ok(),
+                None => rx.recv().ok(),
+            };
+
+            if explain {
+                let (result, reason) = match &received {
+                    Some((Some(_), _, _)) => ("rendered", String::new()),
+                    Some((None, reason, _)) => (
+                        "empty",
+                        reason
+                            .clone()
+                            .unwrap_or_else(|| "no data to show".to_string()),
+                    ),
+                    None => match timeout {
+                        Some(timeout) => (
+                            "timed out",
+                            format!("exceeded timeout_ms ({} ms)", timeout.as_millis()),
+                        ),
+                        None => ("failed", "collection panicked".to_string()),
+                    },
+                };
+                explanations.push((segment_config, result, reason));
+            }
+
+            if benchmark {
+                let name = segment_config.id.name();
+                match &received {
+                    Some((data, _, elapsed)) => eprintln!(
+                        "{:<24} {:>9.2} ms{}",
+                        name,
+                        elapsed.as_secs_f64() * 1000.0,
+                        if data.is_some() { "" } else { "  (empty)" }
+                    ),
+                    None => eprintln!(
+                        "{:<24} {:>9.2} ms  (timed out)",
+                        name,
+                        started_at.elapsed().as_secs_f64() * 1000.0
+                    ),
+                }
+            }
+
+            let Some((data, reason, _)) = received else {
+                log::debug!(
+                    "segment {} timed out or panicked, dropping it",
+                    segment_config.id.name()
+                );
+                return None;
+            };
+            if data.is_none() {
+                match reason {
+                    Some(reason) => log::debug!(
+                        "segment {} returned no data: {}",
+                        segment_config.id.name(),
+                        reason
+                    ),
+                    None => log::debug!("segment {} returned no data", segment_config.id.name()),
+                }
+            }
+            Some(((*segment_config).clone(), data?))
+        })
+        .collect();
+
+    if benchmark {
+        eprintln!(
+            "{:<24} {:>9.2} ms",
+            "total",
+            started_at.elapsed().as_secs_f64() * 1000.0
+        );
+    }
 
-    let mut results = Vec::new();
+    if explain {
+        print_explanation(config, &explanations);
+    }
+
+    segments
+}
 
+/// `--explain` table: every configured segment, its result and why it is empty
+fn print_explanation(config: &Config, explanations: &[(&SegmentConfig, &str, String)]) {
+    eprintln!("{:<24} {:<8} {:<10} reason", "segment", "enabled", "result");
     for segment_config in &config.segments {
-        // Skip disabled segments to avoid unnecessary API requests
-        if !segment_config.enabled {
-            continue;
+        let (enabled, result, reason) = if segment_config.enabled {
+            match explanations
+                .iter()
+                .find(|(explained, _, _)| std::ptr::eq(*explained, segment_config))
+            {
+                Some((_, result, reason)) => ("yes", *result, reason.as_str()),
+                None => ("yes", "-", ""),
+            }
+        } else {
+            ("no", "skipped", "disabled in config")
+        };
+        let row = format!(
+            "{:<24} {:<8} {:<10} {}",
+            segment_config.id.name(),
+            enabled,
+            result,
+            reason
+        );
+        eprintln!("{}", row.trim_end());
+    }
+}
+
+/// Parse an icon option: a plain string applies to both styles, a table sets
+/// `plain`/`nerd_font` separately
+fn parse_icon_option(value: &serde_json::Value) -> Option<crate::config::IconConfig> {
+    match value.as_str() {
+        Some(glyph) => Some(crate::config::IconConfig {
+            plain: glyph.to_string(),
+            nerd_font: glyph.to_string(),
+        }),
+        None => serde_json::from_value(value.clone()).ok(),
+    }
+}
+
+/// Parse an output style entry: `{ icon = ..., color = ... }` sets both, while a
+/// string or icon table only sets the icon
+fn parse_style_icon(value: &serde_json::Value) -> crate::core::segments::StyleIcon {
+    if value.get("icon").is_some() || value.get("color").is_some() {
+        crate::core::segments::StyleIcon {
+            icon: value.get("icon").and_then(parse_icon_option),
+            color: value
+                .get("color")
+                .and_then(|c| serde_json::from_value(c.clone()).ok()),
         }
+    } else {
+        crate::core::segments::StyleIcon {
+            icon: parse_icon_option(value),
+            color: None,
+        }
+    }
+}
+
+/// Collect the data for a single segment according to its config
+pub fn collect_segment(
+    config: &Config,
+    segment_config: &SegmentConfig,
+    input: &crate::config::InputData,
+) -> Option<SegmentData> {
+    use crate::core::segments::*;
 
-        let segment_data = match segment_config.id {
-            crate::config::SegmentId::Model => {
-                let segment = ModelSegment::new();
-                segment.collect(input)
+    let style_mode = config.style.mode.resolve();
+
+    match segment_config.id {
+        crate::config::SegmentId::Model => {
+            let mut segment = ModelSegment::new();
+            if let Some(label) = segment_config
+                .options
+                .get("unknown_label")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_unknown_label(label);
             }
-            crate::config::SegmentId::Directory => {
-                let segment = DirectorySegment::new();
-                segment.collect(input)
+            if let Some(provider_icons) = segment_config
+                .options
+                .get("provider_icons")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+            {
+                segment =
+                    segment.with_provider_icons(provider_icons, style_mode != StyleMode::Plain);
+            }
+            if let Some(max_length) = segment_config
+                .options
+                .get("max_length")
+                .and_then(|v| v.as_u64())
+            {
+                let ellipsis = segment_config
+                    .options
+                    .get("ellipsis")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("…");
+                segment = segment.with_max_length(max_length as usize, ellipsis);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Directory => {
+            let shorten_home = segment_config
+                .options
+                .get("shorten_home")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let max_components = segment_config
+                .options
+                .get("max_components")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize);
+            let keep_root = segment_config
+                .options
+                .get("keep_root")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let repo_relative = segment_config
+                .options
+                .get("repo_relative")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let mut segment = DirectorySegment::new()
+                .with_shorten_home(shorten_home)
+                .with_max_components(max_components)
+                .with_keep_root(keep_root)
+                .with_repo_relative(repo_relative);
+            if let Some(symbol) = segment_config
+                .options
+                .get("truncation_symbol")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_truncation_symbol(symbol);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Git => {
+            let show_sha = segment_config
+                .options
+                .get("show_sha")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let cache_status = segment_config
+                .options
+                .get("cache_status")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let cache_duration = segment_config
+                .options
+                .get("cache_duration")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(5);
+            let state_icons = ["clean", "dirty", "conflict"]
+                .into_iter()
+                .filter_map(|state| {
+                    let value = segment_config.options.get(&format!("{}_icon", state))?;
+                    Some((state.to_string(), parse_icon_option(value)?))
+                })
+                .collect();
+            // `show_commit_hash` is `show_sha` with a prefix, `@` unless configured
+            let show_commit_hash = segment_config
+                .options
+                .get("show_commit_hash")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let sha_prefix = if show_commit_hash {
+                segment_config
+                    .options
+                    .get("commit_hash_prefix")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("@")
+            } else {
+                ""
+            };
+            let show_commit_age = segment_config
+                .options
+                .get("show_commit_age")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let show_upstream = segment_config
+                .options
+                .get("show_upstream")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let show_tags = segment_config
+                .options
+                .get("show_tags")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let tag_icon = segment_config
+                .options
+                .get("tag_icon")
+                .and_then(parse_icon_option);
+            let show_submodules = segment_config
+                .options
+                .get("show_submodules")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let mut segment = GitSegment::new()
+                .with_sha(show_sha || show_commit_hash)
+                .with_sha_prefix(sha_prefix)
+                .with_commit_age(show_commit_age)
+                .with_upstream(show_upstream)
+                .with_tags(show_tags, tag_icon)
+                .with_submodules(show_submodules)
+                .with_status_cache(cache_status.then_some(cache_duration))
+                .with_state_icons(state_icons, style_mode != StyleMode::Plain);
+            let show_host_icon = segment_config
+                .options
+                .get("show_host_icon")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if show_host_icon {
+                let host_icons = segment_config
+                    .options
+                    .get("host_icons")
+                    .and_then(|v| v.as_object())
+                    .map(|icons| {
+                        icons
+                            .iter()
+                            .filter_map(|(host, value)| {
+                                Some((host.to_lowercase(), parse_icon_option(value)?))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let in_branch = segment_config
+                    .options
+                    .get("host_icon_in_branch")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                segment =
+                    segment.with_host_icons(host_icons, in_branch, style_mode != StyleMode::Plain);
             }
-            crate::config::SegmentId::Git => {
-                let show_sha = segment_config
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ContextWindow => {
+            let display_mode = segment_config
+                .options
+                .get("display_mode")
+                .and_then(|v| v.as_str())
+                .and_then(ContextDisplayMode::from_name)
+                .unwrap_or_default();
+            let bar_width = segment_config
+                .options
+                .get("bar_width")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(8);
+            let segment = ContextWindowSegment::new()
+                .with_display_mode(display_mode)
+                .with_bar_width(bar_width as usize)
+                .with_ascii_bar(style_mode == StyleMode::Plain)
+                .with_thresholds(ContextThresholds::from_options(&segment_config.options));
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Usage => {
+            let segment = UsageSegment::new();
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Cost => {
+            let mut segment = CostSegment::new().with_budget_limit(
+                segment_config
                     .options
-                    .get("show_sha")
+                    .get("budget_limit")
+                    .and_then(|v| v.as_f64()),
+            );
+            if let Some(color) = segment_config
+                .options
+                .get("budget_color")
+                .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
+            {
+                segment = segment.with_budget_color(color);
+            }
+            if let Some(symbol) = segment_config
+                .options
+                .get("over_budget_symbol")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_over_budget_symbol(symbol);
+            }
+            if let Some(symbol) = segment_config
+                .options
+                .get("currency_symbol")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_currency_symbol(symbol);
+            }
+            if let Some(separator) = segment_config
+                .options
+                .get("decimal_separator")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_decimal_separator(separator);
+            }
+            if let Some(rate) = segment_config
+                .options
+                .get("exchange_rate")
+                .and_then(|v| v.as_f64())
+            {
+                segment = segment.with_exchange_rate(rate);
+            }
+            if let Some(placement) = segment_config
+                .options
+                .get("placement")
+                .and_then(|v| v.as_str())
+                .and_then(SymbolPlacement::from_name)
+            {
+                segment = segment.with_symbol_placement(placement);
+            }
+            let show_rate = segment_config
+                .options
+                .get("show_rate")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            segment = segment.with_rate(show_rate);
+            let daily_total = segment_config
+                .options
+                .get("daily_total")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            segment = segment.with_daily_total(daily_total);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Session => {
+            let show_duration = segment_config
+                .options
+                .get("show_duration")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let show_messages = segment_config
+                .options
+                .get("show_messages")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let segment = SessionSegment::new()
+                .with_duration(show_duration)
+                .with_messages(show_messages);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::OutputStyle => {
+            let style_icons = segment_config
+                .options
+                .get("style_icons")
+                .and_then(|v| v.as_object())
+                .map(|styles| {
+                    styles
+                        .iter()
+                        .map(|(name, value)| (name.clone(), parse_style_icon(value)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let segment = OutputStyleSegment::new()
+                .with_style_icons(style_icons, style_mode != StyleMode::Plain);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Update => {
+            let cache_duration = segment_config
+                .options
+                .get("cache_duration")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(3600);
+            let mut segment = UpdateSegment::new().with_cache_duration(cache_duration);
+            if let Some(repo) = segment_config.options.get("repo").and_then(|v| v.as_str()) {
+                segment = segment.with_repo(repo);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::CliProxyApiQuota => {
+            let segment = CliProxyApiQuotaSegment::from_options(&segment_config.options)
+                .with_background(segment_config.colors.background.clone());
+            segment.collect_with_options(&segment_config.options)
+        }
+        crate::config::SegmentId::RateLimit => {
+            let show_countdown = segment_config
+                .options
+                .get("show_countdown")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let segment = RateLimitSegment::new().with_countdown(show_countdown);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ModelContext => {
+            let mut model = ModelSegment::new();
+            if let Some(label) = segment_config
+                .options
+                .get("unknown_label")
+                .and_then(|v| v.as_str())
+            {
+                model = model.with_unknown_label(label);
+            }
+            let segment = ModelContextSegment::new()
+                .with_model(model)
+                .with_thresholds(ContextThresholds::from_options(&segment_config.options));
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Clock => {
+            let mut segment = ClockSegment::new();
+            if let Some(format) = segment_config
+                .options
+                .get("format")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_format(format);
+            }
+            if let Some(timezone) = segment_config
+                .options
+                .get("timezone")
+                .and_then(|v| v.as_str())
+            {
+                segment = segment.with_timezone(timezone);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Battery => {
+            let options = &segment_config.options;
+            let color = |key: &str| {
+                options
+                    .get(key)
+                    .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
+            };
+
+            let mut segment = BatterySegment::new().with_thresholds(
+                options
+                    .get("low_threshold")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(20.0),
+                options
+                    .get("critical_threshold")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(10.0),
+            );
+            if let Some(low_color) = color("low_color") {
+                segment = segment.with_low_color(low_color);
+            }
+            if let Some(critical_color) = color("critical_color") {
+                segment = segment.with_critical_color(critical_color);
+            }
+            if let Some(icon) = options.get("charging_icon").and_then(|v| v.as_str()) {
+                segment = segment.with_charging_icon(icon);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Kube => {
+            let show_namespace = segment_config
+                .options
+                .get("show_namespace")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let cache_duration = segment_config
+                .options
+                .get("cache_duration")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(5);
+            let segment = KubeSegment::new()
+                .with_namespace(show_namespace)
+                .with_cache_duration(cache_duration);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Venv => {
+            let options = &segment_config.options;
+            let mut segment = VenvSegment::new().with_conda_base(
+                options
+                    .get("show_conda_base")
                     .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let segment = GitSegment::new().with_sha(show_sha);
-                segment.collect(input)
+                    .unwrap_or(false),
+            );
+            if let Some(order) = options.get("detect_order").and_then(|v| v.as_array()) {
+                segment = segment.with_order(
+                    order
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .filter_map(VenvSource::parse)
+                        .collect(),
+                );
             }
-            crate::config::SegmentId::ContextWindow => {
-                let segment = ContextWindowSegment::new();
-                segment.collect(input)
+            if let Some(icons) = options.get("icons").and_then(|v| v.as_object()) {
+                segment = segment.with_icons(
+                    icons
+                        .iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect(),
+                );
             }
-            crate::config::SegmentId::Usage => {
-                let segment = UsageSegment::new();
-                segment.collect(input)
+            segment.collect(input)
+        }
+        crate::config::SegmentId::CloudProfile => {
+            let options = &segment_config.options;
+            let mut segment = CloudProfileSegment::new();
+            if let Some(show) = options.get("show").and_then(|v| v.as_array()) {
+                segment = segment.with_show(
+                    show.iter()
+                        .filter_map(|v| v.as_str())
+                        .filter_map(CloudSource::parse)
+                        .collect(),
+                );
             }
-            crate::config::SegmentId::Cost => {
-                let segment = CostSegment::new();
-                segment.collect(input)
+            if let Some(icons) = options.get("icons").and_then(|v| v.as_object()) {
+                segment = segment.with_icons(
+                    icons
+                        .iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect(),
+                );
             }
-            crate::config::SegmentId::Session => {
-                let segment = SessionSegment::new();
-                segment.collect(input)
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Host => {
+            let ssh_only = segment_config
+                .options
+                .get("ssh_only")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let fqdn = segment_config
+                .options
+                .get("fqdn")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let segment = HostSegment::new().with_ssh_only(ssh_only).with_fqdn(fqdn);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ExitCode => {
+            let options = &segment_config.options;
+            let color = |key: &str| {
+                options
+                    .get(key)
+                    .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
+            };
+            let string = |key: &str| options.get(key).and_then(|v| v.as_str());
+
+            let mut segment = ExitCodeSegment::new();
+            if let Some(env_var) = string("env_var") {
+                segment = segment.with_env_var(env_var);
             }
-            crate::config::SegmentId::OutputStyle => {
-                let segment = OutputStyleSegment::new();
-                segment.collect(input)
+            if let Some(icon) = string("success_icon") {
+                segment = segment.with_success_icon(icon);
             }
-            crate::config::SegmentId::Update => {
-                let segment = UpdateSegment::new();
-                segment.collect(input)
+            if let Some(icon) = string("failure_icon") {
+                segment = segment.with_failure_icon(icon);
             }
-            crate::config::SegmentId::CliProxyApiQuota => {
-                let segment = CliProxyApiQuotaSegment::new();
-                segment.collect_with_options(&segment_config.options)
+            if let Some(success_color) = color("success_color") {
+                segment = segment.with_success_color(success_color);
             }
+            if let Some(failure_color) = color("failure_color") {
+                segment = segment.with_failure_color(failure_color);
+            }
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Command => {
+            let options = &segment_config.options;
+            let command = options
+                .get("command")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            // Not `timeout_ms`: that is every segment's collection deadline
+            let command_timeout_ms = options
+                .get("command_timeout_ms")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(500);
+            let cache_duration = options
+                .get("cache_duration")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10);
+            let segment = CommandSegment::new(command)
+                .with_timeout(std::time::Duration::from_millis(command_timeout_ms))
+                .with_cache_duration(cache_duration)
+                .with_sanitize(crate::utils::SanitizeOptions::from_options(options));
+            segment.collect(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn text(primary: &str) -> SegmentData {
+        SegmentData {
+            primary: primary.to_string(),
+            secondary: String::new(),
+            metadata: Default::default(),
+            raw: false,
+        }
+    }
+
+    /// `count` copies of the first preset segment, told apart by `line`
+    fn segments(count: usize) -> Vec<SegmentConfig> {
+        let template = Config::default().segments.remove(0);
+        (0..count)
+            .map(|line| SegmentConfig {
+                line,
+                ..template.clone()
+            })
+            .collect()
+    }
+
+    fn generator(max_width: Option<usize>) -> StatusLineGenerator {
+        let mut config = Config::default();
+        config.style.color_depth = ColorDepth::Truecolor;
+        config.style.max_width = max_width;
+        StatusLineGenerator::new(config)
+    }
+
+    /// An enabled segment on `line` showing `primary`, with an optional background
+    fn rendered(
+        line: usize,
+        primary: &str,
+        background: Option<AnsiColor>,
+    ) -> (SegmentConfig, SegmentData) {
+        let mut segment_config = segments(1).remove(0);
+        segment_config.enabled = true;
+        segment_config.line = line;
+        segment_config.colors.background = background;
+        (segment_config, text(primary))
+    }
+
+    const BLUE: Option<AnsiColor> = Some(AnsiColor::Color16 { c16: 4 });
+
+    #[test]
+    fn segments_within_the_pool_size_run_at_the_same_time() {
+        // Each segment waits for all of them to start, which only a parallel run achieves
+        let count = 6;
+        let running = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let receivers = {
+            let (running, peak) = (running.clone(), peak.clone());
+            spawn_collection(segments(count), move |segment_config| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Generous deadline so a sequential run fails instead of hanging
+                let deadline = Instant::now() + Duration::from_secs(5);
+                while peak.load(Ordering::SeqCst) < count && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                running.fetch_sub(1, Ordering::SeqCst);
+                Some(text(&segment_config.line.to_string()))
+            })
+        };
+
+        let primaries: Vec<_> = receivers
+            .into_iter()
+            .map(|rx| rx.recv().unwrap().0.unwrap().primary)
+            .collect();
+
+        assert_eq!(primaries, ["0", "1", "2", "3", "4", "5"]);
+        assert_eq!(peak.load(Ordering::SeqCst), count);
+    }
+
+    #[test]
+    fn segments_beyond_the_pool_size_queue() {
+        let count = MAX_COLLECT_WORKERS * 2;
+        let running = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let receivers = {
+            let (running, peak) = (running.clone(), peak.clone());
+            spawn_collection(segments(count), move |_| {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                running.fetch_sub(1, Ordering::SeqCst);
+                None
+            })
         };
+        for rx in receivers {
+            assert!(rx.recv().unwrap().0.is_none());
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), MAX_COLLECT_WORKERS);
+    }
+
+    #[test]
+    fn panicking_segment_does_not_take_down_its_worker() {
+        // One worker would also run every later segment
+        let receivers = spawn_collection(segments(MAX_COLLECT_WORKERS + 2), |segment_config| {
+            if segment_config.line == 0 {
+                panic!("segment bug");
+            }
+            Some(text("ok"))
+        });
+
+        let mut receivers = receivers.into_iter();
+        assert!(receivers.next().unwrap().recv().is_err());
+        for rx in receivers {
+            assert_eq!(rx.recv().unwrap().0.unwrap().primary, "ok");
+        }
+    }
+
+    #[test]
+    fn skip_reasons_stay_with_their_segment() {
+        let receivers = spawn_collection(segments(MAX_COLLECT_WORKERS * 3), |segment_config| {
+            if segment_config.line % 2 == 0 {
+                crate::core::segments::skip_reason(format!("skip {}", segment_config.line));
+                return None;
+            }
+            Some(text("shown"))
+        });
+
+        for (line, rx) in receivers.into_iter().enumerate() {
+            let (data, reason, _) = rx.recv().unwrap();
+            if line % 2 == 0 {
+                assert_eq!(reason, Some(format!("skip {}", line)));
+            } else {
+                assert!(data.is_some());
+                assert_eq!(reason, None);
+            }
+        }
+    }
+
+    #[test]
+    fn lines_with_a_background_end_on_a_full_reset() {
+        let cases = [
+            vec![rendered(0, "only", BLUE)],
+            vec![rendered(0, "plain", None), rendered(0, "last", BLUE)],
+            vec![rendered(0, "first", BLUE), rendered(0, "plain", None)],
+        ];
+        for segments in cases {
+            let line = generator(None).generate(segments);
+            assert!(line.ends_with("\x1b[0m"), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn every_line_ends_on_a_full_reset() {
+        let output =
+            generator(None).generate(vec![rendered(0, "top", BLUE), rendered(1, "bottom", BLUE)]);
+
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            assert!(line.ends_with("\x1b[0m"), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn truncated_background_still_ends_on_a_full_reset() {
+        let line = generator(Some(8)).generate(vec![rendered(0, "a long segment text", BLUE)]);
+
+        assert!(display_width(&line) <= 8);
+        assert!(line.ends_with("\x1b[0m"), "{:?}", line);
+    }
+
+    #[test]
+    fn right_aligned_background_ends_on_a_full_reset() {
+        let (mut right, data) = rendered(0, "right", BLUE);
+        right.align = SegmentAlign::Right;
+
+        let line = generator(Some(40)).generate(vec![rendered(0, "left", None), (right, data)]);
+
+        assert_eq!(display_width(&line), 40);
+        assert!(line.ends_with("\x1b[0m"), "{:?}", line);
+    }
+
+    /// Segment config with green bold text and no icon color
+    fn styled(background: Option<AnsiColor>) -> SegmentConfig {
+        let (mut segment_config, _) = rendered(0, "", background);
+        segment_config.colors.icon = None;
+        segment_config.colors.text = Some(AnsiColor::Color16 { c16: 2 });
+        segment_config.styles.text_bold = true;
+        segment_config
+    }
 
-        if let Some(data) = segment_data {
-            results.push((segment_config.clone(), data));
+    fn raw(primary: &str, secondary: &str) -> SegmentData {
+        SegmentData {
+            secondary: secondary.to_string(),
+            raw: true,
+            ..text(primary)
         }
     }
 
-    results
+    #[test]
+    fn non_raw_text_gets_the_configured_style() {
+        let output = generator(None).render_segment(&styled(None), &text("plain"));
+        assert!(output.ends_with(" \x1b[1;32mplain\x1b[0m"), "{:?}", output);
+    }
+
+    #[test]
+    fn raw_text_is_emitted_unstyled() {
+        let primary = "\x1b[35mmine\x1b[39m";
+        let output = generator(None).render_segment(&styled(None), &raw(primary, "2nd"));
+
+        assert!(
+            output.ends_with(&format!(" {} 2nd", primary)),
+            "{:?}",
+            output
+        );
+        assert!(!output.contains("\x1b[1;32m"), "{:?}", output);
+    }
+
+    #[test]
+    fn non_raw_text_on_a_background_keeps_it_to_the_end() {
+        let output = generator(None).render_segment(&styled(BLUE), &text("plain"));
+
+        assert!(output.starts_with("\x1b[44m "), "{:?}", output);
+        assert!(output.contains("\x1b[1;32mplain"), "{:?}", output);
+        // The style's own reset would end the background early
+        assert!(!output.contains("\x1b[0m"), "{:?}", output);
+        assert!(output.ends_with(" \x1b[49m"), "{:?}", output);
+    }
+
+    #[test]
+    fn raw_text_on_a_background_cannot_reset_it() {
+        let output = generator(None)
+            .render_segment(&styled(BLUE), &raw("\x1b[35mmine\x1b[0m", "\x1b[49mbare"));
+
+        assert!(output.contains("\x1b[35mmine"), "{:?}", output);
+        assert!(!output.contains("\x1b[1;32m"), "{:?}", output);
+        assert!(!output.contains("\x1b[0m"), "{:?}", output);
+        // Only the segment's own closing background reset remains
+        assert_eq!(output.matches("\x1b[49m").count(), 1, "{:?}", output);
+        assert!(output.contains("\x1b[44mbare"), "{:?}", output);
+    }
+
+    fn plain(text: &ratatui::text::Text) -> Vec<String> {
+        text.lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tui_preview_keeps_every_statusline_row() {
+        let generator = generator(None);
+        let preview = generator.generate_for_tui_preview(
+            vec![rendered(0, "top", None), rendered(1, "bottom", None)],
+            200,
+        );
+
+        let lines = plain(&preview);
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[0].contains("top") && !lines[0].contains("bottom"));
+        assert!(lines[1].contains("bottom"));
+    }
+
+    #[test]
+    fn tui_preview_skips_rows_with_only_disabled_segments() {
+        let (mut hidden, data) = rendered(1, "hidden", None);
+        hidden.enabled = false;
+        let generator = generator(None);
+        let preview = generator.generate_for_tui_preview(
+            vec![
+                rendered(0, "top", None),
+                (hidden, data),
+                rendered(2, "third", None),
+            ],
+            200,
+        );
+
+        let lines = plain(&preview);
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[1].contains("third"));
+    }
+
+    #[test]
+    fn tui_text_keeps_every_statusline_row() {
+        let text = generator(None)
+            .generate_for_tui(vec![rendered(0, "top", None), rendered(1, "bottom", None)]);
+
+        let lines = plain(&text);
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[1].contains("bottom"));
+    }
 }
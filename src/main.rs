@@ -1,11 +1,32 @@
-use ccometixline::cli::Cli;
-use ccometixline::config::{Config, InputData};
-use ccometixline::core::{collect_all_segments, StatusLineGenerator};
-use std::io::{self, IsTerminal};
+use ccometixline::cli::{Cli, OutputFormat, PrintFormat};
+use ccometixline::config::{Config, InputData, SegmentId};
+use ccometixline::core::{
+    collect_all_segments, set_benchmark, set_color_depth, set_color_enabled, set_explain,
+    StatusLineGenerator,
+};
+use std::io::{self, IsTerminal, Read};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse_args();
 
+    // Silent unless asked for, RUST_LOG always wins over the --verbose default
+    let default_filter = if cli.verbose {
+        "ccometixline=debug"
+    } else {
+        "off"
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+        .init();
+
+    if let Some(config_path) = &cli.config_path {
+        // --init creates the file, everything else needs it to exist
+        if !cli.init && !config_path.is_file() {
+            eprintln!("✗ Config file not found: {}", config_path.display());
+            std::process::exit(1);
+        }
+        Config::set_config_path(config_path.clone());
+    }
+
     // Handle configuration commands
     if cli.init {
         use ccometixline::config::InitResult;
@@ -19,21 +40,157 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     if cli.print {
-        let mut config = Config::load().unwrap_or_else(|_| Config::default());
+        let config = load_effective_config(cli.theme.as_deref());
+        print_config(&config, cli.print_format)?;
+        return Ok(());
+    }
 
-        // Apply theme override if provided
-        if let Some(theme) = cli.theme {
-            config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
+    if cli.dump_effective_config {
+        let mut config = load_effective_config(cli.theme.as_deref());
+        if cli.redact {
+            config = config.redacted();
         }
 
-        config.print()?;
+        print_config(&config, cli.print_format)?;
+        return Ok(());
+    }
+
+    if cli.dump_quota {
+        use ccometixline::core::segments::CliProxyApiQuotaSegment;
+
+        let config = load_effective_config(cli.theme.as_deref());
+        let options = config
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::CliProxyApiQuota)
+            .map(|s| s.options.clone())
+            .unwrap_or_default();
+
+        let dump = CliProxyApiQuotaSegment::from_options(&options).dump(&options);
+        println!("{}", serde_json::to_string_pretty(&dump)?);
         return Ok(());
     }
 
     if cli.check {
         let config = Config::load()?;
         config.check()?;
-        println!("✓ Configuration valid");
+        let warnings = config.warnings();
+        for warning in &warnings {
+            eprintln!("⚠ {}", warning);
+        }
+        if warnings.is_empty() {
+            println!("✓ Configuration valid");
+        } else {
+            println!("✓ Configuration valid ({} warning(s))", warnings.len());
+        }
+        return Ok(());
+    }
+
+    if cli.list_themes {
+        #[cfg(feature = "tui")]
+        {
+            use ccometixline::ui::themes::ThemePresets;
+
+            let active = Config::load().map(|config| config.theme).ok();
+            let marker = |name: &str| {
+                if active.as_deref() == Some(name) {
+                    "*"
+                } else {
+                    " "
+                }
+            };
+
+            let builtin = ThemePresets::get_available_themes();
+            println!("Built-in themes:");
+            for (name, description) in &builtin {
+                println!("{} {:<24} {}", marker(name), name, description);
+            }
+
+            // Theme files named after a built-in only override it, so they aren't listed twice
+            let custom: Vec<String> = ThemePresets::list_available_themes()
+                .into_iter()
+                .filter(|name| !builtin.iter().any(|(builtin, _)| builtin == name))
+                .collect();
+            if !custom.is_empty() {
+                println!();
+                println!("Custom themes (~/.claude/ccline/themes):");
+                for name in &custom {
+                    println!("{} {:<24} [custom]", marker(name), name);
+                }
+            }
+        }
+        #[cfg(not(feature = "tui"))]
+        {
+            eprintln!("TUI feature is not enabled. Please install with --features tui");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(theme_name) = &cli.save_theme {
+        #[cfg(feature = "tui")]
+        {
+            use ccometixline::ui::themes::ThemePresets;
+
+            let theme_name = theme_name.trim();
+            if theme_name.is_empty()
+                || theme_name.contains(['/', '\\'])
+                || theme_name.starts_with('.')
+            {
+                eprintln!("✗ Invalid theme name: {:?}", theme_name);
+                std::process::exit(1);
+            }
+
+            let is_builtin = ThemePresets::get_available_themes()
+                .iter()
+                .any(|(name, _)| *name == theme_name);
+            if is_builtin && !cli.force {
+                eprintln!(
+                    "✗ {} is a built-in theme; pass --force to overwrite it",
+                    theme_name
+                );
+                std::process::exit(1);
+            }
+
+            let config = load_effective_config(cli.theme.as_deref());
+            match ThemePresets::save_theme(theme_name, &config) {
+                Ok(path) => println!("✓ Saved theme {} to {}", theme_name, path.display()),
+                Err(e) => {
+                    eprintln!("✗ Failed to save theme {}: {}", theme_name, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        #[cfg(not(feature = "tui"))]
+        {
+            let _ = theme_name;
+            eprintln!("TUI feature is not enabled. Please install with --features tui");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(theme_path) = cli.check_theme {
+        #[cfg(feature = "tui")]
+        {
+            let problems = ccometixline::ui::themes::ThemePresets::validate_theme_file(
+                std::path::Path::new(&theme_path),
+            );
+            if !problems.is_empty() {
+                eprintln!("✗ Theme {} has {} problem(s):", theme_path, problems.len());
+                for problem in &problems {
+                    eprintln!("  - {}", problem);
+                }
+                std::process::exit(1);
+            }
+            println!("✓ Theme {} valid", theme_path);
+        }
+        #[cfg(not(feature = "tui"))]
+        {
+            let _ = theme_path;
+            eprintln!("TUI feature is not enabled. Please install with --features tui");
+            std::process::exit(1);
+        }
         return Ok(());
     }
 
@@ -53,7 +210,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if cli.update {
         #[cfg(feature = "self-update")]
         {
-            println!("Update feature not implemented in new architecture yet");
+            use ccometixline::updater::{github::DEFAULT_REPO, install::run_update};
+
+            // Follow the repository configured for the update segment, if any
+            let config = Config::load().unwrap_or_else(|_| Config::default());
+            let repo = config
+                .segments
+                .iter()
+                .find(|s| s.id == SegmentId::Update)
+                .and_then(|s| s.options.get("repo"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(DEFAULT_REPO)
+                .to_string();
+
+            if let Err(e) = run_update(&repo, cli.dry_run) {
+                eprintln!("❌ Update failed: {}", e);
+                std::process::exit(1);
+            }
         }
         #[cfg(not(feature = "self-update"))]
         {
@@ -62,6 +235,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // Preview the Claude Code patches on an in-memory copy
+    if let Some(claude_path) = cli.patch_dry_run {
+        use ccometixline::utils::ClaudeCodePatcher;
+
+        println!("🔍 Claude Code Patch Preview (dry run)");
+        println!("Target file: {}", claude_path);
+
+        let mut patcher = ClaudeCodePatcher::new(&claude_path)?;
+
+        println!("\n🔄 Matching patches...");
+        let results = patcher.apply_all_patches();
+
+        ClaudeCodePatcher::print_summary(&results);
+        println!(
+            "💡 Dry run only, {} was not modified. Apply with:",
+            claude_path
+        );
+        println!("   ccline --patch {}", claude_path);
+
+        return Ok(());
+    }
+
     // Handle Claude Code patcher
     if let Some(claude_path) = cli.patch {
         use ccometixline::utils::ClaudeCodePatcher;
@@ -69,10 +264,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("🔧 Claude Code Context Warning Disabler");
         println!("Target file: {}", claude_path);
 
-        // Create backup in same directory
-        let backup_path = format!("{}.backup", claude_path);
-        std::fs::copy(&claude_path, &backup_path)?;
-        println!("📦 Created backup: {}", backup_path);
+        // Create a timestamped backup in the same directory
+        let backup_path = ClaudeCodePatcher::create_backup(&claude_path)?;
+        println!("📦 Created backup: {}", backup_path.display());
 
         // Load and patch
         let mut patcher = ClaudeCodePatcher::new(&claude_path)?;
@@ -82,22 +276,72 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         patcher.save()?;
 
         ClaudeCodePatcher::print_summary(&results);
-        println!("💡 To restore warnings, replace your cli.js with the backup file:");
-        println!("   cp {} {}", backup_path, claude_path);
+        println!("💡 To restore warnings, restore the backup:");
+        println!("   ccline --restore {}", claude_path);
+
+        return Ok(());
+    }
+
+    // Restore cli.js from a patcher backup
+    if let Some(claude_path) = cli.restore {
+        use ccometixline::utils::ClaudeCodePatcher;
+
+        let backups = ClaudeCodePatcher::list_backups(&claude_path);
+        if backups.is_empty() {
+            eprintln!("✗ No backups found for {}", claude_path);
+            std::process::exit(1);
+        }
+
+        println!("📦 Available backups (newest first):");
+        for backup in &backups {
+            println!("   {}", backup.display());
+        }
+
+        let chosen = match cli.backup.as_deref() {
+            Some(wanted) => backups.iter().find(|backup| {
+                let path = backup.to_string_lossy();
+                path == wanted || path.ends_with(&format!(".backup.{}", wanted))
+            }),
+            None => backups.first(),
+        };
+        let Some(chosen) = chosen else {
+            eprintln!("✗ Backup {} not found", cli.backup.unwrap_or_default());
+            std::process::exit(1);
+        };
+
+        ClaudeCodePatcher::restore_backup(&claude_path, chosen)?;
+        println!("✅ Restored {} from {}", claude_path, chosen.display());
 
         return Ok(());
     }
 
     // Load configuration
-    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+    let mut config = load_effective_config(cli.theme.as_deref());
 
-    // Apply theme override if provided
-    if let Some(theme) = cli.theme {
-        config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
+    // Restrict rendering to a single segment when requested
+    if let Some(name) = cli.segment.as_deref() {
+        let Some(segment_id) = SegmentId::from_name(name) else {
+            eprintln!("Unknown segment: {}", name);
+            eprintln!("Valid segments:");
+            for id in SegmentId::all() {
+                eprintln!("  {}", id.name());
+            }
+            std::process::exit(1);
+        };
+
+        config.segments.retain(|s| s.id == segment_id);
+        if config.segments.is_empty() {
+            eprintln!("Segment '{}' is not present in the configuration", name);
+            std::process::exit(1);
+        }
+        // Debugging a disabled segment should still show its output
+        for segment in &mut config.segments {
+            segment.enabled = true;
+        }
     }
 
-    // Check if stdin has data
-    if io::stdin().is_terminal() {
+    // Check if stdin has data (an input file takes its place)
+    if cli.input.is_none() && io::stdin().is_terminal() {
         // No input data available, show main menu
         #[cfg(feature = "tui")]
         {
@@ -130,18 +374,122 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    // Read Claude Code data from the input file if given, otherwise stdin
+    let (content, source) = match cli.input.as_deref() {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Failed to read input file {}: {}", path, e);
+                std::process::exit(1);
+            });
+            (content, path)
+        }
+        None => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            (content, "stdin")
+        }
+    };
+    let input: InputData = match serde_json::from_str(&content) {
+        Ok(input) => input,
+        Err(e) if cli.explain => {
+            eprintln!("invalid input JSON, no segments collected: {}", e);
+            return Ok(());
+        }
+        Err(e) => {
+            // A schema change in Claude Code shouldn't leave the prompt broken
+            log::debug!(
+                "invalid input JSON in {}, using fallback statusline: {}",
+                source,
+                e
+            );
+            println!("{}", fallback_statusline(&content));
+            return Ok(());
+        }
+    };
+
+    // Segments embed colors while collecting, so disable them up front
+    let no_color = cli.colors_disabled();
+    set_color_enabled(!no_color);
+    set_color_depth(config.style.color_depth);
 
     // Collect segment data
+    set_benchmark(cli.benchmark);
+    set_explain(cli.explain);
     let segments_data = collect_all_segments(&config, &input);
 
+    // The diagnostic table on stderr is the whole output
+    if cli.explain {
+        return Ok(());
+    }
+
     // Render statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(segments_data);
+    let generator = StatusLineGenerator::new(config).with_no_color(no_color);
+    let statusline = match cli.format {
+        OutputFormat::Text => generator.generate(segments_data),
+        OutputFormat::Json => generator.generate_json(segments_data),
+    };
 
     println!("{}", statusline);
 
     Ok(())
 }
+
+/// Minimal statusline for input that doesn't match `InputData`: just the directory name
+///
+/// The directory comes from whatever `workspace.current_dir` or `cwd` the JSON still
+/// carries, falling back to the process working directory.
+fn fallback_statusline(raw: &str) -> String {
+    let value: serde_json::Value = serde_json::from_str(raw).unwrap_or_default();
+    let dir = value
+        .pointer("/workspace/current_dir")
+        .or_else(|| value.get("cwd"))
+        .and_then(|v| v.as_str())
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.to_string_lossy().to_string())
+}
+
+/// Print a configuration for --print and --dump-effective-config
+fn print_config(config: &Config, format: PrintFormat) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        PrintFormat::Toml => config.print()?,
+        PrintFormat::Json => println!("{}", serde_json::to_string_pretty(config)?),
+        PrintFormat::Rendered => {
+            #[cfg(feature = "tui")]
+            {
+                // The TUI preview's sample data, so the output doesn't depend on the environment
+                let mut preview = ccometixline::ui::components::preview::PreviewComponent::new();
+                preview.update_preview(config);
+                println!("{}", preview.get_preview_cache());
+            }
+            #[cfg(not(feature = "tui"))]
+            {
+                eprintln!("TUI feature is not enabled. Please install with --features tui");
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load the configuration exactly as the statusline render path uses it
+fn load_effective_config(theme: Option<&str>) -> Config {
+    let mut config = Config::load().unwrap_or_else(|e| {
+        log::debug!("cannot load config, using defaults: {}", e);
+        Config::default()
+    });
+
+    // Apply theme override if provided
+    if let Some(theme) = theme {
+        config = ccometixline::ui::themes::ThemePresets::get_theme(theme);
+    }
+
+    // Environment overrides win over the file, a requested theme and the defaults
+    // alike; applying them again after Config::load changes nothing
+    config.apply_env_overrides();
+    config
+}
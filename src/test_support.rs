@@ -0,0 +1,11 @@
+//! Helpers shared by the unit tests
+
+use std::path::PathBuf;
+
+/// Empty directory for one test, unique per test name and process
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ccline-test-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create test directory");
+    dir
+}
@@ -1,4 +1,4 @@
-use crate::config::models::{ModelAlias, ModelConfig};
+use crate::config::models::{ModelAlias, ModelConfig, ModelEntry};
 use crate::ui::components::name_input::NameInputComponent;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
@@ -14,7 +14,8 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item, TableLike, Value};
 
 #[derive(Debug, Clone, PartialEq)]
 enum InputMode {
@@ -22,8 +23,19 @@ enum InputMode {
     EditingId,
     EditingName,
     EditingContext,
+    EditingSample,
 }
 
+/// Which section of models.toml the list is editing
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Aliases,
+    Patterns,
+}
+
+/// Model id shown in the preview until the user types their own
+const DEFAULT_SAMPLE_ID: &str = "claude-sonnet-4-5-20250929";
+
 pub struct AliasEditorApp {
     config: ModelConfig,
     config_path: PathBuf,
@@ -35,14 +47,38 @@ pub struct AliasEditorApp {
     // For editing
     editing_index: Option<usize>,
     temp_alias: Option<ModelAlias>,
+    temp_entry: Option<ModelEntry>,
+    // [[models]] patterns from the user's file, without the built-in defaults
+    entries: Vec<ModelEntry>,
+    tab: Tab,
+    // Model id resolved in the preview pane
+    sample_id: String,
+    // Search: the list only shows aliases matching `filter`
+    filter: String,
+    searching: bool,
+}
+
+impl Default for AliasEditorApp {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AliasEditorApp {
     pub fn new() -> Self {
-        let config = ModelConfig::load();
-        let config_path = dirs::home_dir()
-            .map(|d| d.join(".claude").join("ccline").join("models.toml"))
-            .unwrap_or_else(|| PathBuf::from("models.toml"));
+        let mut config = ModelConfig::load();
+        // models.toml in the working directory is ModelConfig's fallback location too
+        let config_path = crate::config::config_dir()
+            .map(|dir| dir.join("models.toml"))
+            .unwrap_or_else(|_| PathBuf::from("models.toml"));
+
+        // Only this file is edited, so records from the ./models.toml fallback stay out of it
+        let file = ModelConfig::load_from_file(&config_path).ok();
+        config.model_aliases = file
+            .as_ref()
+            .map(|c| c.model_aliases.clone())
+            .unwrap_or_default();
+        let entries = file.map(|c| c.model_entries).unwrap_or_default();
 
         let mut state = ListState::default();
         if !config.model_aliases.is_empty() {
@@ -59,6 +95,12 @@ impl AliasEditorApp {
             status_message: None,
             editing_index: None,
             temp_alias: None,
+            temp_entry: None,
+            entries,
+            tab: Tab::Aliases,
+            sample_id: DEFAULT_SAMPLE_ID.to_string(),
+            filter: String::new(),
+            searching: false,
         }
     }
 
@@ -100,11 +142,14 @@ impl AliasEditorApp {
                             self.name_input.close();
                             self.input_mode = InputMode::Normal;
                             self.temp_alias = None;
+                            self.temp_entry = None;
                             self.editing_index = None;
                         }
                         KeyCode::Enter => {
-                            // For context limit (optional), allow empty input
-                            if self.input_mode == InputMode::EditingContext {
+                            // For alias context limit (optional), allow empty input
+                            if self.input_mode == InputMode::EditingContext
+                                && self.temp_alias.is_some()
+                            {
                                 self.handle_input_submission(self.name_input.input.clone());
                             } else if let Some(input) = self.name_input.get_input() {
                                 self.handle_input_submission(input);
@@ -117,17 +162,45 @@ impl AliasEditorApp {
                     continue;
                 }
 
+                // Typing a search filter
+                if self.searching {
+                    match key.code {
+                        KeyCode::Esc => self.clear_filter(),
+                        KeyCode::Enter => self.searching = false,
+                        KeyCode::Char(c) => {
+                            self.filter.push(c);
+                            self.reset_selection();
+                        }
+                        KeyCode::Backspace => {
+                            self.filter.pop();
+                            self.reset_selection();
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
                 // Main navigation
                 match key.code {
+                    KeyCode::Esc if !self.filter.is_empty() => self.clear_filter(),
                     KeyCode::Esc | KeyCode::Char('q') => {
                         self.should_quit = true;
                     }
+                    KeyCode::Char('/') => self.searching = true,
+                    KeyCode::Tab => self.switch_tab(),
                     KeyCode::Up => self.previous(),
                     KeyCode::Down => self.next(),
-                    KeyCode::Char('a') => self.start_add_alias(),
-                    KeyCode::Char('e') | KeyCode::Enter => self.start_edit_alias(),
-                    KeyCode::Char('d') | KeyCode::Delete => self.delete_alias(),
+                    KeyCode::Char('a') => match self.tab {
+                        Tab::Aliases => self.start_add_alias(),
+                        Tab::Patterns => self.start_add_entry(),
+                    },
+                    KeyCode::Char('e') | KeyCode::Enter => match self.tab {
+                        Tab::Aliases => self.start_edit_alias(),
+                        Tab::Patterns => self.start_edit_entry(),
+                    },
+                    KeyCode::Char('d') | KeyCode::Delete => self.delete_selected(),
                     KeyCode::Char('s') => self.save_config()?,
+                    KeyCode::Char('p') => self.start_edit_sample(),
                     _ => {}
                 }
             }
@@ -139,13 +212,94 @@ impl AliasEditorApp {
         Ok(())
     }
 
+    /// Indices into the current tab's list that match the search filter, in list order
+    fn visible_indices(&self) -> Vec<usize> {
+        let filter = self.filter.to_lowercase();
+        let matches = |key: &str, name: &str| {
+            filter.is_empty()
+                || key.to_lowercase().contains(&filter)
+                || name.to_lowercase().contains(&filter)
+        };
+        match self.tab {
+            Tab::Aliases => self
+                .config
+                .model_aliases
+                .iter()
+                .enumerate()
+                .filter(|(_, alias)| matches(&alias.id, &alias.display_name))
+                .map(|(i, _)| i)
+                .collect(),
+            Tab::Patterns => self
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| matches(&entry.pattern, &entry.display_name))
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+
+    fn switch_tab(&mut self) {
+        self.tab = match self.tab {
+            Tab::Aliases => Tab::Patterns,
+            Tab::Patterns => Tab::Aliases,
+        };
+        self.filter.clear();
+        self.searching = false;
+        self.reset_selection();
+    }
+
+    /// Model config as the statusline would see it, with the patterns being edited
+    fn effective_config(&self) -> ModelConfig {
+        let mut model_entries = self.entries.clone();
+        model_entries.extend(ModelConfig::default().model_entries);
+        ModelConfig {
+            model_aliases: self.config.model_aliases.clone(),
+            model_entries,
+            case_insensitive: self.config.case_insensitive,
+            fallback_transform: self.config.fallback_transform.clone(),
+        }
+    }
+
+    /// Underlying list index of the highlighted row
+    fn selected_index(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|row| self.visible_indices().get(row).copied())
+    }
+
+    /// Highlight the item at `index` of the current list, clearing the filter if it hides it
+    fn select_index(&mut self, index: usize) {
+        if !self.visible_indices().contains(&index) {
+            self.filter.clear();
+        }
+        let row = self.visible_indices().iter().position(|&i| i == index);
+        self.state.select(row);
+    }
+
+    fn reset_selection(&mut self) {
+        let has_rows = !self.visible_indices().is_empty();
+        self.state.select(has_rows.then_some(0));
+    }
+
+    fn clear_filter(&mut self) {
+        let selected = self.selected_index();
+        self.filter.clear();
+        self.searching = false;
+        match selected {
+            Some(index) => self.select_index(index),
+            None => self.reset_selection(),
+        }
+    }
+
     fn next(&mut self) {
-        if self.config.model_aliases.is_empty() {
+        let len = self.visible_indices().len();
+        if len == 0 {
             return;
         }
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.config.model_aliases.len() - 1 {
+                if i >= len - 1 {
                     0
                 } else {
                     i + 1
@@ -157,13 +311,14 @@ impl AliasEditorApp {
     }
 
     fn previous(&mut self) {
-        if self.config.model_aliases.is_empty() {
+        let len = self.visible_indices().len();
+        if len == 0 {
             return;
         }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.config.model_aliases.len() - 1
+                    len - 1
                 } else {
                     i - 1
                 }
@@ -180,12 +335,14 @@ impl AliasEditorApp {
             id: String::new(),
             display_name: String::new(),
             context_limit: None,
+            case_insensitive: None,
         });
-        self.name_input.open("Add New Alias", "Enter Model ID (exact match):");
+        self.name_input
+            .open("Add New Alias", "Enter Model ID (exact match):");
     }
 
     fn start_edit_alias(&mut self) {
-        if let Some(i) = self.state.selected() {
+        if let Some(i) = self.selected_index() {
             if let Some(alias) = self.config.model_aliases.get(i) {
                 self.input_mode = InputMode::EditingId;
                 self.editing_index = Some(i);
@@ -193,29 +350,80 @@ impl AliasEditorApp {
                 self.name_input.open_with_value(
                     "Edit Alias",
                     "Enter Model ID (exact match):",
-                    &alias.id
+                    &alias.id,
                 );
             }
         }
     }
 
-    fn delete_alias(&mut self) {
-        if let Some(i) = self.state.selected() {
-            if i < self.config.model_aliases.len() {
-                let removed = self.config.model_aliases.remove(i);
-                self.status_message = Some(format!("Deleted alias: {}", removed.display_name));
+    fn start_add_entry(&mut self) {
+        self.input_mode = InputMode::EditingId;
+        self.editing_index = None;
+        self.temp_entry = Some(ModelEntry {
+            pattern: String::new(),
+            display_name: String::new(),
+            context_limit: 0,
+            glob: false,
+        });
+        self.name_input
+            .open("Add New Pattern", "Enter Pattern (substring of model ID):");
+    }
+
+    fn start_edit_entry(&mut self) {
+        if let Some(i) = self.selected_index() {
+            if let Some(entry) = self.entries.get(i) {
+                self.input_mode = InputMode::EditingId;
+                self.editing_index = Some(i);
+                self.temp_entry = Some(entry.clone());
+                self.name_input.open_with_value(
+                    "Edit Pattern",
+                    "Enter Pattern (substring of model ID):",
+                    &entry.pattern,
+                );
+            }
+        }
+    }
 
-                // Adjust selection
-                if self.config.model_aliases.is_empty() {
-                    self.state.select(None);
-                } else if i >= self.config.model_aliases.len() {
-                    self.state.select(Some(self.config.model_aliases.len() - 1));
-                }
+    fn delete_selected(&mut self) {
+        if let (Some(row), Some(i)) = (self.state.selected(), self.selected_index()) {
+            let removed = match self.tab {
+                Tab::Aliases => format!(
+                    "alias: {}",
+                    self.config.model_aliases.remove(i).display_name
+                ),
+                Tab::Patterns => format!("pattern: {}", self.entries.remove(i).pattern),
+            };
+            self.status_message = Some(format!("Deleted {}", removed));
+
+            // Adjust selection
+            let len = self.visible_indices().len();
+            if len == 0 {
+                self.state.select(None);
+            } else if row >= len {
+                self.state.select(Some(len - 1));
             }
         }
     }
 
+    fn start_edit_sample(&mut self) {
+        self.input_mode = InputMode::EditingSample;
+        self.name_input
+            .open_with_value("Preview", "Enter a model ID to preview:", &self.sample_id);
+    }
+
     fn handle_input_submission(&mut self, input: String) {
+        if self.input_mode == InputMode::EditingSample {
+            self.sample_id = input.trim().to_string();
+            self.input_mode = InputMode::Normal;
+            self.name_input.close();
+            return;
+        }
+
+        if self.temp_entry.is_some() {
+            self.handle_entry_submission(input);
+            return;
+        }
+
         if let Some(alias) = &mut self.temp_alias {
             match self.input_mode {
                 InputMode::EditingId => {
@@ -228,20 +436,28 @@ impl AliasEditorApp {
                     }
 
                     // Validate: ID must be unique (except when editing the same entry)
-                    let is_duplicate = self.config.model_aliases.iter().enumerate().any(|(i, a)| {
-                        a.id == trimmed_id && self.editing_index != Some(i)
-                    });
+                    let is_duplicate = self
+                        .config
+                        .model_aliases
+                        .iter()
+                        .enumerate()
+                        .any(|(i, a)| a.id == trimmed_id && self.editing_index != Some(i));
                     if is_duplicate {
-                        self.status_message = Some(format!("Error: Model ID '{}' already exists", trimmed_id));
+                        self.status_message =
+                            Some(format!("Error: Model ID '{}' already exists", trimmed_id));
                         return;
                     }
 
                     alias.id = trimmed_id;
                     self.input_mode = InputMode::EditingName;
                     self.name_input.open_with_value(
-                        if self.editing_index.is_some() { "Edit Alias" } else { "Add New Alias" },
+                        if self.editing_index.is_some() {
+                            "Edit Alias"
+                        } else {
+                            "Add New Alias"
+                        },
                         "Enter Display Name:",
-                        &alias.display_name
+                        &alias.display_name,
                     );
                 }
                 InputMode::EditingName => {
@@ -249,17 +465,25 @@ impl AliasEditorApp {
 
                     // Validate: Display name must not be empty
                     if trimmed_name.is_empty() {
-                        self.status_message = Some("Error: Display name cannot be empty".to_string());
+                        self.status_message =
+                            Some("Error: Display name cannot be empty".to_string());
                         return;
                     }
 
                     alias.display_name = trimmed_name;
                     self.input_mode = InputMode::EditingContext;
-                    let limit_str = alias.context_limit.map(|l| l.to_string()).unwrap_or_default();
+                    let limit_str = alias
+                        .context_limit
+                        .map(|l| l.to_string())
+                        .unwrap_or_default();
                     self.name_input.open_with_value(
-                        if self.editing_index.is_some() { "Edit Alias" } else { "Add New Alias" },
+                        if self.editing_index.is_some() {
+                            "Edit Alias"
+                        } else {
+                            "Add New Alias"
+                        },
                         "Enter Context Limit (optional, press Enter to skip):",
-                        &limit_str
+                        &limit_str,
                     );
                 }
                 InputMode::EditingContext => {
@@ -268,40 +492,115 @@ impl AliasEditorApp {
                         alias.context_limit = None;
                     } else if let Ok(limit) = trimmed.parse::<u32>() {
                         if limit == 0 {
-                            self.status_message = Some("Error: Context limit must be greater than 0".to_string());
+                            self.status_message =
+                                Some("Error: Context limit must be greater than 0".to_string());
                             return;
                         }
                         alias.context_limit = Some(limit);
                     } else {
-                        self.status_message = Some(format!("Error: '{}' is not a valid number", trimmed));
+                        self.status_message =
+                            Some(format!("Error: '{}' is not a valid number", trimmed));
                         return;
                     }
 
                     // Save to list
-                    if let Some(index) = self.editing_index {
+                    let selected = if let Some(index) = self.editing_index {
                         self.config.model_aliases[index] = alias.clone();
                         self.status_message = Some("Alias updated".to_string());
+                        index
                     } else {
                         self.config.model_aliases.push(alias.clone());
                         self.status_message = Some("Alias added".to_string());
-                        // Select new item
-                        self.state.select(Some(self.config.model_aliases.len() - 1));
-                    }
+                        self.config.model_aliases.len() - 1
+                    };
 
                     // Reset state
                     self.temp_alias = None;
                     self.editing_index = None;
                     self.input_mode = InputMode::Normal;
                     self.name_input.close();
+
+                    // Keep the edited item highlighted, even if it no longer matches the filter
+                    self.select_index(selected);
                 }
                 _ => {}
             }
         }
     }
 
-    /// Escape a string for TOML (handle quotes and backslashes)
-    fn escape_toml_string(s: &str) -> String {
-        s.replace('\\', "\\\\").replace('"', "\\\"")
+    fn handle_entry_submission(&mut self, input: String) {
+        let Some(entry) = &mut self.temp_entry else {
+            return;
+        };
+        let title = if self.editing_index.is_some() {
+            "Edit Pattern"
+        } else {
+            "Add New Pattern"
+        };
+
+        match self.input_mode {
+            InputMode::EditingId => {
+                let trimmed_pattern = input.trim().to_string();
+
+                // Validate: pattern must be unique (except when editing the same entry)
+                let is_duplicate =
+                    self.entries.iter().enumerate().any(|(i, e)| {
+                        e.pattern == trimmed_pattern && self.editing_index != Some(i)
+                    });
+                if is_duplicate {
+                    self.status_message = Some(format!(
+                        "Error: Pattern '{}' already exists",
+                        trimmed_pattern
+                    ));
+                    return;
+                }
+
+                entry.pattern = trimmed_pattern;
+                self.input_mode = InputMode::EditingName;
+                self.name_input
+                    .open_with_value(title, "Enter Display Name:", &entry.display_name);
+            }
+            InputMode::EditingName => {
+                entry.display_name = input.trim().to_string();
+                self.input_mode = InputMode::EditingContext;
+                let limit_str = if entry.context_limit > 0 {
+                    entry.context_limit.to_string()
+                } else {
+                    String::new()
+                };
+                self.name_input
+                    .open_with_value(title, "Enter Context Limit:", &limit_str);
+            }
+            InputMode::EditingContext => {
+                let trimmed = input.trim();
+                match trimmed.parse::<u32>() {
+                    Ok(limit) if limit > 0 => entry.context_limit = limit,
+                    _ => {
+                        self.status_message =
+                            Some(format!("Error: '{}' is not a valid context limit", trimmed));
+                        return;
+                    }
+                }
+
+                let selected = if let Some(index) = self.editing_index {
+                    self.entries[index] = entry.clone();
+                    self.status_message = Some("Pattern updated".to_string());
+                    index
+                } else {
+                    self.entries.push(entry.clone());
+                    self.status_message = Some("Pattern added".to_string());
+                    self.entries.len() - 1
+                };
+
+                // Reset state
+                self.temp_entry = None;
+                self.editing_index = None;
+                self.input_mode = InputMode::Normal;
+                self.name_input.close();
+                self.select_index(selected);
+            }
+            _ => {}
+        }
     }
 
     fn save_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
@@ -309,125 +608,176 @@ impl AliasEditorApp {
             std::fs::create_dir_all(parent)?;
         }
 
-        // Try to preserve existing file content (comments, models section, etc.)
+        // Preserve existing file content (comments, [[models]] section, etc.)
         let existing_content = std::fs::read_to_string(&self.config_path).unwrap_or_default();
+        let new_content = Self::render_models_toml(
+            &existing_content,
+            &self.config.model_aliases,
+            &self.entries,
+            &self.config_path,
+        )?;
 
-        // Generate only the aliases section with proper TOML escaping
-        let aliases_toml = if self.config.model_aliases.is_empty() {
-            String::new()
-        } else {
-            let mut aliases_str = String::new();
-            for alias in &self.config.model_aliases {
-                aliases_str.push_str("[[aliases]]\n");
-                aliases_str.push_str(&format!("id = \"{}\"\n", Self::escape_toml_string(&alias.id)));
-                aliases_str.push_str(&format!("display_name = \"{}\"\n", Self::escape_toml_string(&alias.display_name)));
-                if let Some(limit) = alias.context_limit {
-                    aliases_str.push_str(&format!("context_limit = {}\n", limit));
-                }
-                aliases_str.push('\n');
-            }
-            aliases_str
-        };
+        crate::utils::atomic_write(&self.config_path, new_content)?;
+        self.status_message = Some(format!("Saved to {}", self.config_path.display()));
+        Ok(())
+    }
 
-        let new_content = if existing_content.is_empty() {
-            // Create new file with header and aliases
-            format!(
-                "# CCometixLine Model Configuration\n\
-                 # File location: {}\n\
-                 \n\
-                 # =============================================================================\n\
-                 # Model Aliases (Exact Match - Highest Priority)\n\
-                 # =============================================================================\n\
-                 \n\
-                 {}\
+    /// Rewrite the `aliases` and `models` arrays of a models.toml document, keeping everything else intact
+    fn render_models_toml(
+        existing_content: &str,
+        aliases: &[ModelAlias],
+        entries: &[ModelEntry],
+        config_path: &Path,
+    ) -> Result<String, toml_edit::TomlError> {
+        let is_new_file = existing_content.trim().is_empty();
+        let mut doc = existing_content.parse::<DocumentMut>()?;
+
+        let records: Vec<Vec<(&str, Option<Value>)>> = aliases
+            .iter()
+            .map(|alias| {
+                vec![
+                    ("id", Some(Value::from(alias.id.as_str()))),
+                    (
+                        "display_name",
+                        Some(Value::from(alias.display_name.as_str())),
+                    ),
+                    (
+                        "context_limit",
+                        alias.context_limit.map(|l| Value::from(l as i64)),
+                    ),
+                    ("case_insensitive", alias.case_insensitive.map(Value::from)),
+                ]
+            })
+            .collect();
+        Self::update_records(&mut doc, "aliases", &records);
+
+        let records: Vec<Vec<(&str, Option<Value>)>> = entries
+            .iter()
+            .map(|entry| {
+                vec![
+                    ("pattern", Some(Value::from(entry.pattern.as_str()))),
+                    (
+                        "display_name",
+                        Some(Value::from(entry.display_name.as_str())),
+                    ),
+                    (
+                        "context_limit",
+                        Some(Value::from(entry.context_limit as i64)),
+                    ),
+                    ("glob", entry.glob.then(|| Value::from(true))),
+                ]
+            })
+            .collect();
+        Self::update_records(&mut doc, "models", &records);
+
+        if is_new_file {
+            // Start new files with the documented layout
+            if let Some(first) = doc
+                .get_mut("aliases")
+                .and_then(|item| item.as_array_of_tables_mut())
+                .and_then(|tables| tables.get_mut(0))
+            {
+                first.decor_mut().set_prefix(format!(
+                    "# CCometixLine Model Configuration\n\
+                     # File location: {}\n\
+                     \n\
+                     # =============================================================================\n\
+                     # Model Aliases (Exact Match - Highest Priority)\n\
+                     # =============================================================================\n\
+                     \n",
+                    config_path.display()
+                ));
+            }
+            let patterns_header = "\n\
                  # =============================================================================\n\
                  # Model Patterns (Fuzzy Match - Fallback)\n\
                  # =============================================================================\n\
-                 # Add [[models]] entries below for pattern matching\n",
-                self.config_path.display(),
-                aliases_toml
-            )
-        } else {
-            // Preserve existing content, only update aliases section
-            // Strategy: Remove old [[aliases]] entries and insert new ones
-
-            let lines: Vec<&str> = existing_content.lines().collect();
-            let mut new_lines: Vec<String> = Vec::new();
-            let mut in_alias_block = false;
-            let mut aliases_inserted = false;
+                 # Add [[models]] entries below for pattern matching\n";
+            match doc
+                .get_mut("models")
+                .and_then(|item| item.as_array_of_tables_mut())
+                .and_then(|tables| tables.get_mut(0))
+            {
+                Some(first) => first.decor_mut().set_prefix(patterns_header),
+                None => doc.set_trailing(patterns_header),
+            }
+        }
 
-            for line in lines.iter() {
-                let trimmed = line.trim();
+        // Rebuilt `[[...]]` tables always end their last line, the original file may not
+        let mut content = doc.to_string();
+        if !is_new_file && !existing_content.ends_with('\n') && content.ends_with('\n') {
+            content.pop();
+        }
+        Ok(content)
+    }
 
-                // Detect start of [[aliases]] block
-                if trimmed == "[[aliases]]" {
-                    in_alias_block = true;
+    /// Replace the records stored under `key`, reusing existing tables so their comments survive
+    ///
+    /// Works for both `[[key]]` tables and an inline `key = [{ ... }]` array; a
+    /// field set to `None` is removed from its record.
+    fn update_records(doc: &mut DocumentMut, key: &str, records: &[Vec<(&str, Option<Value>)>]) {
+        if records.is_empty() {
+            doc.remove(key);
+            return;
+        }
 
-                    // Insert all new aliases at the position of first [[aliases]]
-                    if !aliases_inserted {
-                        for alias_line in aliases_toml.lines() {
-                            new_lines.push(alias_line.to_string());
-                        }
-                        aliases_inserted = true;
+        if let Some(array) = doc.get_mut(key).and_then(|item| item.as_array_mut()) {
+            // Inline style: keep writing inline tables
+            while array.len() > records.len() {
+                array.remove(array.len() - 1);
+            }
+            for (i, record) in records.iter().enumerate() {
+                if let Some(Value::InlineTable(table)) = array.get_mut(i) {
+                    for (field, value) in record {
+                        Self::set_field(table, field, value.clone());
                     }
-                    continue;
-                }
-
-                // Inside alias block: skip until we hit another section or empty line followed by non-alias content
-                if in_alias_block {
-                    // Check if this line starts a new section
-                    if trimmed.starts_with("[[") || (trimmed.starts_with('[') && !trimmed.starts_with("[[")) {
-                        in_alias_block = false;
-                        new_lines.push(line.to_string());
+                    // Added or removed fields leave uneven spacing behind
+                    table.fmt();
+                } else {
+                    let mut table = InlineTable::new();
+                    for (field, value) in record {
+                        Self::set_field(&mut table, field, value.clone());
+                    }
+                    if i < array.len() {
+                        array.replace(i, table);
+                    } else {
+                        array.push(table);
                     }
-                    // Skip alias content (key = value lines and empty lines within alias blocks)
-                    continue;
                 }
-
-                new_lines.push(line.to_string());
             }
+            return;
+        }
 
-            // If no aliases existed in file, insert at appropriate position
-            if !aliases_inserted && !aliases_toml.is_empty() {
-                // Find position after header comments
-                let mut insert_pos = 0;
-                for (i, line) in new_lines.iter().enumerate() {
-                    let trimmed = line.trim();
-                    if !trimmed.is_empty() && !trimmed.starts_with('#') {
-                        insert_pos = i;
-                        break;
-                    }
-                    insert_pos = i + 1;
-                }
+        let existing = doc
+            .get(key)
+            .and_then(|item| item.as_array_of_tables())
+            .cloned()
+            .unwrap_or_default();
 
-                // Insert aliases
-                let alias_lines: Vec<String> = aliases_toml.lines().map(|s| s.to_string()).collect();
-                for (i, alias_line) in alias_lines.into_iter().enumerate() {
-                    new_lines.insert(insert_pos + i, alias_line);
-                }
+        let mut tables = ArrayOfTables::new();
+        for (i, record) in records.iter().enumerate() {
+            let mut table = existing.get(i).cloned().unwrap_or_default();
+            for (field, value) in record {
+                Self::set_field(&mut table, field, value.clone());
             }
+            tables.push(table);
+        }
+        doc.insert(key, Item::ArrayOfTables(tables));
+    }
 
-            // Clean up: remove excessive empty lines (more than 2 consecutive)
-            let mut result_lines: Vec<String> = Vec::new();
-            let mut empty_count = 0;
-            for line in new_lines {
-                if line.trim().is_empty() {
-                    empty_count += 1;
-                    if empty_count <= 2 {
-                        result_lines.push(line);
-                    }
-                } else {
-                    empty_count = 0;
-                    result_lines.push(line);
+    /// Set or remove one field, keeping the surrounding whitespace and comments of an existing value
+    fn set_field(table: &mut dyn TableLike, field: &str, value: Option<Value>) {
+        match value {
+            Some(mut value) => {
+                if let Some(old) = table.get(field).and_then(|item| item.as_value()) {
+                    *value.decor_mut() = old.decor().clone();
                 }
+                table.insert(field, Item::Value(value));
             }
-
-            result_lines.join("\n")
-        };
-
-        std::fs::write(&self.config_path, new_content)?;
-        self.status_message = Some(format!("Saved to {}", self.config_path.display()));
-        Ok(())
+            None => {
+                table.remove(field);
+            }
+        }
     }
 
     fn ui(&mut self, f: &mut Frame) {
@@ -439,58 +789,183 @@ impl AliasEditorApp {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3),      // Title
-                Constraint::Min(5),         // List
+                Constraint::Length(3),           // Title
+                Constraint::Min(5),              // List
+                Constraint::Length(3),           // Preview
                 Constraint::Length(help_height), // Help/Status
             ])
             .split(size);
 
         // Title
-        let title = Paragraph::new(format!("Model Aliases Editor ({})", self.config_path.display()))
-            .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-            .alignment(ratatui::layout::Alignment::Center);
+        let title = Paragraph::new(format!(
+            "Model Aliases Editor ({})",
+            self.config_path.display()
+        ))
+        .block(Block::default().borders(Borders::ALL))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(ratatui::layout::Alignment::Center);
         f.render_widget(title, chunks[0]);
 
         // List
-        let items: Vec<ListItem> = self.config.model_aliases
-            .iter()
-            .map(|alias| {
-                let limit_str = alias.context_limit
-                    .map(|l| format!(" ({}k)", l / 1000))
-                    .unwrap_or_default();
-
-                let content = Line::from(vec![
-                    Span::styled(format!("{:<30}", alias.display_name), Style::default().fg(Color::Green)),
-                    Span::raw(" │ "),
-                    Span::styled(&alias.id, Style::default().fg(Color::Cyan)),
-                    Span::styled(limit_str, Style::default().fg(Color::Yellow)),
-                ]);
-                ListItem::new(content)
-            })
-            .collect();
+        let row = |name: &str, key: &str, limit: Option<u32>| {
+            let limit_str = limit
+                .map(|l| format!(" ({}k)", l / 1000))
+                .unwrap_or_default();
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<30}", name), Style::default().fg(Color::Green)),
+                Span::raw(" │ "),
+                Span::styled(key.to_string(), Style::default().fg(Color::Cyan)),
+                Span::styled(limit_str, Style::default().fg(Color::Yellow)),
+            ]))
+        };
+        let (items, total): (Vec<ListItem>, usize) = match self.tab {
+            Tab::Aliases => (
+                self.visible_indices()
+                    .into_iter()
+                    .map(|i| &self.config.model_aliases[i])
+                    .map(|alias| row(&alias.display_name, &alias.id, alias.context_limit))
+                    .collect(),
+                self.config.model_aliases.len(),
+            ),
+            Tab::Patterns => (
+                self.visible_indices()
+                    .into_iter()
+                    .map(|i| &self.entries[i])
+                    .map(|entry| {
+                        row(
+                            &entry.display_name,
+                            &entry.pattern,
+                            Some(entry.context_limit),
+                        )
+                    })
+                    .collect(),
+                self.entries.len(),
+            ),
+        };
+
+        // Tab header: the active section is highlighted
+        let tab_span = |tab: Tab, label: &str| {
+            if self.tab == tab {
+                Span::styled(
+                    label.to_string(),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::styled(label.to_string(), Style::default().fg(Color::DarkGray))
+            }
+        };
+        let mut list_title = vec![
+            tab_span(Tab::Aliases, " Aliases "),
+            Span::raw("│"),
+            tab_span(Tab::Patterns, " Patterns "),
+        ];
+        if self.searching || !self.filter.is_empty() {
+            list_title.push(Span::raw(format!(
+                "({} of {}) /{}{} ",
+                items.len(),
+                total,
+                self.filter,
+                if self.searching { "_" } else { "" }
+            )));
+        }
+        let list_title = Line::from(list_title);
 
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Aliases"))
-            .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
+            .block(Block::default().borders(Borders::ALL).title(list_title))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::Cyan)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            )
             .highlight_symbol(">> ");
 
         f.render_stateful_widget(list, chunks[1], &mut self.state);
 
+        // Preview: how the sample id resolves with the aliases and patterns being edited
+        let effective = self.effective_config();
+        let (name_span, source) = match effective.get_display_name(&self.sample_id) {
+            Some(name) => {
+                let exact = effective
+                    .model_aliases
+                    .iter()
+                    .any(|a| effective.alias_matches(a, &self.sample_id));
+                let sample_lower = self.sample_id.to_lowercase();
+                let pattern = effective
+                    .model_entries
+                    .iter()
+                    .any(|e| e.matches(&sample_lower));
+                let source = if exact {
+                    "alias"
+                } else if pattern {
+                    "pattern"
+                } else {
+                    "fallback transform"
+                };
+                (
+                    Span::styled(
+                        name,
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    source,
+                )
+            }
+            None => (
+                Span::styled("no match", Style::default().fg(Color::Red)),
+                "Claude Code display name used",
+            ),
+        };
+        let limit = effective.get_context_limit(&self.sample_id);
+        let preview_line = Line::from(vec![
+            Span::styled(&self.sample_id, Style::default().fg(Color::Cyan)),
+            Span::raw(" → "),
+            name_span,
+            Span::styled(
+                format!(" ({}k)", limit / 1000),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(format!("  [{}]", source), Style::default().fg(Color::Gray)),
+        ]);
+        let preview = Paragraph::new(preview_line).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Preview [P] Change ID"),
+        );
+        f.render_widget(preview, chunks[2]);
+
         // Help + Status
-        let help_text = "[A] Add  [E/Enter] Edit  [D/Del] Delete  [S] Save  [Esc/Q] Quit";
-        let mut lines = vec![
-            Line::from(Span::styled(help_text, Style::default().fg(Color::Gray)))
-        ];
+        let help_text = if self.searching {
+            "Type to filter  [Enter] Keep Filter  [Esc] Clear"
+        } else {
+            match self.tab {
+                Tab::Aliases => "[Tab] Patterns  [A] Add  [E/Enter] Edit  [D/Del] Delete  [/] Search  [P] Preview ID  [S] Save  [Esc/Q] Quit",
+                Tab::Patterns => "[Tab] Aliases  [A] Add Pattern  [E/Enter] Edit  [D/Del] Delete  [/] Search  [P] Preview ID  [S] Save  [Esc/Q] Quit",
+            }
+        };
+        let mut lines = vec![Line::from(Span::styled(
+            help_text,
+            Style::default().fg(Color::Gray),
+        ))];
 
         if let Some(msg) = &self.status_message {
             lines.push(Line::from(""));
-            lines.push(Line::from(Span::styled(msg.clone(), Style::default().fg(Color::Yellow))));
+            lines.push(Line::from(Span::styled(
+                msg.clone(),
+                Style::default().fg(Color::Yellow),
+            )));
         }
 
-        let status = Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL).title("Help"));
-        f.render_widget(status, chunks[2]);
+        let status =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Help"));
+        f.render_widget(status, chunks[3]);
 
         // Popup
         if self.name_input.is_open {
@@ -498,3 +973,168 @@ impl AliasEditorApp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTED: &str = r#"# My models
+case_insensitive = true
+
+# Work aliases
+[[aliases]]
+id = "claude-opus-4"   # the big one
+display_name = "Opus"
+context_limit = 200000
+
+# Kept for the old proxy
+[[aliases]]
+id = "gpt-4o"
+display_name = "GPT-4o"
+
+# Fuzzy fallbacks
+[[models]]
+pattern = "sonnet" # any sonnet
+display_name = "Sonnet"
+context_limit = 200000
+
+[[models]]
+pattern = "gemini-*-pro"
+display_name = "Gemini Pro"
+context_limit = 1000000
+glob = true
+"#;
+
+    const INLINE: &str = r#"aliases = [{ id = "a", display_name = "A" }, { id = "b", display_name = "B", context_limit = 1000 }]
+# trailing comment"#;
+
+    fn parse(content: &str) -> ModelConfig {
+        toml::from_str(content).unwrap()
+    }
+
+    fn render(content: &str, config: &ModelConfig) -> String {
+        AliasEditorApp::render_models_toml(
+            content,
+            &config.model_aliases,
+            &config.model_entries,
+            Path::new("/tmp/models.toml"),
+        )
+        .unwrap()
+    }
+
+    /// Unchanged records write the file back byte for byte
+    #[test]
+    fn unchanged_records_round_trip_exactly() {
+        for content in [COMMENTED, INLINE] {
+            assert_eq!(render(content, &parse(content)), content);
+        }
+    }
+
+    #[test]
+    fn editing_a_record_keeps_interleaved_comments() {
+        let mut config = parse(COMMENTED);
+        config.model_aliases[1].display_name = "GPT 4o".to_string();
+        config.model_aliases[0].context_limit = None;
+        config.model_entries[1].glob = false;
+
+        let output = render(COMMENTED, &config);
+        for kept in [
+            "# My models\ncase_insensitive = true\n",
+            "# Work aliases\n[[aliases]]\n",
+            "id = \"claude-opus-4\"   # the big one\n",
+            "# Kept for the old proxy\n",
+            "pattern = \"sonnet\" # any sonnet\n",
+            "# Fuzzy fallbacks\n",
+        ] {
+            assert!(output.contains(kept), "lost {:?} in:\n{}", kept, output);
+        }
+        assert!(output.contains("display_name = \"GPT 4o\""));
+        assert!(!output.contains("context_limit = 200000\n\n# Kept"));
+        assert!(!output.contains("glob"));
+
+        let reparsed = parse(&output);
+        assert!(reparsed.case_insensitive);
+        assert_eq!(reparsed.model_aliases[0].context_limit, None);
+        assert_eq!(reparsed.model_aliases[1].display_name, "GPT 4o");
+        assert!(!reparsed.model_entries[1].glob);
+    }
+
+    #[test]
+    fn removed_and_added_records() {
+        let mut config = parse(COMMENTED);
+        config.model_aliases.remove(1);
+        config.model_entries.push(ModelEntry {
+            pattern: "haiku".to_string(),
+            display_name: "Haiku".to_string(),
+            context_limit: 200000,
+            glob: false,
+        });
+
+        let output = render(COMMENTED, &config);
+        assert!(!output.contains("gpt-4o"));
+        assert!(output.contains("# Work aliases\n"));
+
+        let reparsed = parse(&output);
+        assert_eq!(reparsed.model_aliases.len(), 1);
+        assert_eq!(reparsed.model_entries.len(), 3);
+        assert_eq!(reparsed.model_entries[2].pattern, "haiku");
+
+        config.model_aliases.clear();
+        let output = render(&output, &config);
+        assert!(!output.contains("[[aliases]]"));
+        assert_eq!(parse(&output).model_entries.len(), 3);
+    }
+
+    #[test]
+    fn inline_tables_stay_inline() {
+        let mut config = parse(INLINE);
+        config.model_aliases[0].context_limit = Some(5000);
+        config.model_aliases.remove(1);
+
+        let output = render(INLINE, &config);
+        assert_eq!(
+            output,
+            "aliases = [{ id = \"a\", display_name = \"A\", context_limit = 5000 }]\n# trailing comment"
+        );
+
+        config.model_aliases.push(ModelAlias {
+            id: "c".to_string(),
+            display_name: "C".to_string(),
+            context_limit: None,
+            case_insensitive: Some(true),
+        });
+        let output = render(&output, &config);
+        assert!(!output.contains("[[aliases]]"), "{}", output);
+        let reparsed = parse(&output);
+        assert_eq!(reparsed.model_aliases[1].id, "c");
+        assert_eq!(reparsed.model_aliases[1].case_insensitive, Some(true));
+    }
+
+    #[test]
+    fn missing_trailing_newline_is_preserved() {
+        let content = "[[aliases]]\nid = \"a\"\ndisplay_name = \"A\"";
+        assert_eq!(render(content, &parse(content)), content);
+
+        let mut config = parse(content);
+        config.model_aliases[0].display_name = "Renamed".to_string();
+        let output = render(content, &config);
+        assert_eq!(
+            output,
+            "[[aliases]]\nid = \"a\"\ndisplay_name = \"Renamed\""
+        );
+    }
+
+    #[test]
+    fn new_file_gets_the_documented_layout() {
+        let config = parse(COMMENTED);
+        let output = render("", &config);
+
+        assert!(output.starts_with("# CCometixLine Model Configuration\n"));
+        assert!(output.contains("# File location: /tmp/models.toml\n"));
+        assert!(output.contains("# Model Patterns (Fuzzy Match - Fallback)"));
+
+        let reparsed = parse(&output);
+        assert_eq!(reparsed.model_aliases.len(), 2);
+        assert_eq!(reparsed.model_entries.len(), 2);
+    }
+}
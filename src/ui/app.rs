@@ -1,8 +1,8 @@
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{AnsiColor, Config, SegmentId, StyleMode};
 use crate::core::segments::TrackedModel;
 use crate::ui::components::{
-    color_picker::{ColorPickerComponent, NavDirection},
     cli_proxy_api_quota_options::{CliProxyApiQuotaOptionField, CliProxyApiQuotaOptionsComponent},
+    color_picker::{ColorPickerComponent, NavDirection},
     help::HelpComponent,
     icon_selector::IconSelectorComponent,
     name_input::NameInputComponent,
@@ -33,6 +33,7 @@ enum TextInputTarget {
     CliProxyApiQuotaHost,
     CliProxyApiQuotaKey,
     CliProxyApiQuotaAlias(TrackedModel),
+    CliProxyApiQuotaColor(TrackedModel),
     CliProxyApiQuotaSeparator,
 }
 
@@ -148,7 +149,8 @@ impl App {
                         KeyCode::Esc => app.separator_editor.close(),
                         KeyCode::Enter => {
                             let new_separator = app.separator_editor.get_separator();
-                            app.config.style.separator = new_separator;
+                            let mode = app.config.style.mode.resolve();
+                            app.config.style.separator.set_for_mode(mode, new_separator);
                             app.separator_editor.close();
                             app.preview.update_preview(&app.config);
                             app.status_message = Some("Separator updated!".to_string());
@@ -220,6 +222,7 @@ impl App {
                         KeyCode::Up => app.cli_proxy_api_quota_options.move_selection(-1),
                         KeyCode::Down => app.cli_proxy_api_quota_options.move_selection(1),
                         KeyCode::Enter => app.open_cli_proxy_api_quota_option_editor(),
+                        KeyCode::Char('#') => app.open_cli_proxy_api_quota_color_input(),
                         _ => {}
                     }
                 } else {
@@ -232,13 +235,18 @@ impl App {
                                 app.name_input.open("Save as New Theme", "Enter theme name");
                                 app.text_input_target = Some(TextInputTarget::SaveThemeName);
                             } else {
-                                // s: Save config to config.toml
+                                // s: Save config to config.toml (or its json/yaml sibling)
                                 if let Err(e) = app.save_config() {
                                     app.status_message =
                                         Some(format!("Failed to save config: {}", e));
                                 } else {
-                                    app.status_message =
-                                        Some("Configuration saved to config.toml!".to_string());
+                                    // Saving succeeded, so the path resolves
+                                    let path = crate::config::Config::get_config_path()
+                                        .unwrap_or_default();
+                                    app.status_message = Some(format!(
+                                        "Configuration saved to {}!",
+                                        path.file_name().unwrap_or_default().to_string_lossy()
+                                    ));
                                 }
                             }
                         }
@@ -260,6 +268,9 @@ impl App {
                                 app.move_selection(1);
                             }
                         }
+                        // Fallback for terminals that don't report Shift+arrows
+                        KeyCode::Char('K') => app.move_segment_up(),
+                        KeyCode::Char('J') => app.move_segment_down(),
                         KeyCode::Enter => app.toggle_current(),
                         KeyCode::Tab => app.switch_panel(),
                         KeyCode::Char('1') => app.switch_to_theme("default"),
@@ -345,7 +356,7 @@ impl App {
             vec![
                 "[Tab] Switch Panel",
                 "[Enter] Toggle/Edit",
-                "[Shift+↑↓] Reorder",
+                "[Shift+↑↓/J/K] Reorder",
                 "[1-4] Theme",
                 "[P] Switch Theme",
                 "[R] Reset",
@@ -482,8 +493,12 @@ impl App {
 
         // Render popups on top
         if self.cli_proxy_api_quota_options.is_open {
-            self.cli_proxy_api_quota_options
-                .render(f, f.area(), &self.config, self.selected_segment);
+            self.cli_proxy_api_quota_options.render(
+                f,
+                f.area(),
+                &self.config,
+                self.selected_segment,
+            );
         }
         if self.color_picker.is_open {
             self.color_picker.render(f, f.area());
@@ -551,6 +566,16 @@ impl App {
                         SegmentId::OutputStyle => "Output Style",
                         SegmentId::Update => "Update",
                         SegmentId::CliProxyApiQuota => "CLI Proxy API Quota",
+                        SegmentId::RateLimit => "Rate Limit",
+                        SegmentId::ModelContext => "Model + Context",
+                        SegmentId::Clock => "Clock",
+                        SegmentId::Battery => "Battery",
+                        SegmentId::Kube => "Kubernetes",
+                        SegmentId::Venv => "Virtualenv",
+                        SegmentId::Command => "Command",
+                        SegmentId::CloudProfile => "Cloud Profile",
+                        SegmentId::Host => "Host",
+                        SegmentId::ExitCode => "Exit Code",
                     };
                     let is_enabled = segment.enabled;
                     self.status_message = Some(format!(
@@ -578,7 +603,17 @@ impl App {
                                 SegmentId::Session => "Session",
                                 SegmentId::OutputStyle => "Output Style",
                                 SegmentId::Update => "Update",
-                        SegmentId::CliProxyApiQuota => "CLI Proxy API Quota",
+                                SegmentId::CliProxyApiQuota => "CLI Proxy API Quota",
+                                SegmentId::RateLimit => "Rate Limit",
+                                SegmentId::ModelContext => "Model + Context",
+                                SegmentId::Clock => "Clock",
+                                SegmentId::Battery => "Battery",
+                                SegmentId::Kube => "Kubernetes",
+                                SegmentId::Venv => "Virtualenv",
+                                SegmentId::Command => "Command",
+                                SegmentId::CloudProfile => "Cloud Profile",
+                                SegmentId::Host => "Host",
+                                SegmentId::ExitCode => "Exit Code",
                             };
                             let is_enabled = segment.enabled;
                             self.status_message = Some(format!(
@@ -612,8 +647,7 @@ impl App {
                         if let Some(segment) = self.config.segments.get(self.selected_segment) {
                             if segment.id == SegmentId::CliProxyApiQuota {
                                 self.cli_proxy_api_quota_options.open();
-                                self.status_message =
-                                    Some("Editing CPA Quota options".to_string());
+                                self.status_message = Some("Editing CPA Quota options".to_string());
                             } else {
                                 self.status_message =
                                     Some("Options editor not implemented yet".to_string());
@@ -639,7 +673,16 @@ impl App {
                 || self.selected_field == FieldSelection::BackgroundColor)
         {
             self.color_picker_target = None;
-            self.color_picker.open();
+            let current = self
+                .config
+                .segments
+                .get(self.selected_segment)
+                .and_then(|segment| match self.selected_field {
+                    FieldSelection::IconColor => segment.colors.icon.as_ref(),
+                    FieldSelection::TextColor => segment.colors.text.as_ref(),
+                    _ => segment.colors.background.as_ref(),
+                });
+            self.color_picker.open_with_color(current);
         }
     }
 
@@ -649,22 +692,9 @@ impl App {
         }
     }
 
-    fn apply_selected_color(&mut self, color: crate::config::AnsiColor) {
+    fn apply_selected_color(&mut self, color: AnsiColor) {
         if let Some(target) = self.color_picker_target {
-            match target {
-                ColorPickerTarget::CliProxyApiQuotaModelColor(model) => {
-                    if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
-                        if segment.id == SegmentId::CliProxyApiQuota {
-                            if let Ok(v) = serde_json::to_value(color) {
-                                segment.options.insert(model.color_key().to_string(), v);
-                                self.status_message =
-                                    Some(format!("Updated {} color", model.display_name()));
-                                self.preview.update_preview(&self.config);
-                            }
-                        }
-                    }
-                }
-            }
+            self.apply_selected_color_to(target, color);
             return;
         }
 
@@ -679,11 +709,30 @@ impl App {
         }
     }
 
+    fn apply_selected_color_to(&mut self, target: ColorPickerTarget, color: AnsiColor) {
+        match target {
+            ColorPickerTarget::CliProxyApiQuotaModelColor(model) => {
+                if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
+                    if segment.id == SegmentId::CliProxyApiQuota {
+                        if let Ok(v) = serde_json::to_value(color) {
+                            segment.options.insert(model.color_key().to_string(), v);
+                            self.status_message =
+                                Some(format!("Updated {} color", model.display_name()));
+                            self.preview.update_preview(&self.config);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     fn apply_selected_icon(&mut self, icon: String) {
         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
-            match self.config.style.mode {
+            match self.config.style.mode.resolve() {
                 StyleMode::Plain => segment.icon.plain = icon,
-                StyleMode::NerdFont | StyleMode::Powerline => segment.icon.nerd_font = icon,
+                StyleMode::NerdFont | StyleMode::Powerline | StyleMode::Auto => {
+                    segment.icon.nerd_font = icon
+                }
             }
             self.preview.update_preview(&self.config);
         }
@@ -723,25 +772,33 @@ impl App {
 
     /// Move the currently selected segment up in the list
     fn move_segment_up(&mut self) {
-        if self.selected_panel == Panel::SegmentList && self.selected_segment > 0 {
+        if self.selected_panel != Panel::SegmentList {
+            return;
+        }
+        if self.selected_segment > 0 && self.selected_segment < self.config.segments.len() {
             let current_idx = self.selected_segment;
             self.config.segments.swap(current_idx, current_idx - 1);
             self.selected_segment -= 1;
             self.preview.update_preview(&self.config);
             self.status_message = Some("Moved segment up".to_string());
+        } else {
+            self.status_message = Some("Segment is already first".to_string());
         }
     }
 
     /// Move the currently selected segment down in the list
     fn move_segment_down(&mut self) {
-        if self.selected_panel == Panel::SegmentList
-            && self.selected_segment < self.config.segments.len() - 1
-        {
+        if self.selected_panel != Panel::SegmentList {
+            return;
+        }
+        if self.selected_segment + 1 < self.config.segments.len() {
             let current_idx = self.selected_segment;
             self.config.segments.swap(current_idx, current_idx + 1);
             self.selected_segment += 1;
             self.preview.update_preview(&self.config);
             self.status_message = Some("Moved segment down".to_string());
+        } else {
+            self.status_message = Some("Segment is already last".to_string());
         }
     }
 
@@ -776,7 +833,7 @@ impl App {
     /// Open separator editor with current separator
     fn open_separator_editor(&mut self) {
         self.status_message = Some("Opening separator editor...".to_string());
-        self.separator_editor.open(&self.config.style.separator);
+        self.separator_editor.open(self.config.style.separator());
     }
 
     fn apply_text_input(&mut self, value: String) {
@@ -827,10 +884,49 @@ impl App {
                     }
                 }
             }
+            Some(TextInputTarget::CliProxyApiQuotaColor(model)) => {
+                let Some(color) = AnsiColor::parse(&value) else {
+                    self.status_message = Some(format!(
+                        "Invalid color '{}', {} color unchanged",
+                        value.trim(),
+                        model.display_name()
+                    ));
+                    return;
+                };
+                self.apply_selected_color_to(
+                    ColorPickerTarget::CliProxyApiQuotaModelColor(model),
+                    color,
+                );
+            }
             None => {}
         }
     }
 
+    /// Type a quota model color as `#rrggbb`, `#rgb` or `hsl(h, s%, l%)`
+    fn open_cli_proxy_api_quota_color_input(&mut self) {
+        let Some(segment) = self.config.segments.get(self.selected_segment) else {
+            return;
+        };
+        let CliProxyApiQuotaOptionField::Color(model) =
+            self.cli_proxy_api_quota_options.selected_field()
+        else {
+            return;
+        };
+
+        let current = segment
+            .options
+            .get(model.color_key())
+            .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok())
+            .map(|color| color.to_hex())
+            .unwrap_or_default();
+        self.name_input.open_with_value(
+            &format!("{} Color", model.display_name()),
+            "#rrggbb, #rgb or hsl(h, s%, l%)",
+            &current,
+        );
+        self.text_input_target = Some(TextInputTarget::CliProxyApiQuotaColor(model));
+    }
+
     fn open_cli_proxy_api_quota_option_editor(&mut self) {
         let Some(segment) = self.config.segments.get(self.selected_segment) else {
             return;
@@ -846,11 +942,8 @@ impl App {
                     .get("host")
                     .and_then(|v| v.as_str())
                     .unwrap_or("http://localhost:8317");
-                self.name_input.open_with_value(
-                    "CLI Proxy API Host",
-                    "Enter host URL...",
-                    current,
-                );
+                self.name_input
+                    .open_with_value("CLI Proxy API Host", "Enter host URL...", current);
                 self.text_input_target = Some(TextInputTarget::CliProxyApiQuotaHost);
             }
             CliProxyApiQuotaOptionField::Key => {
@@ -880,8 +973,13 @@ impl App {
                 self.text_input_target = Some(TextInputTarget::CliProxyApiQuotaAlias(model));
             }
             CliProxyApiQuotaOptionField::Color(model) => {
-                self.color_picker_target = Some(ColorPickerTarget::CliProxyApiQuotaModelColor(model));
-                self.color_picker.open();
+                let current = segment
+                    .options
+                    .get(model.color_key())
+                    .and_then(|v| serde_json::from_value::<AnsiColor>(v.clone()).ok());
+                self.color_picker_target =
+                    Some(ColorPickerTarget::CliProxyApiQuotaModelColor(model));
+                self.color_picker.open_with_color(current.as_ref());
             }
             CliProxyApiQuotaOptionField::Separator => {
                 let current = segment
@@ -889,8 +987,11 @@ impl App {
                     .get("separator")
                     .and_then(|v| v.as_str())
                     .unwrap_or(" | ");
-                self.name_input
-                    .open_with_value("CLI Proxy API Quota Separator", "Enter separator...", current);
+                self.name_input.open_with_value(
+                    "CLI Proxy API Quota Separator",
+                    "Enter separator...",
+                    current,
+                );
                 self.text_input_target = Some(TextInputTarget::CliProxyApiQuotaSeparator);
             }
         }
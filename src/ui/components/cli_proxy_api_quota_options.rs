@@ -66,6 +66,10 @@ impl CliProxyApiQuotaOptionsComponent {
             CliProxyApiQuotaOptionField::Key,
             CliProxyApiQuotaOptionField::Alias(TrackedModel::Opus),
             CliProxyApiQuotaOptionField::Color(TrackedModel::Opus),
+            CliProxyApiQuotaOptionField::Alias(TrackedModel::Sonnet),
+            CliProxyApiQuotaOptionField::Color(TrackedModel::Sonnet),
+            CliProxyApiQuotaOptionField::Alias(TrackedModel::Haiku),
+            CliProxyApiQuotaOptionField::Color(TrackedModel::Haiku),
             CliProxyApiQuotaOptionField::Alias(TrackedModel::Gemini3Pro),
             CliProxyApiQuotaOptionField::Color(TrackedModel::Gemini3Pro),
             CliProxyApiQuotaOptionField::Alias(TrackedModel::Gemini3Flash),
@@ -92,7 +96,7 @@ impl CliProxyApiQuotaOptionsComponent {
         match color {
             Some(AnsiColor::Color16 { c16 }) => format!("c16:{}", c16),
             Some(AnsiColor::Color256 { c256 }) => format!("c256:{}", c256),
-            Some(AnsiColor::Rgb { r, g, b }) => format!("rgb({},{},{})", r, g, b),
+            Some(color @ AnsiColor::Rgb { .. }) => color.to_hex(),
             None => "default".to_string(),
         }
     }
@@ -137,7 +141,7 @@ impl CliProxyApiQuotaOptionsComponent {
 
         // Avoid covering bottom help area
         let popup_width = 70_u16.min(area.width.saturating_sub(4));
-        let popup_height = 18_u16;
+        let popup_height = 22_u16;
         let max_y = area.height.saturating_sub(popup_height + 4);
         let popup_y = if max_y > 2 {
             (area.height.saturating_sub(popup_height)) / 2
@@ -187,7 +191,9 @@ impl CliProxyApiQuotaOptionsComponent {
                     spans.push(Span::raw("Host: ".to_string()));
                     spans.push(Span::styled(
                         host.to_string(),
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
                     ));
                 }
                 CliProxyApiQuotaOptionField::Key => {
@@ -205,7 +211,9 @@ impl CliProxyApiQuotaOptionsComponent {
                     spans.push(Span::raw("Key: ".to_string()));
                     spans.push(Span::styled(
                         masked,
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
                     ));
                 }
                 CliProxyApiQuotaOptionField::Alias(model) => {
@@ -213,7 +221,9 @@ impl CliProxyApiQuotaOptionsComponent {
                     spans.push(Span::raw(format!("{} Alias: ", model.display_name())));
                     spans.push(Span::styled(
                         alias,
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
                     ));
                 }
                 CliProxyApiQuotaOptionField::Color(model) => {
@@ -240,7 +250,9 @@ impl CliProxyApiQuotaOptionsComponent {
                     spans.push(Span::raw("Separator: ".to_string()));
                     spans.push(Span::styled(
                         sep.to_string(),
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
                     ));
                 }
             }
@@ -259,7 +271,7 @@ impl CliProxyApiQuotaOptionsComponent {
         );
 
         f.render_widget(
-            Paragraph::new("[↑↓] Navigate  [Enter] Edit  [Esc] Close")
+            Paragraph::new("[↑↓] Navigate  [Enter] Edit  [#] Type color  [Esc] Close")
                 .block(Block::default().borders(Borders::ALL)),
             chunks[1],
         );
@@ -87,9 +87,37 @@ impl ColorPickerComponent {
     }
 
     pub fn open(&mut self) {
+        self.open_with_color(None);
+    }
+
+    /// Open with `color` preselected in the matching mode, so Enter keeps it unchanged
+    pub fn open_with_color(&mut self, color: Option<&AnsiColor>) {
         self.is_open = true;
+        self.current_color = color.cloned();
         self.mode = ColorPickerMode::Basic16;
         self.selected_basic = 0;
+
+        match color {
+            Some(AnsiColor::Color16 { c16 }) => {
+                self.selected_basic = (*c16 as usize).min(15);
+            }
+            Some(AnsiColor::Color256 { c256 }) => {
+                self.mode = ColorPickerMode::Extended256;
+                self.selected_extended = *c256 as usize;
+            }
+            Some(AnsiColor::Rgb { r, g, b }) => {
+                self.mode = ColorPickerMode::RgbInput;
+                self.rgb_input.r = r.to_string();
+                self.rgb_input.g = g.to_string();
+                self.rgb_input.b = b.to_string();
+                self.rgb_input.hex = format!("{:02X}{:02X}{:02X}", r, g, b);
+                self.rgb_input.editing_field = RgbField::Red;
+            }
+            None => {}
+        }
+
+        self.basic_list_state.select(Some(self.selected_basic));
+        self.show_extended = matches!(self.mode, ColorPickerMode::Extended256);
     }
 
     pub fn close(&mut self) {
@@ -323,16 +351,10 @@ impl ColorPickerComponent {
     }
 
     fn update_rgb_color(&mut self) {
-        if !self.rgb_input.hex.is_empty() && self.rgb_input.hex.len() == 6 {
-            // Parse hex color
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                u8::from_str_radix(&self.rgb_input.hex[0..2], 16),
-                u8::from_str_radix(&self.rgb_input.hex[2..4], 16),
-                u8::from_str_radix(&self.rgb_input.hex[4..6], 16),
-            ) {
-                self.current_color = Some(AnsiColor::Rgb { r, g, b });
-                return;
-            }
+        // Hex wins once it is complete, `rrggbb` or the `rgb` shorthand
+        if let Some(color) = AnsiColor::from_hex(&self.rgb_input.hex) {
+            self.current_color = Some(color);
+            return;
         }
 
         // Parse RGB values
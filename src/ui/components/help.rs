@@ -41,7 +41,7 @@ impl HelpComponent {
             vec![
                 ("[Tab]", "Switch Panel"),
                 ("[Enter]", "Toggle/Edit"),
-                ("[Shift+↑↓]", "Reorder"),
+                ("[Shift+↑↓/J/K]", "Reorder"),
                 ("[1-4]", "Theme"),
                 ("[P]", "Switch Theme"),
                 ("[R]", "Reset"),
@@ -60,7 +60,8 @@ impl IconSelectorComponent {
         self.is_open = true;
         self.icon_style = match current_style {
             StyleMode::Plain => IconStyle::Plain,
-            StyleMode::NerdFont | StyleMode::Powerline => IconStyle::NerdFont,
+            StyleMode::Auto if current_style.resolve() == StyleMode::Plain => IconStyle::Plain,
+            StyleMode::NerdFont | StyleMode::Powerline | StyleMode::Auto => IconStyle::NerdFont,
         };
         self.update_current_icon();
     }
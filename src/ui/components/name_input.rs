@@ -49,8 +49,14 @@ impl NameInputComponent {
     }
 
     pub fn input_char(&mut self, c: char) {
-        // Allow alphanumeric, common punctuation for model IDs and display names
-        if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ' | ':' | '/' | '+') {
+        // Allow alphanumeric, common punctuation for model IDs and display names,
+        // and what `#rrggbb` / `hsl(h, s%, l%)` colors need
+        if c.is_ascii_alphanumeric()
+            || matches!(
+                c,
+                '_' | '-' | '.' | ' ' | ':' | '/' | '+' | '#' | '(' | ')' | ',' | '%'
+            )
+        {
             self.input.push(c);
         }
     }
@@ -103,6 +103,7 @@ impl PreviewComponent {
                         map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::Directory => SegmentData {
                     primary: "CCometixLine".to_string(),
@@ -112,6 +113,7 @@ impl PreviewComponent {
                         map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::Git => SegmentData {
                     primary: "master".to_string(),
@@ -124,6 +126,7 @@ impl PreviewComponent {
                         map.insert("behind".to_string(), "0".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::ContextWindow => SegmentData {
                     primary: "78.2%".to_string(),
@@ -135,11 +138,13 @@ impl PreviewComponent {
                         map.insert("session_tokens".to_string(), "48200".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::Usage => SegmentData {
                     primary: "24%".to_string(),
                     secondary: "· 10-7-2".to_string(),
                     metadata: HashMap::new(),
+                    raw: false,
                 },
                 SegmentId::Cost => SegmentData {
                     primary: "$0.02".to_string(),
@@ -149,6 +154,7 @@ impl PreviewComponent {
                         map.insert("cost".to_string(), "0.01234".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::Session => SegmentData {
                     primary: "3m45s".to_string(),
@@ -160,6 +166,7 @@ impl PreviewComponent {
                         map.insert("lines_removed".to_string(), "23".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::OutputStyle => SegmentData {
                     primary: "default".to_string(),
@@ -169,6 +176,7 @@ impl PreviewComponent {
                         map.insert("style_name".to_string(), "default".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::Update => SegmentData {
                     primary: format!("v{}", env!("CARGO_PKG_VERSION")),
@@ -182,6 +190,7 @@ impl PreviewComponent {
                         map.insert("update_available".to_string(), "false".to_string());
                         map
                     },
+                    raw: false,
                 },
                 SegmentId::CliProxyApiQuota => SegmentData {
                     primary: {
@@ -204,13 +213,19 @@ impl PreviewComponent {
                             segment_config
                                 .options
                                 .get(key)
-                                .and_then(|v| serde_json::from_value::<crate::config::AnsiColor>(v.clone()).ok())
+                                .and_then(|v| {
+                                    serde_json::from_value::<crate::config::AnsiColor>(v.clone())
+                                        .ok()
+                                })
                                 .unwrap_or(default)
                         };
 
                         let opus = CliProxyApiQuotaSegment::apply_foreground_color(
                             &format!("{}:27%", alias("opus_alias", "opus")),
-                            &color("opus_color", crate::config::AnsiColor::Color256 { c256: 214 }),
+                            &color(
+                                "opus_color",
+                                crate::config::AnsiColor::Color256 { c256: 214 },
+                            ),
                         );
                         let g3p = CliProxyApiQuotaSegment::apply_foreground_color(
                             &format!("{}:100%", alias("gemini3pro_alias", "3pro")),
@@ -227,14 +242,129 @@ impl PreviewComponent {
                             ),
                         );
 
-                        vec![opus, g3p, g3f].join(separator)
+                        [opus, g3p, g3f].join(separator)
+                    },
+                    secondary: "".to_string(),
+                    metadata: HashMap::new(),
+                    raw: true,
+                },
+                SegmentId::RateLimit => SegmentData {
+                    primary: "rate limited".to_string(),
+                    secondary: "30s".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("state".to_string(), "rate_limited".to_string());
+                        map.insert("retry_after_seconds".to_string(), "30".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::ModelContext => SegmentData {
+                    primary: "Sonnet 4".to_string(),
+                    secondary: "52%".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert(
+                            "model_id".to_string(),
+                            "claude-4-sonnet-20250512".to_string(),
+                        );
+                        map.insert("percentage".to_string(), "52".to_string());
+                        map.insert("severity".to_string(), "normal".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::Clock => SegmentData {
+                    primary: "14:05".to_string(),
+                    secondary: "".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("format".to_string(), "%H:%M".to_string());
+                        map.insert("timezone".to_string(), "local".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::Battery => SegmentData {
+                    primary: "85%".to_string(),
+                    secondary: "⚡".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("percentage".to_string(), "85".to_string());
+                        map.insert("state".to_string(), "charging".to_string());
+                        map.insert("severity".to_string(), "normal".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::Kube => SegmentData {
+                    primary: "prod-cluster".to_string(),
+                    secondary: "payments".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("context".to_string(), "prod-cluster".to_string());
+                        map.insert("namespace".to_string(), "payments".to_string());
+                        map
                     },
+                    raw: false,
+                },
+                SegmentId::Venv => SegmentData {
+                    primary: "myproject ⬢ 20".to_string(),
+                    secondary: "".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("env".to_string(), "myproject".to_string());
+                        map.insert("node_version".to_string(), "20".to_string());
+                        map.insert("sources".to_string(), "virtualenv,node".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::Command => SegmentData {
+                    primary: "42 open PRs".to_string(),
+                    secondary: "".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("command".to_string(), "gh pr list | wc -l".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::CloudProfile => SegmentData {
+                    primary: "aws prod us-east-1".to_string(),
+                    secondary: "".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("aws_profile".to_string(), "prod".to_string());
+                        map.insert("aws_region".to_string(), "us-east-1".to_string());
+                        map.insert("sources".to_string(), "aws_profile,aws_region".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::Host => SegmentData {
+                    primary: "build-01".to_string(),
+                    secondary: "".to_string(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("hostname".to_string(), "build-01.example.com".to_string());
+                        map.insert("short_hostname".to_string(), "build-01".to_string());
+                        map.insert("ssh".to_string(), "true".to_string());
+                        map
+                    },
+                    raw: false,
+                },
+                SegmentId::ExitCode => SegmentData {
+                    primary: "1".to_string(),
                     secondary: "".to_string(),
                     metadata: {
                         let mut map = HashMap::new();
-                        map.insert("raw_text".to_string(), "true".to_string());
+                        map.insert("exit_code".to_string(), "1".to_string());
+                        map.insert("success".to_string(), "false".to_string());
+                        map.insert("dynamic_icon".to_string(), "✗".to_string());
                         map
                     },
+                    raw: false,
                 },
             };
 
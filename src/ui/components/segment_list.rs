@@ -58,6 +58,16 @@ impl SegmentListComponent {
                     SegmentId::OutputStyle => "Output Style",
                     SegmentId::Update => "Update",
                     SegmentId::CliProxyApiQuota => "CLI Proxy API Quota",
+                    SegmentId::RateLimit => "Rate Limit",
+                    SegmentId::ModelContext => "Model + Context",
+                    SegmentId::Clock => "Clock",
+                    SegmentId::Battery => "Battery",
+                    SegmentId::Kube => "Kubernetes",
+                    SegmentId::Venv => "Virtualenv",
+                    SegmentId::Command => "Command",
+                    SegmentId::CloudProfile => "Cloud Profile",
+                    SegmentId::Host => "Host",
+                    SegmentId::ExitCode => "Exit Code",
                 };
 
                 if is_selected {
@@ -37,10 +37,22 @@ impl SettingsComponent {
                 SegmentId::OutputStyle => "Output Style",
                 SegmentId::Update => "Update",
                 SegmentId::CliProxyApiQuota => "CLI Proxy API Quota",
+                SegmentId::RateLimit => "Rate Limit",
+                SegmentId::ModelContext => "Model + Context",
+                SegmentId::Clock => "Clock",
+                SegmentId::Battery => "Battery",
+                SegmentId::Kube => "Kubernetes",
+                SegmentId::Venv => "Virtualenv",
+                SegmentId::Command => "Command",
+                SegmentId::CloudProfile => "Cloud Profile",
+                SegmentId::Host => "Host",
+                SegmentId::ExitCode => "Exit Code",
             };
-            let current_icon = match config.style.mode {
+            let current_icon = match config.style.mode.resolve() {
                 StyleMode::Plain => &segment.icon.plain,
-                StyleMode::NerdFont | StyleMode::Powerline => &segment.icon.nerd_font,
+                StyleMode::NerdFont | StyleMode::Powerline | StyleMode::Auto => {
+                    &segment.icon.nerd_font
+                }
             };
             // Convert AnsiColor to ratatui Color
             let icon_ratatui_color = match &segment.colors.icon {
@@ -56,7 +56,7 @@ impl ThemeSelectorComponent {
         }
 
         // Add separator display at the end
-        let separator_display = format!("\nSeparator: \"{}\"", config.style.separator);
+        let separator_display = format!("\nSeparator: \"{}\"", config.style.separator());
 
         let full_text = format!("{}{}", lines.join("\n"), separator_display);
         let title = format!("Themes{}", modified_indicator);
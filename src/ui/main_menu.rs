@@ -85,10 +85,8 @@ impl MainMenu {
                     KeyCode::Esc | KeyCode::Char('q') => {
                         self.should_quit = true;
                     }
-                    KeyCode::Up => {
-                        if self.selected_item > 0 {
-                            self.selected_item -= 1;
-                        }
+                    KeyCode::Up if self.selected_item > 0 => {
+                        self.selected_item -= 1;
                     }
                     KeyCode::Down => {
                         let menu_items = self.get_menu_items();
@@ -1,6 +1,9 @@
 // Theme presets for TUI configuration
 
-use crate::config::{AnsiColor, ColorConfig, Config, IconConfig, SegmentConfig, SegmentId, StyleConfig, StyleMode, TextStyleConfig};
+use crate::config::{
+    AnsiColor, ColorConfig, ColorDepth, Config, ConfigLoader, IconConfig, SegmentAlign,
+    SegmentConfig, SegmentId, StyleConfig, StyleMode, TextStyleConfig,
+};
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -18,6 +21,8 @@ impl ThemePresets {
         SegmentConfig {
             id: SegmentId::CliProxyApiQuota,
             enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
             icon: IconConfig {
                 plain: "📈".to_string(),
                 nerd_font: "\u{f0201}".to_string(),
@@ -55,6 +60,308 @@ impl ThemePresets {
         }
     }
 
+    /// Default Rate Limit segment configuration (shared across all themes)
+    fn default_rate_limit_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::RateLimit,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "⚠️".to_string(),
+                nerd_font: "\u{f071}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 11 }),
+                text: Some(AnsiColor::Color16 { c16: 11 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert("show_countdown".to_string(), serde_json::Value::Bool(true));
+                opts
+            },
+        }
+    }
+
+    /// Default Model + Context badge segment configuration (shared across all themes)
+    fn default_model_context_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::ModelContext,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "🤖".to_string(),
+                nerd_font: "\u{e26d}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 14 }),
+                text: Some(AnsiColor::Color16 { c16: 14 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert("warn_threshold".to_string(), serde_json::Value::from(70));
+                opts.insert(
+                    "critical_threshold".to_string(),
+                    serde_json::Value::from(90),
+                );
+                opts
+            },
+        }
+    }
+
+    /// Default Battery segment configuration (shared across all themes)
+    fn default_battery_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Battery,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "🔋".to_string(),
+                nerd_font: "\u{f0079}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 10 }),
+                text: Some(AnsiColor::Color16 { c16: 10 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert("low_threshold".to_string(), serde_json::Value::from(20));
+                opts.insert(
+                    "critical_threshold".to_string(),
+                    serde_json::Value::from(10),
+                );
+                opts
+            },
+        }
+    }
+
+    /// Default Kubernetes context segment configuration (shared across all themes)
+    fn default_kube_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Kube,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "☸".to_string(),
+                nerd_font: "\u{f10fe}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 12 }),
+                text: Some(AnsiColor::Color16 { c16: 12 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert("show_namespace".to_string(), serde_json::Value::Bool(true));
+                opts.insert("cache_duration".to_string(), serde_json::Value::from(5));
+                opts
+            },
+        }
+    }
+
+    /// Default virtualenv/Node segment configuration (shared across all themes)
+    fn default_venv_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Venv,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "🐍".to_string(),
+                nerd_font: "\u{e73c}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 11 }),
+                text: Some(AnsiColor::Color16 { c16: 11 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert(
+                    "detect_order".to_string(),
+                    serde_json::json!(["virtualenv", "conda", "node"]),
+                );
+                opts.insert("icons".to_string(), serde_json::json!({ "node": "⬢" }));
+                opts.insert(
+                    "show_conda_base".to_string(),
+                    serde_json::Value::Bool(false),
+                );
+                opts
+            },
+        }
+    }
+
+    /// Default custom command segment configuration (shared across all themes)
+    ///
+    /// Disabled because enabling it runs the configured shell command.
+    fn default_command_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Command,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "⚙".to_string(),
+                nerd_font: "\u{f120}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 13 }),
+                text: Some(AnsiColor::Color16 { c16: 13 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert(
+                    "command".to_string(),
+                    serde_json::Value::String(String::new()),
+                );
+                opts.insert(
+                    "command_timeout_ms".to_string(),
+                    serde_json::Value::from(500),
+                );
+                opts.insert("cache_duration".to_string(), serde_json::Value::from(10));
+                opts.insert("max_length".to_string(), serde_json::Value::from(40));
+                opts
+            },
+        }
+    }
+
+    /// Default cloud profile segment configuration (shared across all themes)
+    fn default_cloud_profile_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::CloudProfile,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "☁️".to_string(),
+                nerd_font: "\u{f0c2}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 3 }),
+                text: Some(AnsiColor::Color16 { c16: 3 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert(
+                    "show".to_string(),
+                    serde_json::json!(["aws_profile", "aws_region", "gcp_project"]),
+                );
+                opts.insert(
+                    "icons".to_string(),
+                    serde_json::json!({ "aws_profile": "aws", "gcp_project": "gcp" }),
+                );
+                opts
+            },
+        }
+    }
+
+    /// Default host segment configuration (shared across all themes)
+    fn default_host_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Host,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "🖥️".to_string(),
+                nerd_font: "\u{f233}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 9 }),
+                text: Some(AnsiColor::Color16 { c16: 9 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert("ssh_only".to_string(), serde_json::Value::Bool(true));
+                opts.insert("fqdn".to_string(), serde_json::Value::Bool(false));
+                opts
+            },
+        }
+    }
+
+    /// Default exit code segment configuration (shared across all themes)
+    ///
+    /// Colors come from the status, so the segment sets none of its own.
+    fn default_exit_code_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::ExitCode,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "✓".to_string(),
+                nerd_font: "\u{f00c}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: None,
+                text: None,
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert(
+                    "env_var".to_string(),
+                    serde_json::Value::String("CCLINE_EXIT_CODE".to_string()),
+                );
+                opts.insert(
+                    "success_icon".to_string(),
+                    serde_json::Value::String("✓".to_string()),
+                );
+                opts.insert(
+                    "failure_icon".to_string(),
+                    serde_json::Value::String("✗".to_string()),
+                );
+                opts
+            },
+        }
+    }
+
+    /// Default Clock segment configuration (shared across all themes)
+    fn default_clock_segment() -> SegmentConfig {
+        SegmentConfig {
+            id: SegmentId::Clock,
+            enabled: false,
+            align: SegmentAlign::Left,
+            line: 0,
+            icon: IconConfig {
+                plain: "🕐".to_string(),
+                nerd_font: "\u{f0954}".to_string(),
+            },
+            colors: ColorConfig {
+                icon: Some(AnsiColor::Color16 { c16: 12 }),
+                text: Some(AnsiColor::Color16 { c16: 12 }),
+                background: None,
+            },
+            styles: TextStyleConfig::default(),
+            options: {
+                let mut opts = HashMap::new();
+                opts.insert(
+                    "format".to_string(),
+                    serde_json::Value::String("%H:%M".to_string()),
+                );
+                opts
+            },
+        }
+    }
+
     pub fn get_theme(theme_name: &str) -> Config {
         // First try to load from file
         if let Ok(config) = Self::load_theme_from_file(theme_name) {
@@ -67,15 +374,23 @@ impl ThemePresets {
 
     /// Load theme from file system
     pub fn load_theme_from_file(theme_name: &str) -> Result<Config, Box<dyn std::error::Error>> {
-        let themes_dir = Self::get_themes_path();
+        let themes_dir = ConfigLoader::get_themes_path()?;
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
         if !theme_path.exists() {
             return Err(format!("Theme file not found: {}", theme_path.display()).into());
         }
 
-        let content = std::fs::read_to_string(&theme_path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        Self::load_theme_from_path(&theme_path, theme_name)
+    }
+
+    /// Load a theme TOML from any path, named `theme_name`
+    pub fn load_theme_from_path(
+        theme_path: &std::path::Path,
+        theme_name: &str,
+    ) -> Result<Config, Box<dyn std::error::Error>> {
+        let mut chain = vec![theme_name.to_string()];
+        let mut config = Self::resolve_theme_file(theme_path, &mut chain)?;
 
         // Ensure the theme field matches the requested theme
         config.theme = theme_name.to_string();
@@ -88,6 +403,208 @@ impl ThemePresets {
         Ok(config)
     }
 
+    /// Parse a theme file, overlaying it on the theme named by its `extends` field
+    ///
+    /// `chain` holds the theme names visited so far and is used to reject cycles.
+    fn resolve_theme_file(
+        theme_path: &std::path::Path,
+        chain: &mut Vec<String>,
+    ) -> Result<Config, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(theme_path)?;
+        let mut raw: toml::Table = toml::from_str(&content)?;
+
+        let parent = match raw.remove("extends") {
+            None => return Ok(toml::Value::Table(raw).try_into()?),
+            Some(toml::Value::String(parent)) => parent,
+            Some(other) => {
+                return Err(format!("extends: expected a theme name, got {}", other).into())
+            }
+        };
+
+        let is_cycle = chain.contains(&parent);
+        chain.push(parent.clone());
+        if is_cycle {
+            return Err(format!("Theme inheritance cycle: {}", chain.join(" -> ")).into());
+        }
+
+        // A parent theme file wins over the built-in of the same name, like get_theme
+        let parent_path = ConfigLoader::get_themes_path()?.join(format!("{}.toml", parent));
+        let parent_config = if parent_path.exists() {
+            Self::resolve_theme_file(&parent_path, chain)?
+        } else {
+            Self::builtin_theme(&parent)
+                .ok_or_else(|| format!("extends: unknown theme '{}'", parent))?
+        };
+
+        let mut merged = match toml::Value::try_from(&parent_config)? {
+            toml::Value::Table(table) => table,
+            _ => unreachable!("Config always serializes to a table"),
+        };
+        Self::overlay_theme(&mut merged, raw);
+
+        Ok(toml::Value::Table(merged).try_into()?)
+    }
+
+    /// Apply a child theme's overrides on top of its parent
+    ///
+    /// `[style]` keys replace the parent's one by one. Segments are matched by
+    /// id: each key of a child segment replaces the parent's, and its `icon`,
+    /// `colors`, `styles` and `options` tables are merged a single level deep so
+    /// a color slot is always swapped whole. Segments the parent lacks are appended.
+    fn overlay_theme(base: &mut toml::Table, overrides: toml::Table) {
+        for (key, value) in overrides {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Array(base_segments)), toml::Value::Array(segments))
+                    if key == "segments" =>
+                {
+                    Self::overlay_segments(base_segments, segments);
+                }
+                (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+                    Self::overlay_table(base_table, table, 0);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
+    }
+
+    fn overlay_segments(base: &mut Vec<toml::Value>, overrides: Vec<toml::Value>) {
+        for segment in overrides {
+            let id = segment.get("id").cloned();
+            let existing = base
+                .iter_mut()
+                .find(|base_segment| id.is_some() && base_segment.get("id") == id.as_ref());
+            match (existing, segment) {
+                (Some(toml::Value::Table(base_segment)), toml::Value::Table(segment)) => {
+                    Self::overlay_table(base_segment, segment, 1);
+                }
+                (_, segment) => base.push(segment),
+            }
+        }
+    }
+
+    /// Replace keys of `base` with those of `overrides`, merging nested tables `depth` levels down
+    fn overlay_table(base: &mut toml::Table, overrides: toml::Table, depth: usize) {
+        for (key, value) in overrides {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) if depth > 0 => {
+                    Self::overlay_table(base_table, table, depth - 1);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Validate a theme file before installing it, returning every problem found
+    ///
+    /// Unknown segment ids, invalid colors and missing required fields are
+    /// reported individually; a file that parses is also run through `check()`.
+    pub fn validate_theme_file(theme_path: &std::path::Path) -> Vec<String> {
+        let content = match std::fs::read_to_string(theme_path) {
+            Ok(content) => content,
+            Err(e) => return vec![format!("Cannot read {}: {}", theme_path.display(), e)],
+        };
+        let raw: toml::Value = match toml::from_str(&content) {
+            Ok(raw) => raw,
+            Err(e) => return vec![format!("Invalid TOML: {}", e)],
+        };
+
+        let mut problems = Vec::new();
+        // Fields a child theme leaves out come from the theme it extends
+        let extends = raw.get("extends").is_some();
+
+        match raw.get("style") {
+            Some(style) => {
+                for field in ["mode", "separator"] {
+                    if style.get(field).is_none() && !extends {
+                        problems.push(format!("style: missing required field '{}'", field));
+                    }
+                }
+                if let Some(mode) = style.get("mode") {
+                    if mode.clone().try_into::<StyleMode>().is_err() {
+                        problems.push(format!(
+                            "style.mode: invalid value {} (expected plain, nerd_font, powerline or auto)",
+                            mode
+                        ));
+                    }
+                }
+            }
+            None if !extends => problems.push("missing required [style] table".to_string()),
+            None => {}
+        }
+
+        let segments = match raw.get("segments").and_then(|v| v.as_array()) {
+            Some(segments) => segments.as_slice(),
+            None => {
+                if !extends {
+                    problems.push("missing required [[segments]] entries".to_string());
+                }
+                &[]
+            }
+        };
+
+        for (index, segment) in segments.iter().enumerate() {
+            let location = format!("segments[{}]", index);
+
+            match segment.get("id").and_then(|v| v.as_str()) {
+                Some(id) if SegmentId::from_name(id).is_none() => {
+                    let valid: Vec<String> = SegmentId::all().iter().map(|s| s.name()).collect();
+                    problems.push(format!(
+                        "{}: unknown segment id '{}' (valid: {})",
+                        location,
+                        id,
+                        valid.join(", ")
+                    ));
+                }
+                Some(_) => {}
+                None => problems.push(format!("{}: missing required field 'id'", location)),
+            }
+
+            for field in ["enabled", "icon", "colors", "styles", "options"] {
+                if segment.get(field).is_none() && !extends {
+                    problems.push(format!("{}: missing required field '{}'", location, field));
+                }
+            }
+
+            if let Some(colors) = segment.get("colors").and_then(|v| v.as_table()) {
+                for (slot, color) in colors {
+                    let valid = match color.clone().try_into::<AnsiColor>() {
+                        Ok(AnsiColor::Color16 { c16 }) => c16 < 16,
+                        Ok(_) => true,
+                        Err(_) => false,
+                    };
+                    if !valid {
+                        problems.push(format!(
+                            "{}.colors.{}: invalid color {} (expected {{ c16 = 0-15 }}, {{ c256 = 0-255 }}, {{ r, g, b }} or \"#rrggbb\")",
+                            location, slot, color
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Structural problems would only repeat as a less precise parse error
+        if problems.is_empty() {
+            let theme_name = theme_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match Self::load_theme_from_path(theme_path, &theme_name) {
+                Ok(config) => {
+                    if let Err(e) = config.check() {
+                        problems.push(e.to_string());
+                    }
+                }
+                Err(e) => problems.push(e.to_string()),
+            }
+        }
+
+        problems
+    }
+
     fn builtin_theme(theme_name: &str) -> Option<Config> {
         match theme_name {
             "cometix" => Some(Self::get_cometix()),
@@ -113,18 +630,12 @@ impl ThemePresets {
         config
     }
 
-    /// Get the themes directory path (~/.claude/ccline/themes/)
-    fn get_themes_path() -> std::path::PathBuf {
-        if let Some(home) = dirs::home_dir() {
-            home.join(".claude").join("ccline").join("themes")
-        } else {
-            std::path::PathBuf::from(".claude/ccline/themes")
-        }
-    }
-
-    /// Save current config as a new theme
-    pub fn save_theme(theme_name: &str, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-        let themes_dir = Self::get_themes_path();
+    /// Save current config as a new theme, returning the written file
+    pub fn save_theme(
+        theme_name: &str,
+        config: &Config,
+    ) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        let themes_dir = ConfigLoader::get_themes_path()?;
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
         // Create themes directory if it doesn't exist
@@ -135,9 +646,9 @@ impl ThemePresets {
         theme_config.theme = theme_name.to_string();
 
         let content = toml::to_string_pretty(&theme_config)?;
-        std::fs::write(&theme_path, content)?;
+        crate::utils::atomic_write(&theme_path, content)?;
 
-        Ok(())
+        Ok(theme_path)
     }
 
     /// List all available themes (built-in + custom)
@@ -155,7 +666,8 @@ impl ThemePresets {
         ];
 
         // Add custom themes from file system
-        if let Ok(themes_dir) = std::fs::read_dir(Self::get_themes_path()) {
+        let themes_dir = ConfigLoader::get_themes_path().ok();
+        if let Some(themes_dir) = themes_dir.and_then(|dir| std::fs::read_dir(dir).ok()) {
             for entry in themes_dir.flatten() {
                 if let Some(name) = entry.file_name().to_str() {
                     if name.ends_with(".toml") {
@@ -189,7 +701,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: " | ".to_string(),
+                separator: " | ".into(),
+                powerline: false,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_cometix::model_segment(),
@@ -201,6 +716,16 @@ impl ThemePresets {
                 theme_cometix::session_segment(),
                 theme_cometix::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "cometix".to_string(),
         }
@@ -210,7 +735,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::Plain,
-                separator: " | ".to_string(),
+                separator: " | ".into(),
+                powerline: false,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_default::model_segment(),
@@ -222,6 +750,16 @@ impl ThemePresets {
                 theme_default::session_segment(),
                 theme_default::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "default".to_string(),
         }
@@ -231,7 +769,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::Plain,
-                separator: " │ ".to_string(),
+                separator: " │ ".into(),
+                powerline: false,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_minimal::model_segment(),
@@ -243,6 +784,16 @@ impl ThemePresets {
                 theme_minimal::session_segment(),
                 theme_minimal::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "minimal".to_string(),
         }
@@ -252,7 +803,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: " | ".to_string(),
+                separator: " | ".into(),
+                powerline: false,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_gruvbox::model_segment(),
@@ -264,6 +818,16 @@ impl ThemePresets {
                 theme_gruvbox::session_segment(),
                 theme_gruvbox::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "gruvbox".to_string(),
         }
@@ -273,7 +837,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: "".to_string(),
+                separator: "".into(),
+                powerline: true,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_nord::model_segment(),
@@ -285,6 +852,16 @@ impl ThemePresets {
                 theme_nord::session_segment(),
                 theme_nord::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "nord".to_string(),
         }
@@ -294,7 +871,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: "".to_string(),
+                separator: "".into(),
+                powerline: true,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_powerline_dark::model_segment(),
@@ -306,6 +886,16 @@ impl ThemePresets {
                 theme_powerline_dark::session_segment(),
                 theme_powerline_dark::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "powerline-dark".to_string(),
         }
@@ -315,7 +905,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: "".to_string(),
+                separator: "".into(),
+                powerline: true,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_powerline_light::model_segment(),
@@ -327,6 +920,16 @@ impl ThemePresets {
                 theme_powerline_light::session_segment(),
                 theme_powerline_light::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "powerline-light".to_string(),
         }
@@ -336,7 +939,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: "".to_string(),
+                separator: "".into(),
+                powerline: true,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_powerline_rose_pine::model_segment(),
@@ -348,6 +954,16 @@ impl ThemePresets {
                 theme_powerline_rose_pine::session_segment(),
                 theme_powerline_rose_pine::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "powerline-rose-pine".to_string(),
         }
@@ -357,7 +973,10 @@ impl ThemePresets {
         Config {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
-                separator: "".to_string(),
+                separator: "".into(),
+                powerline: true,
+                max_width: None,
+                color_depth: ColorDepth::Truecolor,
             },
             segments: vec![
                 theme_powerline_tokyo_night::model_segment(),
@@ -369,6 +988,16 @@ impl ThemePresets {
                 theme_powerline_tokyo_night::session_segment(),
                 theme_powerline_tokyo_night::output_style_segment(),
                 Self::default_cli_proxy_api_quota_segment(),
+                Self::default_rate_limit_segment(),
+                Self::default_model_context_segment(),
+                Self::default_clock_segment(),
+                Self::default_battery_segment(),
+                Self::default_kube_segment(),
+                Self::default_venv_segment(),
+                Self::default_command_segment(),
+                Self::default_cloud_profile_segment(),
+                Self::default_host_segment(),
+                Self::default_exit_code_segment(),
             ],
             theme: "powerline-tokyo-night".to_string(),
         }
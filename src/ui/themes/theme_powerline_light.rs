@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    AnsiColor, ColorConfig, IconConfig, SegmentAlign, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -7,6 +7,8 @@ pub fn model_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Model,
         enabled: true,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "🤖".to_string(),
             nerd_font: "\u{e26d}".to_string(),
@@ -29,6 +31,8 @@ pub fn directory_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Directory,
         enabled: true,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "📁".to_string(),
             nerd_font: "\u{f024b}".to_string(),
@@ -59,6 +63,8 @@ pub fn git_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Git,
         enabled: true,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "🌿".to_string(),
             nerd_font: "\u{f02a2}".to_string(),
@@ -93,6 +99,8 @@ pub fn context_window_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::ContextWindow,
         enabled: true,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "⚡️".to_string(),
             nerd_font: "\u{f49b}".to_string(),
@@ -123,6 +131,8 @@ pub fn cost_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Cost,
         enabled: false,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "💰".to_string(),
             nerd_font: "\u{eec1}".to_string(),
@@ -153,6 +163,8 @@ pub fn session_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Session,
         enabled: false,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "⏱️".to_string(),
             nerd_font: "\u{f19bb}".to_string(),
@@ -183,6 +195,8 @@ pub fn output_style_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::OutputStyle,
         enabled: false,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "🎯".to_string(),
             nerd_font: "\u{f12f5}".to_string(),
@@ -213,6 +227,8 @@ pub fn usage_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Usage,
         enabled: false,
+        align: SegmentAlign::Left,
+        line: 0,
         icon: IconConfig {
             plain: "📊".to_string(),
             nerd_font: "\u{f0a9e}".to_string(),
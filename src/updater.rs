@@ -77,14 +77,10 @@ impl UpdateState {
     pub fn load() -> Self {
         #[cfg(feature = "self-update")]
         {
-            let config_dir = dirs::home_dir()
-                .unwrap_or_default()
-                .join(".claude")
-                .join("ccline");
-
-            let state_file = config_dir.join(".update_state.json");
-
-            let mut state = if let Ok(content) = std::fs::read_to_string(&state_file) {
+            let content = crate::config::config_dir()
+                .ok()
+                .and_then(|dir| std::fs::read_to_string(dir.join(".update_state.json")).ok());
+            let mut state = if let Some(content) = content {
                 if let Ok(state) = serde_json::from_str::<UpdateState>(&content) {
                     state
                 } else {
@@ -192,16 +188,14 @@ impl UpdateState {
     pub fn save(&self) -> Result<(), std::io::Error> {
         #[cfg(feature = "self-update")]
         {
-            let config_dir = dirs::home_dir()
-                .unwrap_or_default()
-                .join(".claude")
-                .join("ccline");
+            let config_dir = crate::config::config_dir()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e))?;
 
             std::fs::create_dir_all(&config_dir)?;
             let state_file = config_dir.join(".update_state.json");
 
             let content = serde_json::to_string_pretty(self)?;
-            std::fs::write(&state_file, content)?;
+            crate::utils::atomic_write(&state_file, content)?;
         }
 
         Ok(())
@@ -275,7 +269,13 @@ pub mod github {
             let platform_suffix = get_platform_asset_name();
             self.assets
                 .iter()
-                .find(|asset| asset.name.contains(&platform_suffix))
+                .find(|asset| asset.name.ends_with(&platform_suffix))
+        }
+
+        /// The `<asset>.sha256` file published alongside `asset`
+        pub fn find_checksum_for(&self, asset: &ReleaseAsset) -> Option<&ReleaseAsset> {
+            let checksum_name = format!("{}.sha256", asset.name);
+            self.assets.iter().find(|a| a.name == checksum_name)
         }
     }
 
@@ -348,15 +348,27 @@ pub mod github {
         None
     }
 
+    /// Repository whose releases are checked by default
+    pub const DEFAULT_REPO: &str = "Haleclipse/CCometixLine";
+
     /// Check for updates from GitHub Releases API
     pub fn check_for_updates() -> Result<Option<GitHubRelease>, Box<dyn std::error::Error>> {
-        let url = "https://api.github.com/repos/Haleclipse/CCometixLine/releases/latest";
+        check_for_updates_in(DEFAULT_REPO, std::time::Duration::from_secs(30))
+    }
+
+    /// Fetch the latest release of `repo` (`owner/name`) and return it if newer than this build
+    pub fn check_for_updates_in(
+        repo: &str,
+        timeout: std::time::Duration,
+    ) -> Result<Option<GitHubRelease>, Box<dyn std::error::Error>> {
+        let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
 
-        let response = ureq::get(url)
+        let response = ureq::get(&url)
             .set(
                 "User-Agent",
                 &format!("CCometixLine/{}", env!("CARGO_PKG_VERSION")),
             )
+            .timeout(timeout)
             .call()?;
 
         if response.status() == 200 {
@@ -379,3 +391,450 @@ pub mod github {
         }
     }
 }
+
+/// Self-update: download, verify and swap in the latest release binary
+#[cfg(feature = "self-update")]
+pub mod install {
+    use super::github::{check_for_updates_in, GitHubRelease, ReleaseAsset};
+    use super::{UpdateState, UpdateStatus};
+    use std::io::{Read, Write};
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    type UpdateResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+    /// Name of the executable inside release archives
+    #[cfg(windows)]
+    const BINARY_NAME: &str = "ccline.exe";
+    #[cfg(not(windows))]
+    const BINARY_NAME: &str = "ccline";
+
+    /// Check `repo` for a newer release and install it over the running executable
+    ///
+    /// With `dry_run` only the planned change is reported and nothing is downloaded.
+    pub fn run_update(repo: &str, dry_run: bool) -> UpdateResult<()> {
+        let current_version = env!("CARGO_PKG_VERSION");
+        println!("🔍 Checking {} for updates...", repo);
+
+        let release = match check_for_updates_in(repo, Duration::from_secs(30))? {
+            Some(release) => release,
+            None => {
+                println!("✅ Already up to date (v{})", current_version);
+                return Ok(());
+            }
+        };
+
+        let asset = release
+            .find_asset_for_platform()
+            .ok_or("No release asset found for this platform")?;
+        // Without a published checksum there is nothing to check the download against
+        let checksum = release.find_checksum_for(asset).ok_or_else(|| {
+            format!(
+                "Release publishes no {}.sha256 checksum, refusing to install",
+                asset.name
+            )
+        })?;
+        let exe_path = std::env::current_exe()?.canonicalize()?;
+
+        println!(
+            "📦 Update available: v{} → v{}",
+            current_version,
+            release.version()
+        );
+        println!("   Asset: {} ({} bytes)", asset.name, asset.size);
+        println!("   Checksum: {}", checksum.name);
+        println!("   Target: {}", exe_path.display());
+
+        if dry_run {
+            println!("Dry run, nothing was changed");
+            return Ok(());
+        }
+
+        let work_dir = create_work_dir(&std::env::temp_dir())?;
+
+        let result = install_release(&release, asset, checksum, &exe_path, &work_dir);
+        let _ = std::fs::remove_dir_all(&work_dir);
+
+        let mut state = UpdateState::load();
+        match &result {
+            Ok(()) => {
+                state.status = UpdateStatus::Completed {
+                    version: release.version(),
+                    completed_at: chrono::Utc::now(),
+                };
+                state.current_version = release.version();
+                println!("✅ Updated to v{}", release.version());
+            }
+            Err(e) => {
+                state.status = UpdateStatus::Failed {
+                    error: e.to_string(),
+                };
+            }
+        }
+        let _ = state.save();
+
+        result
+    }
+
+    /// Fresh private directory under `base` for the download
+    ///
+    /// The name is never reused: an existing directory, possibly planted by another
+    /// user of a shared temp dir, makes creation fail and the next name is tried.
+    fn create_work_dir(base: &Path) -> std::io::Result<PathBuf> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+
+        for attempt in 0..16 {
+            let dir = base.join(format!(
+                "ccline-update-{}-{}-{}",
+                std::process::id(),
+                nanos,
+                attempt
+            ));
+            match builder.create(&dir) {
+                Ok(()) => return Ok(dir),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("No unused update directory name in {}", base.display()),
+        ))
+    }
+
+    fn install_release(
+        release: &GitHubRelease,
+        asset: &ReleaseAsset,
+        checksum: &ReleaseAsset,
+        exe_path: &Path,
+        work_dir: &Path,
+    ) -> UpdateResult<()> {
+        let archive_path = work_dir.join(&asset.name);
+        println!("⬇️  Downloading {}...", asset.name);
+        download_asset(asset, &archive_path)?;
+
+        // Nothing from the archive runs before it matches the published hash
+        println!("🔒 Checking SHA-256...");
+        let checksum_path = work_dir.join(&checksum.name);
+        download_asset(checksum, &checksum_path)?;
+        let expected = parse_checksum(&std::fs::read_to_string(&checksum_path)?, &asset.name)?;
+        verify_checksum(&archive_path, &expected)?;
+
+        println!("📂 Extracting...");
+        let new_binary = extract_binary(&archive_path, work_dir)?;
+
+        println!("🔎 Verifying...");
+        verify_binary(&new_binary, &release.version())?;
+
+        println!("🔁 Replacing {}...", exe_path.display());
+        replace_executable(&new_binary, exe_path)
+    }
+
+    /// Stream the asset to disk, reporting progress and checking the advertised size
+    fn download_asset(asset: &ReleaseAsset, dest: &Path) -> UpdateResult<()> {
+        let response = ureq::get(&asset.browser_download_url)
+            .set(
+                "User-Agent",
+                &format!("CCometixLine/{}", env!("CARGO_PKG_VERSION")),
+            )
+            .timeout(Duration::from_secs(300))
+            .call()
+            .map_err(Box::new)?;
+
+        let mut reader = response.into_reader();
+        let mut file = std::fs::File::create(dest)?;
+        let mut buffer = [0u8; 64 * 1024];
+        let mut downloaded: u64 = 0;
+        let mut last_reported = 0;
+
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read])?;
+            downloaded += read as u64;
+
+            if let Some(progress) = (downloaded * 100).checked_div(asset.size) {
+                let progress = progress.min(100) as u8;
+                if progress >= last_reported + 10 {
+                    last_reported = progress - progress % 10;
+                    println!("   {}%", last_reported);
+                }
+            }
+        }
+        file.flush()?;
+
+        if downloaded != asset.size {
+            return Err(format!(
+                "Downloaded {} bytes but the release lists {} bytes",
+                downloaded, asset.size
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Expected hash for `file_name` from a `sha256sum` style checksum file
+    ///
+    /// Lines are `<hex>  <name>` (`*<name>` in binary mode); a lone hash is taken as is.
+    fn parse_checksum(contents: &str, file_name: &str) -> UpdateResult<String> {
+        let hash = contents.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            match parts.next() {
+                Some(name) if name.trim_start_matches('*') != file_name => None,
+                _ => Some(hash),
+            }
+        });
+
+        match hash {
+            Some(hash) if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(hash.to_ascii_lowercase())
+            }
+            Some(hash) => Err(format!("Invalid SHA-256 '{}' for {}", hash, file_name).into()),
+            None => Err(format!("No SHA-256 for {} in checksum file", file_name).into()),
+        }
+    }
+
+    /// Lowercase hex SHA-256 of the file at `path`
+    fn sha256_file(path: &Path) -> std::io::Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            context.update(&buffer[..read]);
+        }
+
+        Ok(context
+            .finish()
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    fn verify_checksum(path: &Path, expected: &str) -> UpdateResult<()> {
+        let actual = sha256_file(path)?;
+        if actual != expected {
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                path.display(),
+                expected,
+                actual
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Unpack the archive with the platform's tools and locate the binary
+    fn extract_binary(archive: &Path, work_dir: &Path) -> UpdateResult<PathBuf> {
+        let extract_dir = work_dir.join("extracted");
+        std::fs::create_dir(&extract_dir)?;
+
+        let status = if archive.extension().is_some_and(|ext| ext == "zip") {
+            Command::new("powershell")
+                .arg("-NoProfile")
+                .arg("-Command")
+                .arg(format!(
+                    "Expand-Archive -LiteralPath '{}' -DestinationPath '{}' -Force",
+                    archive.display(),
+                    extract_dir.display()
+                ))
+                .status()?
+        } else {
+            Command::new("tar")
+                .arg("-xzf")
+                .arg(archive)
+                .arg("-C")
+                .arg(&extract_dir)
+                .status()?
+        };
+
+        if !status.success() {
+            return Err(format!("Failed to extract {}", archive.display()).into());
+        }
+
+        let binary = extract_dir.join(BINARY_NAME);
+        if !binary.is_file() {
+            return Err(format!("{} not found in release archive", BINARY_NAME).into());
+        }
+
+        Ok(binary)
+    }
+
+    /// Make sure the new binary runs on this system and reports the expected version
+    fn verify_binary(binary: &Path, version: &str) -> UpdateResult<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(binary, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        let output = Command::new(binary).arg("--version").output()?;
+        let reported = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || !reported.contains(version) {
+            return Err(format!(
+                "New binary failed verification (reported '{}')",
+                reported.trim()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Swap the executable via renames in its own directory, restoring the backup on failure
+    fn replace_executable(new_binary: &Path, exe_path: &Path) -> UpdateResult<()> {
+        let staged = exe_path.with_extension("new");
+        let backup = exe_path.with_extension("old");
+
+        // Stage next to the target so the final rename stays on one filesystem
+        std::fs::copy(new_binary, &staged)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        let _ = std::fs::remove_file(&backup);
+        if let Err(e) = std::fs::rename(exe_path, &backup) {
+            let _ = std::fs::remove_file(&staged);
+            return Err(format!("Failed to back up current executable: {}", e).into());
+        }
+
+        if let Err(e) = std::fs::rename(&staged, exe_path) {
+            println!("↩️  Rolling back...");
+            let _ = std::fs::remove_file(&staged);
+            std::fs::rename(&backup, exe_path)?;
+            return Err(format!("Failed to install new executable: {}", e).into());
+        }
+
+        // Windows keeps the running image locked, the backup is cleaned up next time
+        let _ = std::fs::remove_file(&backup);
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// SHA-256 of `abc`
+        const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        fn asset(name: &str) -> ReleaseAsset {
+            ReleaseAsset {
+                name: name.to_string(),
+                size: 0,
+                download_count: 0,
+                browser_download_url: String::new(),
+                content_type: String::new(),
+            }
+        }
+
+        #[test]
+        fn checksum_is_read_from_sha256sum_output() {
+            let name = "ccline-linux-x64.tar.gz";
+            let text = format!("{}  {}\n", ABC_SHA256.to_uppercase(), name);
+            assert_eq!(parse_checksum(&text, name).unwrap(), ABC_SHA256);
+
+            let binary_mode = format!("{} *{}\n", ABC_SHA256, name);
+            assert_eq!(parse_checksum(&binary_mode, name).unwrap(), ABC_SHA256);
+
+            assert_eq!(parse_checksum(ABC_SHA256, name).unwrap(), ABC_SHA256);
+        }
+
+        #[test]
+        fn checksum_for_another_file_or_malformed_is_rejected() {
+            let name = "ccline-linux-x64.tar.gz";
+            let other = format!("{}  ccline-macos-x64.tar.gz\n", ABC_SHA256);
+            assert!(parse_checksum(&other, name).is_err());
+            assert!(parse_checksum("", name).is_err());
+            assert!(parse_checksum("abc123  ccline-linux-x64.tar.gz", name).is_err());
+            assert!(parse_checksum(&format!("{}  {}", "z".repeat(64), name), name).is_err());
+        }
+
+        #[test]
+        fn archive_must_match_the_published_hash() {
+            let dir = crate::test_support::temp_dir("updater-checksum");
+            let archive = dir.join("ccline-linux-x64.tar.gz");
+            std::fs::write(&archive, "abc").unwrap();
+
+            assert_eq!(sha256_file(&archive).unwrap(), ABC_SHA256);
+            assert!(verify_checksum(&archive, ABC_SHA256).is_ok());
+
+            std::fs::write(&archive, "abd").unwrap();
+            let error = verify_checksum(&archive, ABC_SHA256).unwrap_err();
+            assert!(error.to_string().contains("Checksum mismatch"), "{}", error);
+        }
+
+        #[test]
+        fn work_dirs_are_fresh_and_private() {
+            let base = crate::test_support::temp_dir("updater-work-dir");
+            let first = create_work_dir(&base).unwrap();
+            let second = create_work_dir(&base).unwrap();
+
+            assert_ne!(first, second);
+            assert!(first.starts_with(&base) && second.starts_with(&base));
+            assert_eq!(std::fs::read_dir(&first).unwrap().count(), 0);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o700);
+            }
+        }
+
+        #[test]
+        fn checksum_files_are_never_taken_for_the_archive() {
+            let names = [
+                "ccline-windows-x64.zip",
+                "ccline-macos-x64.tar.gz",
+                "ccline-macos-arm64.tar.gz",
+                "ccline-linux-x64.tar.gz",
+                "ccline-linux-x64-static.tar.gz",
+            ];
+            // Checksums first, where a substring match on the suffix would pick them
+            let mut assets: Vec<_> = names
+                .iter()
+                .map(|name| asset(&format!("{}.sha256", name)))
+                .collect();
+            assets.extend(names.iter().map(|name| asset(name)));
+            let release = GitHubRelease {
+                tag_name: "v9.9.9".to_string(),
+                name: String::new(),
+                body: String::new(),
+                draft: false,
+                prerelease: false,
+                created_at: String::new(),
+                published_at: String::new(),
+                html_url: String::new(),
+                assets,
+            };
+
+            if let Some(archive) = release.find_asset_for_platform() {
+                assert!(!archive.name.ends_with(".sha256"), "{}", archive.name);
+                let checksum = release.find_checksum_for(archive).unwrap();
+                assert_eq!(checksum.name, format!("{}.sha256", archive.name));
+            }
+            assert!(release.find_checksum_for(&asset("ccline.deb")).is_none());
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use regex::Regex;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct LocationResult {
@@ -99,6 +99,75 @@ impl ClaudeCodePatcher {
         Ok(())
     }
 
+    /// Copy the file to `<file>.backup.<timestamp>`, keeping earlier backups intact
+    pub fn create_backup<P: AsRef<Path>>(file_path: P) -> std::io::Result<PathBuf> {
+        let path = file_path.as_ref();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+
+        let mut backup = Self::backup_path(path, &stamp);
+        let mut attempt = 1;
+        // Two runs within the same second must not overwrite each other
+        while backup.exists() {
+            backup = Self::backup_path(path, &format!("{}-{}", stamp, attempt));
+            attempt += 1;
+        }
+
+        fs::copy(path, &backup)?;
+        Ok(backup)
+    }
+
+    /// Backups of the file, newest first; includes a legacy `<file>.backup` last
+    pub fn list_backups<P: AsRef<Path>>(file_path: P) -> Vec<PathBuf> {
+        let path = file_path.as_ref();
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            return Vec::new();
+        };
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let prefix = format!("{}.backup.", file_name);
+
+        let mut backups: Vec<((String, u32), PathBuf)> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let stamp = name.strip_prefix(&prefix)?;
+                // `20250101-120000` or `20250101-120000-2` for same-second runs
+                let (time, attempt) = match stamp.get(15..).and_then(|s| s.strip_prefix('-')) {
+                    Some(attempt) => (&stamp[..15], attempt.parse().unwrap_or(0)),
+                    None => (stamp, 0),
+                };
+                Some(((time.to_string(), attempt), entry.path()))
+            })
+            .collect();
+        backups.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let mut result: Vec<PathBuf> = backups.into_iter().map(|(_, p)| p).collect();
+        let legacy = dir.join(format!("{}.backup", file_name));
+        if legacy.is_file() {
+            result.push(legacy);
+        }
+        result
+    }
+
+    /// Copy a backup over the original file
+    pub fn restore_backup<P: AsRef<Path>, B: AsRef<Path>>(
+        file_path: P,
+        backup_path: B,
+    ) -> std::io::Result<()> {
+        fs::copy(backup_path, file_path)?;
+        Ok(())
+    }
+
+    fn backup_path(path: &Path, stamp: &str) -> PathBuf {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".backup.{}", stamp));
+        PathBuf::from(name)
+    }
+
     /// Save the modified content back to file
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
         fs::write(&self.file_path, &self.file_content)?;
@@ -0,0 +1,170 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes temp files of threads writing the same path concurrently
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `contents` without ever leaving it half written
+///
+/// The data goes to a temp file next to `path` that is synced and then renamed over
+/// it, so a reader or a killed process sees either the old file or the new one. An
+/// existing file keeps its permissions, e.g. a config made private for its keys.
+pub fn atomic_write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let temp_path = temp_path(path)?;
+
+    let result = write_temp(path, &temp_path, contents.as_ref())
+        .and_then(|()| std::fs::rename(&temp_path, path));
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Unique `.<name>.<pid>.<n>.tmp` sibling of `path`
+fn temp_path(path: &Path) -> std::io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("not a file path: {}", path.display()),
+        )
+    })?;
+
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    Ok(path.with_file_name(temp_name))
+}
+
+/// Everything [`atomic_write`] does before the rename that publishes the file
+fn write_temp(path: &Path, temp_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        std::fs::set_permissions(temp_path, metadata.permissions())?;
+    }
+    Ok(())
+}
+
+/// Advisory lock on `<path>.lock`, released when dropped
+///
+/// The lock lives in a sidecar file because [`atomic_write`] swaps out the file
+/// itself, and a lock on the replaced file would go unseen by the next process.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Wait until no other process holds the lock for `path`
+    pub fn exclusive(path: &Path) -> std::io::Result<Self> {
+        let file = open_lock_file(path)?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+
+    /// Take the lock for `path` unless another process holds it, then `Ok(None)`
+    pub fn try_exclusive(path: &Path) -> std::io::Result<Option<Self>> {
+        let file = open_lock_file(path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+            Err(std::fs::TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
+
+fn open_lock_file(path: &Path) -> std::io::Result<File> {
+    let mut lock_path = PathBuf::from(path).into_os_string();
+    lock_path.push(".lock");
+    std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_serializes_concurrent_writers() {
+        let dir = crate::test_support::temp_dir("fs-lock-stress");
+        let counter = dir.join("counter");
+        atomic_write(&counter, "0").unwrap();
+
+        // Unlocked, the read-modify-write cycles would lose increments
+        let threads: Vec<_> = (0..16)
+            .map(|_| {
+                let counter = counter.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        let _lock = FileLock::exclusive(&counter).unwrap();
+                        let n: u32 = std::fs::read_to_string(&counter).unwrap().parse().unwrap();
+                        atomic_write(&counter, (n + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(std::fs::read_to_string(&counter).unwrap(), "400");
+    }
+
+    #[test]
+    fn interrupted_write_leaves_the_original_intact() {
+        let dir = crate::test_support::temp_dir("fs-interrupted-write");
+        let path = dir.join("config.toml");
+        atomic_write(&path, "theme = \"default\"\n").unwrap();
+
+        // A crash after writing part of the new data, before the rename
+        let temp_path = temp_path(&path).unwrap();
+        write_temp(&path, &temp_path, b"theme = \"pow").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "theme = \"default\"\n"
+        );
+
+        // The leftover temp file doesn't get in the way of the next write
+        atomic_write(&path, "theme = \"nord\"\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "theme = \"nord\"\n"
+        );
+    }
+
+    #[test]
+    fn failed_write_removes_its_temp_file() {
+        let dir = crate::test_support::temp_dir("fs-failed-write");
+        // Renaming a file over a non-empty directory fails
+        let path = dir.join("occupied");
+        std::fs::create_dir_all(path.join("child")).unwrap();
+
+        assert!(atomic_write(&path, "data").is_err());
+        assert!(path.join("child").is_dir());
+        let leftovers: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(leftovers.len(), 1);
+    }
+
+    #[test]
+    fn try_exclusive_reports_a_held_lock() {
+        let dir = crate::test_support::temp_dir("fs-try-lock");
+        let path = dir.join("cache.json");
+
+        let held = FileLock::try_exclusive(&path).unwrap();
+        assert!(held.is_some());
+        assert!(FileLock::try_exclusive(&path).unwrap().is_none());
+        drop(held);
+        assert!(FileLock::try_exclusive(&path).unwrap().is_some());
+    }
+}
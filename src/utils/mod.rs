@@ -1,4 +1,10 @@
 pub mod claude_code_patcher;
 pub mod credentials;
+pub mod fs;
+pub mod sanitize;
+pub mod width;
 
 pub use claude_code_patcher::{ClaudeCodePatcher, LocationResult};
+pub use fs::{atomic_write, FileLock};
+pub use sanitize::{sanitize_output, strip_ansi, SanitizeOptions};
+pub use width::{display_width, truncate_to_width};
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+/// Options controlling how external command output is squeezed into one statusline cell
+///
+/// Length is not limited here: the generic `max_length` segment option cuts the
+/// result like any other segment's text.
+#[derive(Debug, Clone)]
+pub struct SanitizeOptions {
+    /// Inserted between the non-empty lines of multi-line output
+    pub joiner: String,
+    /// Keep ANSI escape sequences emitted by the command
+    pub allow_ansi: bool,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            joiner: " ".to_string(),
+            allow_ansi: false,
+        }
+    }
+}
+
+impl SanitizeOptions {
+    /// Read `joiner` and `allow_ansi` from segment options
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            joiner: options
+                .get("joiner")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or(defaults.joiner),
+            allow_ansi: options
+                .get("allow_ansi")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.allow_ansi),
+        }
+    }
+}
+
+/// Make arbitrary script output safe to embed in a single-line statusline
+///
+/// Normalizes CRLF, trims every line, joins non-empty lines with the joiner and
+/// drops control characters (and ANSI sequences unless allowed).
+pub fn sanitize_output(raw: &str, options: &SanitizeOptions) -> String {
+    let text = if options.allow_ansi {
+        raw.to_string()
+    } else {
+        strip_ansi(raw)
+    };
+
+    let joined = text
+        .replace("\r\n", "\n")
+        .split(['\n', '\r'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(&options.joiner);
+
+    // Remaining control characters (tabs, bells, ...) would corrupt the line, but
+    // kept escapes are copied whole so an OSC's BEL terminator survives
+    let mut cleaned = String::with_capacity(joined.len());
+    let mut rest = joined.as_str();
+    while let Some(ch) = rest.chars().next() {
+        if let Some(len) = escape_len(rest) {
+            cleaned.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        match ch {
+            '\t' => cleaned.push(' '),
+            c if c.is_control() => {}
+            c => cleaned.push(c),
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+    cleaned
+}
+
+/// Byte length of the escape sequence `text` starts with, `None` if it doesn't start with ESC
+///
+/// CSI sequences run through their final byte (`@`..`~`), OSC sequences such as
+/// hyperlinks through BEL or `ESC \`, and any other escape is two characters. An
+/// unterminated sequence takes the rest of the text.
+pub fn escape_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    if chars.next()?.1 != '\x1b' {
+        return None;
+    }
+
+    let end = match chars.next() {
+        // CSI: ESC [ ... final byte in @..~
+        Some((_, '[')) => chars
+            .find(|(_, c)| ('@'..='~').contains(c))
+            .map(|(i, c)| i + c.len_utf8()),
+        // OSC: ESC ] ... terminated by BEL or ESC \
+        Some((_, ']')) => {
+            let mut end = None;
+            for (i, c) in chars.by_ref() {
+                if c == '\x07' {
+                    end = Some(i + 1);
+                    break;
+                }
+                if c == '\x1b' && text[i + 1..].starts_with('\\') {
+                    end = Some(i + 2);
+                    break;
+                }
+            }
+            end
+        }
+        // Two-character escape
+        Some((i, c)) => Some(i + c.len_utf8()),
+        None => None,
+    };
+
+    Some(end.unwrap_or(text.len()))
+}
+
+/// Remove ANSI escape sequences (CSI and OSC) from text
+pub fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        match escape_len(rest) {
+            Some(len) => rest = &rest[len..],
+            None => {
+                result.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{display_width, truncate_to_width};
+
+    const LINK: &str = "\x1b]8;;https://example.com/a\x07PR #42\x1b]8;;\x07";
+
+    fn sanitize(raw: &str) -> String {
+        sanitize_output(raw, &SanitizeOptions::default())
+    }
+
+    #[test]
+    fn multiline_output_is_joined_on_one_line() {
+        assert_eq!(sanitize("  main  \n\n  3 ahead \n"), "main 3 ahead");
+
+        let options = SanitizeOptions {
+            joiner: " | ".to_string(),
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(sanitize_output("a\nb\nc", &options), "a | b | c");
+    }
+
+    #[test]
+    fn crlf_and_bare_cr_are_line_breaks() {
+        assert_eq!(sanitize("one\r\ntwo\r\n"), "one two");
+        assert_eq!(sanitize("50%\r100%"), "50% 100%");
+    }
+
+    #[test]
+    fn control_characters_are_dropped_and_tabs_become_spaces() {
+        assert_eq!(sanitize("a\tb\x07c\x00d"), "a bcd");
+    }
+
+    #[test]
+    fn ansi_is_stripped_unless_allowed() {
+        let colored = "\x1b[1;31mred\x1b[0m and \x1b7plain";
+        assert_eq!(sanitize(colored), "red and plain");
+        assert_eq!(sanitize(LINK), "PR #42");
+
+        let options = SanitizeOptions {
+            allow_ansi: true,
+            ..SanitizeOptions::default()
+        };
+        assert_eq!(sanitize_output(colored, &options), colored);
+        // The BELs end the hyperlink and must not go with the other control characters
+        assert_eq!(sanitize_output(LINK, &options), LINK);
+    }
+
+    #[test]
+    fn escape_len_covers_whole_sequences() {
+        assert_eq!(escape_len("\x1b[38;5;208mx"), Some(11));
+        assert_eq!(escape_len("\x1b]8;;https://a.b\x07x"), Some(17));
+        assert_eq!(escape_len("\x1b]0;title\x1b\\x"), Some(11));
+        assert_eq!(escape_len("\x1b7x"), Some(2));
+        assert_eq!(escape_len("\x1b[31"), Some(4));
+        assert_eq!(escape_len("x\x1b[0m"), None);
+    }
+
+    #[test]
+    fn hyperlinks_count_only_their_visible_text() {
+        assert_eq!(display_width(LINK), 6);
+
+        let cut = truncate_to_width(LINK, 4, "…");
+        assert_eq!(strip_ansi(&cut), "PR …");
+        assert!(cut.contains("https://example.com/a\x07"));
+        assert!(cut.ends_with("\x1b]8;;\x07…\x1b[0m"));
+    }
+
+    #[test]
+    fn truncation_counts_display_width() {
+        assert_eq!(truncate_to_width("日本語テキスト", 7, "…"), "日本語…");
+        assert_eq!(
+            truncate_to_width("\x1b[32m日本語\x1b[0m", 6, "…"),
+            "\x1b[32m日本語\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use super::sanitize::escape_len;
+use super::strip_ansi;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Terminal columns taken by `text`, ignoring ANSI escapes and counting wide glyphs as two
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(strip_ansi(text).as_str())
+}
+
+/// Cut `text` down to `max_width` columns including the trailing `ellipsis`
+///
+/// Escape sequences are copied whole and never counted, so colors are not
+/// split mid-sequence. A reset is appended when the cut dropped styled text.
+pub fn truncate_to_width(text: &str, max_width: usize, ellipsis: &str) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let ellipsis_width = display_width(ellipsis);
+    let budget = max_width.saturating_sub(ellipsis_width);
+
+    let mut result = String::new();
+    let mut width = 0;
+    let mut has_escape = false;
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        if let Some(len) = escape_len(rest) {
+            has_escape = true;
+            result.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        rest = &rest[ch.len_utf8()..];
+
+        let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if width + ch_width > budget {
+            // Keep consuming so trailing escapes (resets) still get copied
+            continue;
+        }
+        result.push(ch);
+        width += ch_width;
+    }
+
+    if ellipsis_width <= max_width {
+        result.push_str(ellipsis);
+    }
+    if has_escape {
+        result.push_str("\x1b[0m");
+    }
+    result
+}
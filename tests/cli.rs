@@ -0,0 +1,210 @@
+//! End-to-end runs of the built binary against a throwaway HOME
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const INPUT: &str = r#"{"model": {"id": "claude-sonnet-4", "display_name": "Sonnet 4"}, "workspace": {"current_dir": "/tmp"}, "transcript_path": "/nonexistent/transcript.jsonl"}"#;
+
+/// Empty HOME so no user config or cache is picked up
+fn home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ccline-cli-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(name: &str, args: &[&str], envs: &[(&str, &str)], stdin: &str) -> Output {
+    let home = home(name);
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccometixline"));
+    command
+        .args(args)
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env_remove("NO_COLOR")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn().expect("run ccometixline");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn colored_by_default() {
+    let output = run("colored", &["--theme", "powerline-dark"], &[], INPUT);
+    assert!(stdout(&output).contains("\x1b["));
+}
+
+#[test]
+fn no_color_flag_emits_no_escapes() {
+    for theme in ["default", "powerline-dark"] {
+        let output = run(
+            "no-color-flag",
+            &["--no-color", "--theme", theme],
+            &[],
+            INPUT,
+        );
+        let line = stdout(&output);
+        assert!(line.contains("Sonnet 4"), "{:?}", line);
+        assert!(!line.contains("\x1b["), "{} theme: {:?}", theme, line);
+    }
+}
+
+#[test]
+fn no_color_env_emits_no_escapes() {
+    for theme in ["default", "powerline-dark"] {
+        let output = run(
+            "no-color-env",
+            &["--theme", theme],
+            &[("NO_COLOR", "1")],
+            INPUT,
+        );
+        let line = stdout(&output);
+        assert!(line.contains("Sonnet 4"), "{:?}", line);
+        assert!(!line.contains("\x1b["), "{} theme: {:?}", theme, line);
+    }
+}
+
+#[test]
+fn empty_no_color_env_keeps_colors() {
+    let output = run("no-color-empty", &[], &[("NO_COLOR", "")], INPUT);
+    assert!(stdout(&output).contains("\x1b["));
+}
+
+#[test]
+fn print_applies_env_overrides_like_rendering() {
+    let envs = [
+        ("CCLINE_STYLE_SEPARATOR", " ~ "),
+        ("CCLINE_SEGMENT_GIT_ENABLED", "false"),
+    ];
+    for args in [
+        &["--print", "--print-format", "json"][..],
+        &["--print", "--print-format", "json", "--theme", "nord"],
+        &[
+            "--dump-effective-config",
+            "--print-format",
+            "json",
+            "--theme",
+            "nord",
+        ],
+    ] {
+        let output = run("print-env", args, &envs, "");
+        let config: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+
+        assert_eq!(config["style"]["separator"], " ~ ", "{:?}", args);
+        let git = config["segments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|segment| segment["id"] == "git")
+            .unwrap();
+        assert_eq!(git["enabled"], false, "{:?}", args);
+    }
+
+    // The rendered line agrees with what --print showed
+    let output = run("render-env", &["--no-color"], &envs, INPUT);
+    let line = stdout(&output);
+    assert!(line.contains(" ~ "), "{:?}", line);
+    assert!(!line.contains("🌿"), "{:?}", line);
+}
+
+#[test]
+fn malformed_input_falls_back_the_same_from_stdin_and_a_file() {
+    // Valid JSON that no longer matches InputData, as after a schema change
+    let malformed = r#"{"workspace": {"current_dir": "/tmp/ccline-project"}, "model": 42}"#;
+
+    let from_stdin = run("malformed-stdin", &["--no-color"], &[], malformed);
+    let path = home("malformed-file").join("input.json");
+    std::fs::write(&path, malformed).unwrap();
+    let from_file = run(
+        "malformed-file-run",
+        &["--no-color", "--input", path.to_str().unwrap()],
+        &[],
+        "",
+    );
+
+    assert_eq!(stdout(&from_stdin), "ccline-project\n");
+    assert_eq!(stdout(&from_file), stdout(&from_stdin));
+}
+
+#[test]
+fn bad_color_in_config_keeps_other_settings() {
+    let path = home("bad-color-config").join("config.json");
+    let rendered = run(
+        "bad-color-default",
+        &["--print", "--print-format", "json"],
+        &[],
+        "",
+    );
+    let mut config: serde_json::Value = serde_json::from_str(&stdout(&rendered)).unwrap();
+    config["style"]["separator"] = " ~~ ".into();
+    config["segments"][0]["colors"]["text"] = "#zzzzzz".into();
+    std::fs::write(&path, config.to_string()).unwrap();
+
+    let path = path.to_str().unwrap();
+    let check = run(
+        "bad-color-check",
+        &["--config-path", path, "--check"],
+        &[],
+        "",
+    );
+    assert!(stdout(&check).contains("Configuration valid"));
+
+    let output = run(
+        "bad-color-render",
+        &["--no-color", "--config-path", path],
+        &[],
+        INPUT,
+    );
+    assert!(stdout(&output).contains(" ~~ "), "{:?}", output);
+}
+
+#[test]
+fn over_budget_cost_has_no_escapes_without_color() {
+    let input = INPUT.replace(
+        r#""transcript_path""#,
+        r#""cost": {"total_cost_usd": 12.5}, "transcript_path""#,
+    );
+    let envs = [
+        ("CCLINE_SEGMENT_COST_ENABLED", "true"),
+        ("CCLINE_SEGMENT_COST_OPTION_BUDGET_LIMIT", "10"),
+        ("CCLINE_SEGMENT_COST_OPTION_OVER_BUDGET_SYMBOL", "!"),
+    ];
+
+    let colored = run("budget-colored", &[], &envs, &input);
+    assert!(stdout(&colored).contains("$12.50 !"));
+
+    let text = run("budget-text", &["--no-color"], &envs, &input);
+    let line = stdout(&text);
+    assert!(line.contains("$12.50 !"), "{:?}", line);
+    assert!(!line.contains('\x1b'), "{:?}", line);
+
+    let json = run(
+        "budget-json",
+        &["--no-color", "--format", "json"],
+        &envs,
+        &input,
+    );
+    let segments: serde_json::Value = serde_json::from_str(&stdout(&json)).unwrap();
+    let cost = segments
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|segment| segment["id"] == "cost")
+        .unwrap();
+    assert_eq!(cost["primary"], "$12.50 !");
+    assert_eq!(cost["metadata"]["over_budget"], "true");
+}